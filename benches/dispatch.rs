@@ -0,0 +1,117 @@
+//! Compile time and run time of generated rust code with many states.
+//!
+//! Run with `cargo bench --bench dispatch`.
+//! Each program counts down a loop whose body has `states` commands with area,
+//! so every command is its own state and dispatching is taken on every command.
+//! The generated code is built with `rustc -O` as a standalone file.
+//!
+//! To compare with another dispatch, set `HYEONG_BASELINE` to the `hyeong` binary of that version.
+//! The same programs are built with `hyeong build --emit rust-standalone` of it,
+//! and the binaries are run in turn so that the load of the machine affects both alike.
+
+use hyeong::core::{compile, optimize, parse};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Hyeong code looping `a * b` times over `states` states
+fn make_code(states: usize, a: usize, b: usize) -> String {
+    let mut res = format!("형{} 형{} 하앗... 항...♥", ".".repeat(a), ".".repeat(b));
+    for i in 0..states {
+        // pushes a number and pops it by comparing in area,
+        // with different numbers so that the states are not merged into the same code
+        res.push_str(&format!(" 형{}?", ".".repeat(i % 64 + 1)));
+    }
+    res.push_str(" 형. 흣.... 하앙... 흑...?♥ 흣.");
+    res
+}
+
+/// Builds `src` with `rustc -O` and returns the compile time
+fn compile(src: &Path, bin: &Path) -> Duration {
+    let t = Instant::now();
+    let status = Command::new("rustc")
+        .args(["-O", "--edition", "2018", "-o"])
+        .arg(bin)
+        .arg(src)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    t.elapsed()
+}
+
+/// Runs the binaries in turn and returns the fastest run of each
+fn run(bins: &[PathBuf]) -> Vec<Duration> {
+    let mut res = vec![Duration::MAX; bins.len()];
+    for _ in 0..5 {
+        for (bin, best) in bins.iter().zip(res.iter_mut()) {
+            let t = Instant::now();
+            let output = Command::new(bin).output().unwrap();
+            *best = (*best).min(t.elapsed());
+            assert_eq!(b"2", &output.stdout[..]);
+        }
+    }
+    res
+}
+
+fn main() {
+    if Command::new("rustc").arg("--version").output().is_err() {
+        println!("rustc is not found");
+        return;
+    }
+    let baseline = env::var_os("HYEONG_BASELINE").map(PathBuf::from);
+    let dir = env::temp_dir().join(format!("hyeong-bench-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    println!(
+        "{:>8} {:>9} {:>12} {:>10} {:>12} {:>12}",
+        "states", "dispatch", "source", "lines", "compile", "run"
+    );
+    for states in [100, 1000, 4000] {
+        let code = make_code(states, 100, 100);
+        let (opt_state, opt_code) = optimize::optimize(parse::parse(code.clone()), 1).unwrap();
+        let mut sources = vec![(
+            "match",
+            compile::build_standalone_source(compile::build_source(opt_state, &opt_code, 1)),
+        )];
+        if let Some(hyeong) = &baseline {
+            let input = dir.join(format!("bench{}.hyeong", states));
+            let output = dir.join(format!("baseline{}.rs", states));
+            fs::write(&input, &code).unwrap();
+            let status = Command::new(hyeong)
+                .args(["build", "-O", "1", "--emit", "rust-standalone", "-o"])
+                .arg(&output)
+                .arg(&input)
+                .stdout(Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success());
+            sources.push(("baseline", fs::read_to_string(&output).unwrap()));
+        }
+
+        let mut bins = Vec::new();
+        let mut compile_times = Vec::new();
+        for (name, source) in &sources {
+            let src = dir.join(format!("{}{}.rs", name, states));
+            let bin = dir.join(format!("{}{}", name, states));
+            fs::write(&src, source).unwrap();
+            compile_times.push(compile(&src, &bin));
+            bins.push(bin);
+        }
+        let run_times = run(&bins);
+
+        for (i, (name, source)) in sources.iter().enumerate() {
+            println!(
+                "{:>8} {:>9} {:>12} {:>10} {:>12.2?} {:>12.2?}",
+                states,
+                name,
+                source.len(),
+                source.lines().count(),
+                compile_times[i],
+                run_times[i]
+            );
+        }
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,58 @@
+//! Time of parsing decimal strings into `BigNum` and printing them back.
+//!
+//! Run with `cargo bench --bench radix`.
+//! Both directions split the number in halves at powers of `10^9`,
+//! parsing multiplies the high half by the power and printing divides by it.
+//! Division takes a multiplication for the quotient and one for the remainder,
+//! and the reciprocal of each power is made by Newton's method,
+//! so printing is expected to take a few times longer than parsing.
+
+use hyeong::number::big_number::BigNum;
+use std::time::{Duration, Instant};
+
+/// Decimal string of `n` digits from a simple linear congruential generator
+fn make_digits(n: usize) -> String {
+    let mut x = 20201019u64;
+    (0..n)
+        .map(|i| {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let d = (x >> 33) % 10;
+            (b'0' + if i == 0 { d % 9 + 1 } else { d } as u8) as char
+        })
+        .collect()
+}
+
+/// Minimum time of running `f` `times` times
+fn measure<T>(times: usize, mut f: impl FnMut() -> T) -> (Duration, T) {
+    let mut best = Duration::MAX;
+    let mut res = None;
+    for _ in 0..times {
+        let start = Instant::now();
+        let v = f();
+        best = best.min(start.elapsed());
+        res = Some(v);
+    }
+    (best, res.unwrap())
+}
+
+fn main() {
+    println!(
+        "{:>9} {:>12} {:>12} {:>7}",
+        "digits", "parse", "print", "ratio"
+    );
+    for (n, times) in [(10_000, 10), (100_000, 3), (1_000_000, 1)] {
+        let s = make_digits(n);
+        let (parse, a) = measure(times, || BigNum::from_string(s.clone()).unwrap());
+        let (print, t) = measure(times, || a.to_string());
+        assert_eq!(s, t);
+        println!(
+            "{:>9} {:>12.2?} {:>12.2?} {:>7.2}",
+            n,
+            parse,
+            print,
+            print.as_secs_f64() / parse.as_secs_f64()
+        );
+    }
+}
@@ -1,11 +1,16 @@
 use crate::app::init;
-use crate::core::state::UnOptState;
-use crate::core::{compile, optimize};
+use crate::core::compile;
+use crate::core::compile::map::{self, SourceMap};
+use crate::core::optimize::Program;
+use crate::core::state::{State, UnOptState};
 use crate::util::error::Error;
-use crate::util::option::HyeongOption;
-use crate::util::{ext, io, option};
+use crate::util::option::{Backend, Emit, HyeongOption, Profile};
+use crate::util::{cache, ext, io, option};
 use clap::App;
-use std::fs;
+use std::collections::BTreeMap;
+use std::env::consts::EXE_SUFFIX;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
 use termcolor::{StandardStream, WriteColor};
 
 /// App for build
@@ -13,85 +18,381 @@ use termcolor::{StandardStream, WriteColor};
 pub fn app<'a>() -> App<'a> {
     App::new("build")
         .about("Compiles hyeong code")
+        .arg(option::backend())
         .arg(option::build_path())
         .arg(option::input())
         .arg(option::optimize())
+        .arg(option::dump_after())
+        .arg(option::emit())
+        .arg(option::emit_only())
+        .arg(option::explain_opt())
+        .arg(option::pre_exec_steps())
         .arg(option::output())
+        .arg(option::profile())
+        .arg(option::opt_level())
+        .arg(option::lto())
+        .arg(option::panic_abort())
+        .arg(option::runtime_flags())
+        .arg(option::rustflags())
+}
+
+/// Makes source code in the language of backend
+fn build_source<T>(hy_opt: &HyeongOption, state: T, code: &[T::CodeType]) -> String
+where
+    T: State,
+{
+    match hy_opt.backend {
+        Backend::Rust => {
+            compile::build_source_with_flags(state, code, hy_opt.optimize, hy_opt.runtime_flags)
+        }
+        Backend::C => compile::c::build_source(state, code, hy_opt.optimize),
+        Backend::Js => compile::js::build_source(state, code, hy_opt.optimize),
+        Backend::Wat => compile::wat::build_source(state, code, hy_opt.optimize),
+    }
+}
+
+/// Profile section of `Cargo.toml` for the build crate
+///
+/// `opt-level` and `lto` of the options take precedence over the ones of the profile.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::app::build;
+/// use hyeong::util::option::{HyeongOption, Profile};
+///
+/// assert_eq!(
+///     "[profile.dev]\nopt-level = 1\npanic = \"abort\"\n",
+///     build::cargo_profile(
+///         &HyeongOption::new()
+///             .profile(Profile::Debug)
+///             .opt_level(Some(String::from("1")))
+///             .panic_abort(true)
+///     )
+/// );
+/// assert_eq!(
+///     "[profile.size]\ninherits = \"release\"\nopt-level = \"z\"\nlto = \"fat\"\ncodegen-units = 1\n",
+///     build::cargo_profile(&HyeongOption::new().profile(Profile::Size))
+/// );
+/// ```
+pub fn cargo_profile(hy_opt: &HyeongOption) -> String {
+    let mut res = format!("[profile.{}]\n", hy_opt.profile.cargo_name());
+    if hy_opt.profile == Profile::Size {
+        res.push_str("inherits = \"release\"\n");
+    }
+    let level = hy_opt
+        .opt_level
+        .as_deref()
+        .unwrap_or_else(|| hy_opt.profile.opt_level());
+    if level.parse::<u8>().is_ok() {
+        res.push_str(&format!("opt-level = {}\n", level));
+    } else {
+        res.push_str(&format!("opt-level = \"{}\"\n", level));
+    }
+    match (&hy_opt.lto, hy_opt.profile) {
+        (Some(lto), _) => res.push_str(&format!("lto = \"{}\"\n", lto)),
+        (None, Profile::Size) => res.push_str("lto = \"fat\"\n"),
+        _ => {}
+    }
+    if hy_opt.profile == Profile::Size {
+        res.push_str("codegen-units = 1\n");
+    }
+    if hy_opt.panic_abort {
+        res.push_str("panic = \"abort\"\n");
+    }
+    res
+}
+
+/// Flags of C compiler from the profile
+///
+/// `z` is taken as `s`, since older compilers don't have `-Oz`.
+fn c_flags(hy_opt: &HyeongOption) -> String {
+    let level = match hy_opt
+        .opt_level
+        .as_deref()
+        .unwrap_or_else(|| hy_opt.profile.opt_level())
+    {
+        "z" => "s",
+        t => t,
+    };
+    let mut res = format!("-O{}", level);
+    if hy_opt.profile == Profile::Debug {
+        res.push_str(" -g");
+    }
+    if matches!(hy_opt.lto.as_deref(), Some("thin") | Some("fat"))
+        || (hy_opt.lto.is_none() && hy_opt.profile == Profile::Size)
+    {
+        res.push_str(" -flto");
+    }
+    res
 }
 
 /// Runner for build
 ///
-/// 1. parse code
-/// 2. optimize code
-/// 3. install if build-dir is not set
-/// 4. compile to binary
+/// 1. parse code, and write it with `--emit ast`
+/// 2. optimize code, and write it with the initial state with `--emit opt`
+/// 3. write the source with `--emit rust` or `--emit rust-standalone`,
+///    and the map from its lines to hyeong commands with `--emit map`
+/// 4. stop unless `--emit bin` is set, which is replaced with the source by `--emit-only` and JS backend
+/// 5. use the binary in the cache of build path if the same source was built before
+/// 6. with C backend, compile to binary with `cc` (or `$CC`) and the flags of profile
+/// 7. otherwise install if build-dir is not set, update the runtime
+///    and compile to binary with cargo in the profile, passing `--rustflags` in `RUSTFLAGS`
+/// 8. store the binary to the cache and copy it to the output
+///
+/// The last stage is written to the output, and the others next to it with their extension.
+/// The cache is keyed by the generated source, which depends on the code and optimization level,
+/// the profile and flags, and the version of this compiler with its runtime.
+/// Each build is done in its own directory, so builds can run at the same time.
+/// Rust code has the panic hook that reports the location in hyeong code.
 #[cfg(not(tarpaulin_include))]
 pub fn run(stdout: &mut StandardStream, hy_opt: &HyeongOption) -> Result<(), Error> {
+    let kinds = option::emit_kinds(&hy_opt.emit, hy_opt.emit_only, hy_opt.backend);
+    if kinds.contains(&Emit::Map) && hy_opt.backend != Backend::Rust {
+        return Err(Error::new(
+            "map is only for rust backend",
+            "remove `--backend` option",
+        ));
+    }
+    if hy_opt.runtime_flags && hy_opt.backend != Backend::Rust {
+        return Err(Error::new(
+            "runtime flags are only for rust backend",
+            "remove `--backend` option",
+        ));
+    }
+    if kinds.contains(&Emit::RustStandalone) {
+        if hy_opt.backend != Backend::Rust {
+            return Err(Error::new(
+                "rust-standalone is only for rust backend",
+                "remove `--backend` option",
+            ));
+        }
+        if kinds.contains(&Emit::Rust) {
+            return Err(Error::new(
+                "rust and rust-standalone are written to the same file",
+                "emit only one of them",
+            ));
+        }
+    }
+    let last = *kinds.last().unwrap();
+    let output = hy_opt.output.as_ref().unwrap();
+    let path = |e: Emit| {
+        if e == last {
+            output.clone()
+        } else {
+            output.with_extension(e.extension(hy_opt.backend))
+        }
+    };
+
     // parse
     let un_opt_code = ext::parse_file(stdout, hy_opt.input.as_ref().unwrap(), hy_opt)?;
+    if kinds.contains(&Emit::Ast) {
+        io::save_to_file(&path(Emit::Ast), ext::ast_to_string(&un_opt_code))?;
+    }
+    if last == Emit::Ast {
+        return Ok(());
+    }
 
     // optimize
-    let rust_code = if hy_opt.optimize >= 1 {
-        io::print_log(stdout, format!("optimizing to level {}", hy_opt.optimize))?;
-        let (state, code) = optimize::optimize(un_opt_code, hy_opt.optimize)?;
-        io::print_log(stdout, "compiling to rust")?;
-        compile::build_source(state, &code, hy_opt.optimize)
+    let source = if hy_opt.optimize >= 1 {
+        let (state, code) = ext::optimize_code(stdout, un_opt_code.clone(), hy_opt)?;
+        let program = Program {
+            state,
+            code,
+            stack_map: BTreeMap::new(),
+        };
+        if kinds.contains(&Emit::Opt) {
+            io::save_to_file(&path(Emit::Opt), program.to_string())?;
+        }
+        if last == Emit::Opt {
+            return Ok(());
+        }
+        io::print_log(stdout, "compiling to source")?;
+        build_source(hy_opt, program.state, &program.code)
     } else {
+        if kinds.contains(&Emit::Opt) {
+            io::save_to_file(
+                &path(Emit::Opt),
+                Program::new(un_opt_code.clone()).to_string(),
+            )?;
+        }
+        if last == Emit::Opt {
+            return Ok(());
+        }
         let state = UnOptState::new();
-        io::print_log(stdout, "compiling to rust")?;
-        compile::build_source(state, &un_opt_code, hy_opt.optimize)
+        io::print_log(stdout, "compiling to source")?;
+        build_source(hy_opt, state, &un_opt_code)
     };
 
+    // map lines to hyeong commands
+    let source = if hy_opt.backend == Backend::Rust {
+        let map = SourceMap::new(&source, &un_opt_code);
+        if kinds.contains(&Emit::Map) {
+            io::save_to_file(&path(Emit::Map), map.to_string())?;
+        }
+        let file = hy_opt.input.as_ref().unwrap().file_name().unwrap();
+        map::add_panic_hook(source, &map, &file.to_string_lossy())
+    } else {
+        source
+    };
+
+    // emit source
+    if kinds.contains(&Emit::Rust) {
+        io::save_to_file(&path(Emit::Rust), source.clone())?;
+    }
+    if kinds.contains(&Emit::RustStandalone) {
+        io::print_log(stdout, "inlining runtime")?;
+        io::save_to_file(
+            &path(Emit::RustStandalone),
+            compile::build_standalone_source(source.clone()),
+        )?;
+    }
+    if last != Emit::Bin {
+        return Ok(());
+    }
+
+    // look up the cache
+    let build_path = hy_opt.build_path.as_ref().unwrap();
+    let (flags, compiler) = if hy_opt.backend == Backend::C {
+        (c_flags(hy_opt), c_compiler())
+    } else {
+        (cargo_profile(hy_opt), rustflags(hy_opt))
+    };
+    let mut parts = vec![
+        hy_opt.backend.extension(),
+        source.as_str(),
+        flags.as_str(),
+        compiler.as_str(),
+    ];
+    if hy_opt.backend == Backend::Rust {
+        parts.extend(init::RUNTIME.iter().map(|(_, content)| *content));
+    }
+    let key = cache::key(&parts);
+    let mut binary = cache::binary(build_path, &key);
+    if binary.exists() {
+        io::print_log(stdout, "using cached binary")?;
+    } else {
+        let work = cache::workspace(build_path, &key);
+        fs::create_dir_all(&work)?;
+        let built = if hy_opt.backend == Backend::C {
+            compile_c(stdout, &work, &flags, source)
+        } else {
+            compile_rust(stdout, hy_opt, &work, &key, source)
+        };
+        let res = built.and_then(|b| cache::store(&b, build_path, &key));
+        fs::remove_dir_all(&work)?;
+        binary = res?;
+    }
+
+    // move
+    io::print_log(stdout, "moving binary to output")?;
+    fs::copy(binary, output)?;
+
+    Ok(())
+}
+
+/// Compiles C source in `work` with `cc` (or `$CC`), returning the path of the binary
+#[cfg(not(tarpaulin_include))]
+fn compile_c(
+    stdout: &mut StandardStream,
+    work: &Path,
+    flags: &str,
+    source: String,
+) -> Result<PathBuf, Error> {
+    let bin = work.join(format!("main{}", EXE_SUFFIX));
+    io::save_to_file(&work.join("main.c"), source)?;
+    io::print_log(stdout, "compiling c code")?;
+    ext::execute_command_stderr(
+        stdout,
+        &format!(
+            "{} -std=c99 {} -o {} {}",
+            c_compiler(),
+            flags,
+            ext::path_to_string(&bin)?,
+            ext::path_to_string(&work.join("main.c"))?
+        ),
+    )?;
+    Ok(bin)
+}
+
+/// Compiles rust source in `work` with cargo, returning the path of the binary
+///
+/// Each build has its own crate named after `key`,
+/// and they share the target directory of the installed crate so that the runtime is built once.
+/// Cargo locks the target directory, so concurrent builds wait for each other there.
+#[cfg(not(tarpaulin_include))]
+fn compile_rust(
+    stdout: &mut StandardStream,
+    hy_opt: &HyeongOption,
+    work: &Path,
+    key: &str,
+    source: String,
+) -> Result<PathBuf, Error> {
+    let build_path = hy_opt.build_path.as_ref().unwrap();
+
     // install
-    if !hy_opt
-        .build_path
-        .as_ref()
-        .unwrap()
-        .join("hyeong-build/Cargo.toml")
-        .exists()
-    {
+    if !build_path.join("hyeong-build/Cargo.toml").exists() {
         init::install_run(stdout, hy_opt)?;
+    } else {
+        init::update_runtime(build_path)?;
     }
 
     // compile to binary
+    let name = format!("hyeong-build-{}", key);
     io::save_to_file(
-        &hy_opt
-            .build_path
-            .as_ref()
-            .unwrap()
-            .join("hyeong-build/src/main.rs"),
-        rust_code,
+        &work.join("Cargo.toml"),
+        format!(
+            "{}\n{}",
+            init::build_manifest(&name, "../../hyeong-runtime"),
+            cargo_profile(hy_opt)
+        ),
     )?;
+    fs::create_dir_all(work.join("src"))?;
+    io::save_to_file(&work.join("src/main.rs"), source)?;
     io::print_log(stdout, "compiling rust code")?;
-    ext::execute_command_stderr(
+    let target = build_path.join("hyeong-build/target");
+    let env = if hy_opt.rustflags.is_some() {
+        vec![("RUSTFLAGS", rustflags(hy_opt))]
+    } else {
+        Vec::new()
+    };
+    ext::execute_command_stderr_env(
         stdout,
-        &*format!(
-            "cargo build --manifest-path={} --release --color {}",
-            ext::path_to_string(
-                &hy_opt
-                    .build_path
-                    .as_ref()
-                    .unwrap()
-                    .join("hyeong-build/Cargo.toml")
-            )?,
+        &format!(
+            "cargo build --manifest-path={} --target-dir={} --profile {} --color {}",
+            ext::path_to_string(&work.join("Cargo.toml"))?,
+            ext::path_to_string(&target)?,
+            hy_opt.profile.cargo_name(),
             if stdout.supports_color() {
                 "always"
             } else {
                 "never"
             }
         ),
+        &env,
     )?;
+    Ok(target.join(format!(
+        "{}/{}{}",
+        hy_opt.profile.target_dir(),
+        name,
+        EXE_SUFFIX
+    )))
+}
 
-    // move
-    io::print_log(stdout, "moving binary to current directory")?;
-    fs::copy(
-        hy_opt.build_path.as_ref().unwrap().join(if cfg!(windows) {
-            "hyeong-build/target/release/hyeong-build.exe"
-        } else {
-            "hyeong-build/target/release/hyeong-build"
-        }),
-        hy_opt.output.as_ref().unwrap(),
-    )?;
+/// `RUSTFLAGS` of the environment followed by `--rustflags`
+///
+/// Cargo reads `RUSTFLAGS` of the environment even without `--rustflags`,
+/// so it is a part of the cache key either way.
+fn rustflags(hy_opt: &HyeongOption) -> String {
+    let env = env::var("RUSTFLAGS").unwrap_or_default();
+    match &hy_opt.rustflags {
+        Some(flags) if !env.trim().is_empty() => format!("{} {}", env, flags),
+        Some(flags) => flags.clone(),
+        None => env,
+    }
+}
 
-    Ok(())
+/// C compiler, which is `$CC` if it is set
+fn c_compiler() -> String {
+    env::var("CC").unwrap_or_else(|_| String::from("cc"))
 }
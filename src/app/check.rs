@@ -1,5 +1,6 @@
 use crate::core::code::{Code, UnOptCode};
 use crate::core::parse;
+use crate::number::num::Num;
 use crate::util::error::Error;
 use crate::util::option::HyeongOption;
 use crate::util::{ext, option};
@@ -14,6 +15,8 @@ pub fn app<'a>() -> App<'a> {
     App::new("check")
         .about("Parse your code and check if you are right")
         .arg(option::input())
+        .arg(option::num_format())
+        .arg(option::precision())
 }
 
 /// Runner for check
@@ -30,6 +33,8 @@ pub fn run(stdout: &mut StandardStream, hy_opt: &HyeongOption) -> Result<(), Err
 }
 
 /// Main print function for `UnOptCode`
+///
+/// When number format is set, the value pushed by each `형` command is printed in that format.
 #[cfg(not(tarpaulin_include))]
 pub fn print_un_opt_codes(
     stdout: &mut StandardStream,
@@ -92,7 +97,7 @@ pub fn print_un_opt_codes(
         if raw {
             writeln!(stdout, "{}", c.get_raw())?;
         } else {
-            writeln!(
+            write!(
                 stdout,
                 "{}_{}_{} {}",
                 parse::COMMANDS[c.get_type() as usize],
@@ -100,6 +105,13 @@ pub fn print_un_opt_codes(
                 c.get_dot_count(),
                 c.get_area()
             )?;
+            match hy_opt.num_format {
+                Some(format) if c.get_type() == 0 => {
+                    let n = &Num::from(c.get_hangul_count()) * &Num::from(c.get_dot_count());
+                    writeln!(stdout, " -> {}", n.to_string_format(format))?;
+                }
+                _ => writeln!(stdout)?,
+            }
         }
     }
 
@@ -1,6 +1,7 @@
 use crate::app::check;
 use crate::core::execute;
 use crate::core::state::{State, UnOptState};
+use crate::number::num::Format;
 use crate::util::error::Error;
 use crate::util::option::HyeongOption;
 use crate::util::{ext, io, option};
@@ -18,6 +19,8 @@ pub fn app<'a>() -> App<'a> {
     App::new("debug")
         .about("Debug your code command by command")
         .arg(option::input())
+        .arg(option::num_format())
+        .arg(option::precision())
 }
 
 /// Debug function
@@ -29,8 +32,10 @@ pub fn app<'a>() -> App<'a> {
 /// 4. [h] help        Print this
 /// 5. [n] next        goto next command
 /// 6. [s] state       print state status
-/// 7. [p] previous    move to previous state
-/// 8. [r] run         run until breakpoint
+/// 7. [s] state FORMAT [DIGITS]
+///    print state status with number format (fraction, mixed, decimal, repeating, scientific)
+/// 8. [p] previous    move to previous state
+/// 9. [r] run         run until breakpoint
 #[cfg(not(tarpaulin_include))]
 pub fn run(stdout: &mut StandardStream, hy_opt: &HyeongOption) -> Result<(), Error> {
     let running = Arc::new(AtomicBool::new(true));
@@ -178,7 +183,36 @@ pub fn run(stdout: &mut StandardStream, hy_opt: &HyeongOption) -> Result<(), Err
                     }
 
                     "state" | "s" => {
-                        write!(stdout, "{:?}", state_stack.last().unwrap().0)?;
+                        let precision = match parsed.get(2).map(|x| x.parse::<usize>()) {
+                            Some(Ok(t)) => t,
+                            Some(Err(e)) => {
+                                io::print_error_no_exit(stdout, Error::from(e, ""));
+                                continue;
+                            }
+                            None => hy_opt.precision,
+                        };
+                        let format = match parsed.get(1) {
+                            Some(t) => match option::num_format_from_name(t, precision) {
+                                Some(t) => t,
+                                None => {
+                                    io::print_error_str_no_exit(
+                                        stdout,
+                                        format!(
+                                            "format \"{}\" not found, use one of {}",
+                                            t,
+                                            option::NUM_FORMATS.join(", ")
+                                        ),
+                                    );
+                                    continue;
+                                }
+                            },
+                            None => hy_opt.num_format.unwrap_or(Format::Fraction),
+                        };
+                        write!(
+                            stdout,
+                            "{}",
+                            state_stack.last().unwrap().0.to_string_format(format)
+                        )?;
                     }
 
                     "break" | "b" => {
@@ -225,6 +259,11 @@ pub fn run(stdout: &mut StandardStream, hy_opt: &HyeongOption) -> Result<(), Err
                         writeln!(stdout, "[h] help        Print this")?;
                         writeln!(stdout, "[n] next        goto next command")?;
                         writeln!(stdout, "[s] state       print state status")?;
+                        writeln!(stdout, "[s] state FORMAT [DIGITS]")?;
+                        writeln!(
+                            stdout,
+                            "                print state status with number format"
+                        )?;
                         writeln!(stdout, "[p] previous    move to previous state")?;
                         writeln!(stdout, "[r] run         run until breakpoint")?;
                         continue;
@@ -3,8 +3,89 @@ use crate::util::option::HyeongOption;
 use crate::util::{error, ext, io, option};
 use clap::App;
 use std::fs;
+use std::path::Path;
+use std::process;
 use termcolor::{StandardStream, WriteColor};
 
+/// `Cargo.toml` of the crate named `name` that compiled code is built in,
+/// where `runtime` is the relative path to the runtime crate
+pub(crate) fn build_manifest(name: &str, runtime: &str) -> String {
+    format!(
+        "\
+[package]
+name = \"{}\"
+version = \"0.1.0\"
+edition = \"2018\"
+
+[dependencies]
+hyeong = {{ path = \"{}\" }}
+",
+        name, runtime
+    )
+}
+
+/// Files of the runtime crate that compiled code depends on
+///
+/// It is the `number` module of this compiler, so built code always matches the compiler
+/// and no network is needed to build.
+pub(crate) const RUNTIME: [(&str, &str); 6] = [
+    (
+        "Cargo.toml",
+        concat!(
+            "[package]\n",
+            "name = \"hyeong\"\n",
+            "version = \"",
+            env!("CARGO_PKG_VERSION"),
+            "\"\n",
+            "edition = \"2018\"\n",
+            "\n",
+            "[features]\n",
+            "std = []\n",
+        ),
+    ),
+    (
+        "src/lib.rs",
+        "#![no_std]\n\nextern crate alloc;\n\npub mod number;\n",
+    ),
+    ("src/number/mod.rs", include_str!("../number/mod.rs")),
+    ("src/number/affine.rs", include_str!("../number/affine.rs")),
+    (
+        "src/number/big_number.rs",
+        include_str!("../number/big_number.rs"),
+    ),
+    ("src/number/num.rs", include_str!("../number/num.rs")),
+];
+
+/// Write `content` to `path` if it is different, so that cargo does not build it again
+///
+/// The file is written next to `path` and renamed, so that concurrent builds never read it partly written.
+fn update_file(path: &Path, content: &str) -> Result<(), Error> {
+    if fs::read_to_string(path).ok().as_deref() != Some(content) {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension(format!("{}.tmp", process::id()));
+        io::save_to_file(&temp, String::from(content))?;
+        fs::rename(&temp, path)?;
+    }
+    Ok(())
+}
+
+/// Write the runtime crate and the manifest of build crate to `build_path`
+///
+/// It is done before every build, so the runtime is updated with the compiler.
+/// Files that are already up to date are not touched.
+pub fn update_runtime(build_path: &Path) -> Result<(), Error> {
+    update_file(
+        &build_path.join("hyeong-build/Cargo.toml"),
+        &build_manifest("hyeong-build", "../hyeong-runtime"),
+    )?;
+    for (file, content) in RUNTIME.iter() {
+        update_file(&build_path.join("hyeong-runtime").join(file), content)?;
+    }
+    Ok(())
+}
+
 /// App for install
 #[cfg(not(tarpaulin_include))]
 pub fn install_app<'a>() -> App<'a> {
@@ -25,8 +106,8 @@ pub fn uninstall_app<'a>() -> App<'a> {
 ///
 /// 1. if dir is not empty -> Error
 /// 2. create dir
-/// 3. create Cargo.toml
-/// 4. create main.rs
+/// 3. create main.rs
+/// 4. create Cargo.toml and runtime crate
 /// 5. pre-compile
 #[cfg(not(tarpaulin_include))]
 pub fn install_run(stdout: &mut StandardStream, hy_opt: &HyeongOption) -> Result<(), Error> {
@@ -46,7 +127,7 @@ pub fn install_run(stdout: &mut StandardStream, hy_opt: &HyeongOption) -> Result
         ));
     }
     io::print_log(stdout, "making dir for building hyeong")?;
-    fs::create_dir_all(&hy_opt.build_path.as_ref().unwrap().join("hyeong-build/src"))?;
+    fs::create_dir_all(hy_opt.build_path.as_ref().unwrap().join("hyeong-build/src"))?;
     io::save_to_file(
         &hy_opt
             .build_path
@@ -64,28 +145,11 @@ fn main() {
 ",
         ),
     )?;
-    io::save_to_file(
-        &hy_opt
-            .build_path
-            .as_ref()
-            .unwrap()
-            .join("hyeong-build/Cargo.toml"),
-        String::from(
-            "\
-[package]
-name = \"hyeong-build\"
-version = \"0.1.0\"
-edition = \"2018\"
-
-[dependencies]
-hyeong = { git = \"https://github.com/buttercrab/hyeo-ung-lang\", features = [\"number\"], default-features = false }
-",
-        ),
-    )?;
+    update_runtime(hy_opt.build_path.as_ref().unwrap())?;
     io::print_log(stdout, "test pre-build")?;
     ext::execute_command_stderr(
         stdout,
-        &*format!(
+        &format!(
             "cargo build --manifest-path={} --release --color {}",
             ext::path_to_string(
                 &hy_opt
@@ -4,3 +4,4 @@ pub mod debug;
 pub mod init;
 pub mod interpreter;
 pub mod run;
+pub mod verify;
@@ -1,5 +1,5 @@
-use crate::core::state::{State, UnOptState};
-use crate::core::{execute, optimize};
+use crate::core::execute;
+use crate::core::state::{Output, State, UnOptState};
 use crate::util::error::Error;
 use crate::util::option::HyeongOption;
 use crate::util::{ext, io, option};
@@ -14,6 +14,9 @@ pub fn app<'a>() -> App<'a> {
         .about("Run hyeong code directly")
         .arg(option::input())
         .arg(option::optimize())
+        .arg(option::dump_after())
+        .arg(option::explain_opt())
+        .arg(option::pre_exec_steps())
 }
 
 /// App for run
@@ -30,25 +33,22 @@ pub fn run(
     let un_opt_code = ext::parse_file(stdout, hy_opt.input.as_ref().unwrap(), hy_opt)?;
 
     if hy_opt.optimize >= 1 {
-        io::print_log(stdout, format!("optimizing to level {}", hy_opt.optimize))?;
-        let (mut state, opt_code) = optimize::optimize(un_opt_code, hy_opt.optimize)?;
+        let (mut state, opt_code) = ext::optimize_code(stdout, un_opt_code, hy_opt)?;
         io::print_log(stdout, "running code")?;
 
-        if !state.get_stack(1).is_empty() {
-            for num in state.get_stack(1).iter() {
-                write!(stdout, "{}", ext::num_to_unicode(num)?)?;
+        for output in state.get_output() {
+            match output {
+                Output::Stdout(s) => {
+                    write!(stdout, "{}", s)?;
+                    stdout.flush()?;
+                }
+                Output::Stderr(s) => {
+                    write!(stderr, "{}", s)?;
+                    stderr.flush()?;
+                }
             }
-            stdout.flush()?;
-            state.get_stack(1).clear();
-        }
-
-        if !state.get_stack(2).is_empty() {
-            for num in state.get_stack(2).iter() {
-                write!(stderr, "{}", ext::num_to_unicode(num)?)?;
-            }
-            stdout.flush()?;
-            state.get_stack(2).clear();
         }
+        state.clear_output();
 
         for c in opt_code {
             state = execute::execute(&mut std::io::stdin(), stdout, stderr, state, &c)?;
@@ -0,0 +1,69 @@
+use crate::core::optimize::{Config, PassManager};
+use crate::core::verify::{self, Verdict};
+use crate::util::error::Error;
+use crate::util::option::HyeongOption;
+use crate::util::{ext, io, option};
+use clap::App;
+use std::fs;
+use std::io::Write;
+use termcolor::StandardStream;
+
+/// App for verify
+#[cfg(not(tarpaulin_include))]
+pub fn app<'a>() -> App<'a> {
+    App::new("verify")
+        .about("Check that optimized code runs the same as the source")
+        .arg(option::input())
+        .arg(option::stdin())
+        .arg(option::pre_exec_steps())
+}
+
+/// Runner for verify
+///
+/// 1. parse code
+/// 2. for each optimization level, run it along with level 0 and compare
+/// 3. print the first divergence
+///
+/// Level 3 is also verified without pre-execution,
+/// since pre-executed commands are not run again by the optimized code.
+#[cfg(not(tarpaulin_include))]
+pub fn run(stdout: &mut StandardStream, hy_opt: &HyeongOption) -> Result<(), Error> {
+    let code = ext::parse_file(stdout, hy_opt.input.as_ref().unwrap(), hy_opt)?;
+    let input = match &hy_opt.stdin {
+        Some(path) => fs::read_to_string(path)?,
+        None => String::new(),
+    };
+
+    let mut diverged = Vec::new();
+    let mut configs = (1..=3)
+        .map(|level| (level, hy_opt.pre_exec_steps, level.to_string()))
+        .collect::<Vec<_>>();
+    if hy_opt.pre_exec_steps != 0 {
+        configs.push((3, 0, String::from("3 without pre-execution")));
+    }
+    for (level, steps, name) in configs {
+        io::print_log(stdout, format!("verifying level {}", name))?;
+        let pm = PassManager::from_config(&Config::new(level).pre_exec_steps(steps));
+        match verify::verify(&code, &pm, &input, verify::DEFAULT_STEPS)? {
+            Verdict::Same(end) => io::print_log(stdout, format!("⮑  same result, {}", end))?,
+            Verdict::Diverged(d) => {
+                io::print_log(stdout, "⮑  diverged")?;
+                write!(stdout, "{}", d)?;
+                diverged.push(name);
+            }
+            Verdict::Unknown => io::print_log(
+                stdout,
+                format!("⮑  not finished in {} steps", verify::DEFAULT_STEPS),
+            )?,
+        }
+    }
+
+    if diverged.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::new(
+            format!("optimized code diverged at level {}", diverged.join(", ")),
+            "run with --explain-opt to see what the optimizer did",
+        ))
+    }
+}
@@ -1,7 +1,18 @@
 use crate::core::area;
 use crate::core::area::Area;
+use crate::number::affine::AffineLoop;
+use crate::number::num::Num;
 use std::fmt;
 
+/// Type of `OptCode` that pushes a constant, made by [OptCode::push_const](struct.OptCode.html#method.push_const)
+pub const PUSH_CONST: u8 = 6;
+
+/// Type of `OptCode` that changes current stack, made by [OptCode::move_to](struct.OptCode.html#method.move_to)
+pub const MOVE_TO: u8 = 7;
+
+/// Type of `OptCode` that runs a loop in closed form, made by [OptCode::accelerate](struct.OptCode.html#method.accelerate)
+pub const ACCELERATE: u8 = 8;
+
 /// Code trait
 ///
 /// It defines methods that code structure needs.
@@ -16,12 +27,21 @@ pub trait Code {
     fn get_area(&self) -> &Area;
 
     fn get_area_count(&self) -> usize;
+
+    fn get_value(&self) -> Option<&Num>;
+
+    fn get_loop(&self) -> Option<&Accel>;
+
+    fn get_origin(&self) -> Option<usize>;
 }
 
 /// structure for optimized code
 ///
 /// It contains a single command.
 /// It can be used for level 1, 2 optimization.
+/// Besides the six commands, level 3 optimization makes more kinds of command;
+/// [PUSH_CONST](constant.PUSH_CONST.html), [MOVE_TO](constant.MOVE_TO.html)
+/// and [ACCELERATE](constant.ACCELERATE.html).
 ///
 /// # Examples
 ///
@@ -49,6 +69,25 @@ pub struct OptCode {
     dot_count: usize,
     area_count: usize,
     area: Area,
+    value: Option<Num>,
+    accel: Option<Box<Accel>>,
+    origin: Option<usize>,
+}
+
+/// Loop run in closed form by [ACCELERATE](constant.ACCELERATE.html) command
+///
+/// The loop starts at the point of heart `id`,
+/// which should be `back` commands before the `ACCELERATE` command.
+#[derive(Clone, Debug)]
+pub struct Accel {
+    /// id of the heart that the loop jumps back to
+    pub id: u128,
+    /// distance from the start of the loop
+    pub back: usize,
+    /// updates in the loop
+    pub body: AffineLoop,
+    /// current stack after the loop, which the `back` command may move
+    pub cur: usize,
 }
 
 impl OptCode {
@@ -86,8 +125,108 @@ impl OptCode {
             dot_count,
             area_count,
             area,
+            value: None,
+            accel: None,
+            origin: None,
+        }
+    }
+
+    /// Makes new `OptCode` that pushes `value` onto `stack`
+    ///
+    /// It does not pop anything and does not change current stack.
+    /// `stack` is saved as dot count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::core::code::{self, OptCode, Code};
+    /// use hyeong::number::num::Num;
+    ///
+    /// let a = OptCode::push_const(4, Num::from_num(10));
+    ///
+    /// assert_eq!(code::PUSH_CONST, a.get_type());
+    /// assert_eq!(4, a.get_dot_count());
+    /// assert_eq!("10", a.get_value().unwrap().to_string());
+    /// ```
+    pub fn push_const(stack: usize, value: Num) -> OptCode {
+        OptCode {
+            type_: PUSH_CONST,
+            hangul_count: 0,
+            dot_count: stack,
+            area_count: 0,
+            area: Area::Nil,
+            value: Some(value),
+            accel: None,
+            origin: None,
+        }
+    }
+
+    /// Makes new `OptCode` that changes current stack to `stack`
+    ///
+    /// Unlike `흑`, it does not pop or push anything.
+    /// `stack` is saved as dot count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::core::code::{self, OptCode, Code};
+    ///
+    /// let a = OptCode::move_to(4);
+    ///
+    /// assert_eq!(code::MOVE_TO, a.get_type());
+    /// assert_eq!(4, a.get_dot_count());
+    /// ```
+    pub fn move_to(stack: usize) -> OptCode {
+        OptCode {
+            type_: MOVE_TO,
+            hangul_count: 0,
+            dot_count: stack,
+            area_count: 0,
+            area: Area::Nil,
+            value: None,
+            accel: None,
+            origin: None,
+        }
+    }
+
+    /// Makes new `OptCode` that runs the loop after it in closed form
+    ///
+    /// The command is placed right before the command that jumps back,
+    /// and the loop runs only if current stack is `cur`
+    /// and the point of the heart is at the start of the loop.
+    /// On success, it moves on to the command after the jump.
+    /// Otherwise, it does nothing and the loop runs as usual.
+    /// `cur` is saved as dot count.
+    pub fn accelerate(cur: usize, l: Accel) -> OptCode {
+        OptCode {
+            type_: ACCELERATE,
+            hangul_count: 0,
+            dot_count: cur,
+            area_count: 0,
+            area: Area::Nil,
+            value: None,
+            accel: Some(Box::new(l)),
+            origin: None,
         }
     }
+
+    /// Sets the index of the parsed command that this command comes from
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::core::code::{OptCode, Code};
+    ///
+    /// let a = OptCode::move_to(4).origin(Some(7));
+    ///
+    /// assert_eq!(Some(7), a.get_origin());
+    /// assert_eq!(None, OptCode::move_to(4).get_origin());
+    /// ```
+    #[must_use]
+    pub fn origin(mut self, origin: Option<usize>) -> OptCode {
+        self.origin = origin;
+        self
+    }
 }
 
 impl Code for OptCode {
@@ -115,6 +254,21 @@ impl Code for OptCode {
     fn get_area_count(&self) -> usize {
         self.area_count
     }
+
+    /// Return constant value of code, only for `PUSH_CONST`
+    fn get_value(&self) -> Option<&Num> {
+        self.value.as_ref()
+    }
+
+    /// Return the loop to run, only for `ACCELERATE`
+    fn get_loop(&self) -> Option<&Accel> {
+        self.accel.as_deref()
+    }
+
+    /// Return index of the parsed command that it comes from, if it is known
+    fn get_origin(&self) -> Option<usize> {
+        self.origin
+    }
 }
 
 /// structure for optimized code
@@ -223,4 +377,19 @@ impl Code for UnOptCode {
     fn get_area_count(&self) -> usize {
         self.hangul_count * self.dot_count
     }
+
+    /// Return `None` since constant code only appears after optimization
+    fn get_value(&self) -> Option<&Num> {
+        None
+    }
+
+    /// Return `None` since loops are only accelerated after optimization
+    fn get_loop(&self) -> Option<&Accel> {
+        None
+    }
+
+    /// Return `None` since it is the parsed command itself, whose index is its position
+    fn get_origin(&self) -> Option<usize> {
+        None
+    }
 }
@@ -0,0 +1,241 @@
+use crate::core::area::Area;
+use crate::core::code::{self, Code};
+use crate::core::compile::{make_indent, Layout};
+use crate::core::state::{Output, State};
+use crate::number::num::Num;
+
+/// Runtime of generated code: big integer, rational number, stacks and point table
+const RUNTIME: &str = include_str!("runtime.c");
+
+/// Makes C string literal, escaping every byte that is not printable ASCII
+fn string_literal(s: &str) -> String {
+    let mut res = String::from("\"");
+    for b in s.bytes() {
+        if (0x20..0x7f).contains(&b) && b != b'"' && b != b'\\' && b != b'?' {
+            res.push(b as char);
+        } else {
+            res.push_str(&format!("\\{:03o}", b));
+        }
+    }
+    res.push('"');
+    res
+}
+
+/// Makes arguments of `hy_restore`: numerator and denominator of `Num` in decimal
+fn num_parts(n: &Num) -> String {
+    if n.is_nan() {
+        return String::from("\"1\", \"0\"");
+    }
+    let s = n.to_string();
+    match s.split_once('/') {
+        Some((up, down)) => format!("{:?}, {:?}", up, down),
+        None => format!("{:?}, \"1\"", s),
+    }
+}
+
+/// Makes print function from string to print
+fn fn_write(indent: usize, s: &str, stream: &str) -> String {
+    format!(
+        "\n{}fwrite({}, 1, {}, {});",
+        make_indent(indent),
+        string_literal(s),
+        s.len(),
+        stream
+    )
+}
+
+/// Makes C statements from command, which call `hy_` functions of the runtime.
+fn command(indent: usize, c: &impl Code) -> String {
+    let h = c.get_hangul_count();
+    let d = c.get_dot_count();
+    format!(
+        "{}{}",
+        match c.get_type() {
+            0 => format!("\n{}hy_push_int(cur, {}u);", make_indent(indent), h * d),
+            1 => format!("\n{}hy_sum(cur, {}, {});", make_indent(indent), h, d),
+            2 => format!("\n{}hy_product(cur, {}, {});", make_indent(indent), h, d),
+            3 => format!("\n{}hy_neg_sum(cur, {}, {});", make_indent(indent), h, d),
+            4 => format!(
+                "\n{}hy_flip_product(cur, {}, {});",
+                make_indent(indent),
+                h,
+                d
+            ),
+            5 => format!("\n{}cur = hy_dup(cur, {}, {});", make_indent(indent), h, d),
+            code::PUSH_CONST => format!(
+                "\n{}hy_push({}, num_const({}));",
+                make_indent(indent),
+                d,
+                num_parts(c.get_value().unwrap())
+            ),
+            code::ACCELERATE => String::new(),
+            // MOVE_TO
+            _ => format!("\n{}cur = {};", make_indent(indent), d),
+        },
+        area(indent, c.get_area(), c.get_area_count())
+    )
+}
+
+/// Makes code from area, branching on `hy_cmp_pop` with `if`.
+/// Hearts look up the point table with `hy_point`.
+fn area(mut indent: usize, a: &Area, cnt: usize) -> String {
+    let mut st = vec![(a, &Area::Nil, false)];
+    let mut res = String::new();
+    loop {
+        while let Area::Val { type_, left, right } = st.last().unwrap().0 {
+            if *type_ <= 1 {
+                st.push((left, right, false));
+                res.push_str(&format!(
+                    "\n{}if (hy_cmp_pop(cur, {}u) {} 0) {{",
+                    make_indent(indent),
+                    cnt,
+                    if *type_ == 0 { "<" } else { "==" }
+                ));
+                indent += 1;
+                continue;
+            } else {
+                if *type_ < 13 {
+                    res.push_str(&format!(
+                        "\n{0}{{\
+                         \n{0}    size_t v = hy_point({1}u, state);\
+                         \n{0}    if (v != state) {{\
+                         \n{0}        last = state;\
+                         \n{0}        state = v;\
+                         \n{0}        continue;\
+                         \n{0}    }}\
+                         \n{0}}}",
+                        make_indent(indent),
+                        ((cnt as u64) << 4) + *type_ as u64
+                    ));
+                } else {
+                    res.push_str(&format!(
+                        "\n{0}if (last != HY_NONE) {{\
+                         \n{0}    state = last;\
+                         \n{0}    continue;\
+                         \n{0}}}",
+                        make_indent(indent)
+                    ));
+                }
+                break;
+            }
+        }
+
+        while st.len() > 1 && st.last().unwrap().2 {
+            st.pop();
+            indent -= 1;
+            res.push_str(&format!("\n{}}}", make_indent(indent)));
+        }
+
+        if st.len() > 1 {
+            let (left, right, _) = st.pop().unwrap();
+            st.push((right, left, true));
+            res.push_str(&format!("\n{}}} else {{", make_indent(indent - 1)));
+        } else {
+            break res;
+        }
+    }
+}
+
+/// Makes C99 code from parsed hyeong code.
+/// States are dispatched with binary if-else statement on the state,
+/// and numbers are calculated with the runtime written in the same file.
+/// It is built with only `cc main.c`.
+pub fn build_source<T>(state: T, code: &[T::CodeType], level: u8) -> String
+where
+    T: State,
+{
+    let layout = Layout::new(state, code, level);
+    let mut res = format!(
+        "#define HY_STACK_SIZE {}\n\n{}\nint main(void) {{\
+         \n    size_t state = 0;\
+         \n    size_t last = HY_NONE;\
+         \n    size_t cur = 3;\
+         \n    (void)state;\
+         \n    (void)last;\
+         \n    (void)cur;",
+        if level != 0 { layout.stack_size } else { 0 },
+        RUNTIME
+    );
+
+    let mut indent = 1usize;
+
+    for (i, o) in layout.output.iter().enumerate() {
+        match o {
+            Output::Stdout(s) => {
+                res.push_str(&fn_write(indent, s, "stdout"));
+                if i + 1 < layout.output.len() {
+                    res.push_str(&format!("\n{}fflush(stdout);", make_indent(indent)));
+                }
+            }
+            Output::Stderr(s) => res.push_str(&fn_write(indent, s, "stderr")),
+        }
+    }
+
+    let codes = &layout.states;
+    if !codes.is_empty() {
+        if layout.restore {
+            for (i, v) in &layout.stacks {
+                for n in v {
+                    res.push_str(&format!("\n    hy_restore({}, {});", i, num_parts(n)));
+                }
+            }
+            res.push_str(&format!("\n    cur = {};", layout.cur));
+            if let Some(v) = layout.last {
+                res.push_str(&format!("\n    last = {};", v));
+            }
+            for (a, b) in &layout.points {
+                res.push_str(&format!(
+                    "\n    *map_entry(&hy_points, {}u, {0}) = {1};",
+                    a, b
+                ));
+            }
+            res.push_str(&format!("\n    state = {};", layout.start));
+        }
+
+        res.push_str(&format!("\n    while (state < {}) {{", codes.len()));
+        indent += 1;
+
+        let mut stack = vec![(codes.len(), false)];
+
+        for i in 0..codes.len() {
+            while stack.last().unwrap().0 > 1 {
+                stack.push((stack.last().unwrap().0 / 2, false));
+                res.push_str(&format!(
+                    "\n{}if (state < {}) {{",
+                    make_indent(indent),
+                    stack.last().unwrap().0 + i
+                ));
+                indent += 1;
+            }
+
+            for item in &codes[i] {
+                res.push_str(&command(indent, item));
+            }
+
+            while stack.len() > 1 && stack.last().unwrap().1 {
+                stack.pop();
+                indent -= 1;
+                res.push_str(&format!("\n{}}}", make_indent(indent)));
+            }
+
+            if i != codes.len() - 1 {
+                let last = stack.pop().unwrap().0;
+                stack.push((stack.last().unwrap().0 - last, true));
+                res.push_str(&format!("\n{}}} else {{", make_indent(indent - 1)));
+            }
+        }
+
+        res.push_str(
+            "
+        state += 1;
+    }",
+        );
+    }
+    res.push_str(
+        "
+    return 0;
+}
+",
+    );
+    res
+}
@@ -0,0 +1,236 @@
+use crate::core::area::Area;
+use crate::core::code::{self, Code};
+use crate::core::compile::{make_indent, Layout};
+use crate::core::state::{Output, State};
+use crate::number::num::Num;
+
+/// Runtime of generated code: rational number of `BigInt` and the machine with stacks
+const RUNTIME: &str = include_str!("runtime.js");
+
+/// Makes JavaScript string literal, escaping every character that is not printable ASCII
+fn string_literal(s: &str) -> String {
+    let mut res = String::from("\"");
+    for c in s.chars() {
+        if (' '..='~').contains(&c) && c != '"' && c != '\\' {
+            res.push(c);
+        } else {
+            res.push_str(&format!("\\u{{{:x}}}", c as u32));
+        }
+    }
+    res.push('"');
+    res
+}
+
+/// Makes expression of `Num` from the value
+fn num_literal(n: &Num) -> String {
+    if n.is_nan() {
+        return String::from("NAN");
+    }
+    let s = n.to_string();
+    match s.split_once('/') {
+        Some((up, down)) => format!("num({}n, {}n)", up, down),
+        None => format!("num({}n)", s),
+    }
+}
+
+/// Makes JavaScript statements from command, which call methods of the machine `m`.
+fn command(indent: usize, c: &impl Code) -> String {
+    let h = c.get_hangul_count();
+    let d = c.get_dot_count();
+    format!(
+        "{}{}",
+        match c.get_type() {
+            0 => format!("\n{}m.pushInt(cur, {}n);", make_indent(indent), h * d),
+            1 => format!("\n{}m.sum(cur, {}, {});", make_indent(indent), h, d),
+            2 => format!("\n{}m.product(cur, {}, {});", make_indent(indent), h, d),
+            3 => format!("\n{}m.negSum(cur, {}, {});", make_indent(indent), h, d),
+            4 => format!("\n{}m.flipProduct(cur, {}, {});", make_indent(indent), h, d),
+            5 => format!("\n{}cur = m.dup(cur, {}, {});", make_indent(indent), h, d),
+            code::PUSH_CONST => format!(
+                "\n{}m.push({}, {});",
+                make_indent(indent),
+                d,
+                num_literal(c.get_value().unwrap())
+            ),
+            code::ACCELERATE => String::new(),
+            // MOVE_TO
+            _ => format!("\n{}cur = {};", make_indent(indent), d),
+        },
+        area(indent, c.get_area(), c.get_area_count())
+    )
+}
+
+/// Makes code from area, branching on `m.cmpPop` with `if`.
+fn area(mut indent: usize, a: &Area, cnt: usize) -> String {
+    let mut st = vec![(a, &Area::Nil, false)];
+    let mut res = String::new();
+    loop {
+        while let Area::Val { type_, left, right } = st.last().unwrap().0 {
+            if *type_ <= 1 {
+                st.push((left, right, false));
+                res.push_str(&format!(
+                    "\n{}if (m.cmpPop(cur, {}n) {} 0) {{",
+                    make_indent(indent),
+                    cnt,
+                    if *type_ == 0 { "<" } else { "===" }
+                ));
+                indent += 1;
+                continue;
+            } else {
+                if *type_ < 13 {
+                    res.push_str(&format!(
+                        "\n{0}{{\
+                         \n{0}    const v = m.point({1}n, state);\
+                         \n{0}    if (v !== state) {{\
+                         \n{0}        last = state;\
+                         \n{0}        state = v;\
+                         \n{0}        continue;\
+                         \n{0}    }}\
+                         \n{0}}}",
+                        make_indent(indent),
+                        ((cnt as u128) << 4) + *type_ as u128
+                    ));
+                } else {
+                    res.push_str(&format!(
+                        "\n{0}if (last !== null) {{\
+                         \n{0}    state = last;\
+                         \n{0}    continue;\
+                         \n{0}}}",
+                        make_indent(indent)
+                    ));
+                }
+                break;
+            }
+        }
+
+        while st.len() > 1 && st.last().unwrap().2 {
+            st.pop();
+            indent -= 1;
+            res.push_str(&format!("\n{}}}", make_indent(indent)));
+        }
+
+        if st.len() > 1 {
+            let (left, right, _) = st.pop().unwrap();
+            st.push((right, left, true));
+            res.push_str(&format!("\n{}}} else {{", make_indent(indent - 1)));
+        } else {
+            break res;
+        }
+    }
+}
+
+/// Makes JavaScript module from parsed hyeong code.
+/// States are dispatched with binary if-else statement on the state.
+/// The module exports `run(input)` returning `{ stdout, stderr, exitCode }`,
+/// and numbers are rational of `BigInt`.
+pub fn build_source<T>(state: T, code: &[T::CodeType], level: u8) -> String
+where
+    T: State,
+{
+    let layout = Layout::new(state, code, level);
+    let mut res = format!(
+        "{}
+export function run(input = \"\") {{
+    const m = new Machine({}, input);
+    let exitCode = 0;
+    try {{
+        main(m);
+    }} catch (e) {{
+        if (!(e instanceof Exit)) {{
+            throw e;
+        }}
+        exitCode = e.code;
+        m.stderr += e.message;
+    }}
+    return {{ stdout: m.stdout, stderr: m.stderr, exitCode }};
+}}
+
+function main(m) {{
+    let state = 0;
+    let last = null;
+    let cur = 3;",
+        RUNTIME,
+        if level != 0 { layout.stack_size } else { 0 }
+    );
+
+    let mut indent = 1usize;
+
+    for o in &layout.output {
+        match o {
+            Output::Stdout(s) => res.push_str(&format!(
+                "\n{}m.stdout += {};",
+                make_indent(indent),
+                string_literal(s)
+            )),
+            Output::Stderr(s) => res.push_str(&format!(
+                "\n{}m.stderr += {};",
+                make_indent(indent),
+                string_literal(s)
+            )),
+        }
+    }
+
+    let codes = &layout.states;
+    if !codes.is_empty() {
+        if layout.restore {
+            for (i, v) in &layout.stacks {
+                for n in v {
+                    res.push_str(&format!("\n    m.restore({}, {});", i, num_literal(n)));
+                }
+            }
+            res.push_str(&format!("\n    cur = {};", layout.cur));
+            if let Some(v) = layout.last {
+                res.push_str(&format!("\n    last = {};", v));
+            }
+            for (a, b) in &layout.points {
+                res.push_str(&format!("\n    m.points.set({}n, {});", a, b));
+            }
+            res.push_str(&format!("\n    state = {};", layout.start));
+        }
+
+        res.push_str(&format!("\n    while (state < {}) {{", codes.len()));
+        indent += 1;
+
+        let mut stack = vec![(codes.len(), false)];
+
+        for i in 0..codes.len() {
+            while stack.last().unwrap().0 > 1 {
+                stack.push((stack.last().unwrap().0 / 2, false));
+                res.push_str(&format!(
+                    "\n{}if (state < {}) {{",
+                    make_indent(indent),
+                    stack.last().unwrap().0 + i
+                ));
+                indent += 1;
+            }
+
+            for item in &codes[i] {
+                res.push_str(&command(indent, item));
+            }
+
+            while stack.len() > 1 && stack.last().unwrap().1 {
+                stack.pop();
+                indent -= 1;
+                res.push_str(&format!("\n{}}}", make_indent(indent)));
+            }
+
+            if i != codes.len() - 1 {
+                let last = stack.pop().unwrap().0;
+                stack.push((stack.last().unwrap().0 - last, true));
+                res.push_str(&format!("\n{}}} else {{", make_indent(indent - 1)));
+            }
+        }
+
+        res.push_str(
+            "
+        state += 1;
+    }",
+        );
+    }
+    res.push_str(
+        "
+}
+",
+    );
+    res
+}
@@ -0,0 +1,158 @@
+use crate::core::code::UnOptCode;
+use crate::core::compile::{COMMAND_MARK, NO_HOOK};
+use std::fmt;
+
+/// Lines of generated rust code made from one command
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// first line, starting from 1
+    pub first: usize,
+    /// last line, inclusive
+    pub last: usize,
+    /// index of the parsed command
+    pub index: usize,
+    /// location of the command in hyeong code
+    pub location: (usize, usize),
+}
+
+/// Map from lines of generated rust code to the hyeong commands they come from
+///
+/// It is read from the comments that [build_source](../fn.build_source.html) puts before each command,
+/// and the code of a command lasts until the next command or the end of the function.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::compile::{self, map::SourceMap};
+/// use hyeong::core::parse;
+/// use hyeong::core::state::UnOptState;
+///
+/// let code = parse::parse(String::from("형... 흑.\n항."));
+/// let source = compile::build_source(UnOptState::new(), &code, 0);
+/// let map = SourceMap::new(&source, &code);
+///
+/// assert_eq!(3, map.entries.len());
+/// assert_eq!((2, 0), map.entries[2].location);
+/// let line = source.lines().position(|l| l.contains("stack.push(cur, Num::from_num(3))")).unwrap() + 1;
+/// assert_eq!(0, map.locate(line).unwrap().index);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    pub entries: Vec<Entry>,
+}
+
+impl SourceMap {
+    /// Reads the map from `source` made from `code`
+    pub fn new(source: &str, code: &[UnOptCode]) -> SourceMap {
+        let mut res = SourceMap::default();
+        let mut open = None;
+        for (i, line) in source.lines().enumerate() {
+            let k = line.trim_start().strip_prefix(COMMAND_MARK);
+            if k.is_some() || line == "}" {
+                if let Some((first, index)) = open.take() {
+                    res.push(first, i, index, code);
+                }
+            }
+            if let Some(Ok(index)) = k.map(str::parse::<usize>) {
+                open = Some((i + 2, index));
+            }
+        }
+        res
+    }
+
+    /// Adds entry from `first` to `last` if the command is in `code`
+    fn push(&mut self, first: usize, last: usize, index: usize, code: &[UnOptCode]) {
+        if first <= last && index < code.len() {
+            self.entries.push(Entry {
+                first,
+                last,
+                index,
+                location: code[index].get_location(),
+            });
+        }
+    }
+
+    /// Finds the command that `line` of generated code comes from
+    pub fn locate(&self, line: usize) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|e| e.first <= line && line <= e.last)
+    }
+}
+
+impl fmt::Display for SourceMap {
+    /// Prints an entry in each line,
+    /// as the range of lines, the index of command and the location
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::core::compile::map::{Entry, SourceMap};
+    ///
+    /// let map = SourceMap {
+    ///     entries: vec![Entry { first: 10, last: 12, index: 3, location: (2, 5) }],
+    /// };
+    ///
+    /// assert_eq!("10-12 3 2:5\n", map.to_string());
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for e in &self.entries {
+            writeln!(
+                f,
+                "{}-{} {} {}:{}",
+                e.first, e.last, e.index, e.location.0, e.location.1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Puts the map in generated code, with the panic hook reporting the hyeong location.
+///
+/// `source` is the code made by `build_source`, and `file` is the name of hyeong code.
+/// The hook is at the end of the code, so that lines of the map are not moved.
+/// `Stack::push` and `Stack::pop` track their caller,
+/// so a panic inside them is reported at the line of the command.
+pub fn add_panic_hook(source: String, map: &SourceMap, file: &str) -> String {
+    let mut table = String::new();
+    for e in &map.entries {
+        table.push_str(&format!(
+            "\n    ({}, {}, {}, {}, {}),",
+            e.first, e.last, e.index, e.location.0, e.location.1
+        ));
+    }
+    source.replacen(
+        NO_HOOK,
+        &format!(
+            "
+const SOURCE_MAP: &[(u32, u32, usize, usize, usize)] = &[{}
+];
+
+fn set_hook() {{
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {{
+        hook(info);
+        if let Some(l) = info.location() {{
+            if l.file() == file!() {{
+                for &(first, last, index, line, col) in SOURCE_MAP {{
+                    if first <= l.line() && l.line() <= last {{
+                        eprintln!(\"note: at command {{}} of hyeong code, {{}}:{{}}:{{}}\", index, {:?}, line, col);
+                    }}
+                }}
+            }}
+        }}
+    }}));
+}}
+
+fn location(index: usize) -> Option<(usize, usize)> {{
+    SOURCE_MAP
+        .iter()
+        .find(|e| e.2 == index)
+        .map(|e| (e.3, e.4))
+}}
+",
+            table, file
+        ),
+        1,
+    )
+}
@@ -0,0 +1,1002 @@
+use crate::core::area::Area;
+use crate::core::code::{self, Accel, Code};
+use crate::core::state::{Output, State};
+use crate::number::num::Num;
+use std::convert::TryFrom;
+
+pub mod c;
+pub mod js;
+pub mod map;
+pub mod wat;
+
+/// Comment put before the code of each command, followed by the index of the parsed command
+pub(crate) const COMMAND_MARK: &str = "// hyeong command ";
+
+/// Panic hook of generated code without source map, which is replaced by [add_panic_hook](map/fn.add_panic_hook.html)
+pub(crate) const NO_HOOK: &str = "
+fn set_hook() {}
+
+fn location(index: usize) -> Option<(usize, usize)> {
+    None
+}
+";
+
+/// Most commands in a state that rustc may inline into the dispatching loop.
+/// Larger states are kept in their own functions, so that `main` does not grow with the code.
+const INLINE_COMMANDS: usize = 4;
+
+/// Parser of runtime flags and counters, put in generated code by `build_source_with_flags`
+const FLAGS: &str = "
+struct Flags {
+    steps: u64,
+    max_steps: u64,
+    trace: bool,
+    stats: bool,
+    high: HashMap<usize, usize>,
+}
+
+impl Flags {
+    fn new() -> Flags {
+        let mut res = Flags {
+            steps: 0,
+            max_steps: u64::MAX,
+            trace: false,
+            stats: false,
+            high: HashMap::new(),
+        };
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let steps = match arg.as_str() {
+                \"--trace\" => {
+                    res.trace = true;
+                    continue;
+                }
+                \"--stats\" => {
+                    res.stats = true;
+                    continue;
+                }
+                \"--max-steps\" => args.next(),
+                _ => arg.strip_prefix(\"--max-steps=\").map(String::from),
+            };
+            match steps.and_then(|s| s.parse().ok()) {
+                Some(n) => res.max_steps = n,
+                None => {
+                    eprintln!(\"usage: [--trace] [--max-steps N] [--stats]\");
+                    std::process::exit(2);
+                }
+            }
+        }
+        res
+    }
+
+    fn step(&mut self, index: usize, cur: usize) {
+        if self.steps == self.max_steps {
+            eprintln!(\"stopped after {} steps\", self.steps);
+            self.exit(124);
+        }
+        self.steps += 1;
+        if self.trace {
+            match location(index) {
+                Some((line, col)) => eprintln!(
+                    \"trace: step {}, command {} at {}:{}, stack {}\",
+                    self.steps, index, line, col, cur
+                ),
+                None => eprintln!(\"trace: step {}, command {}, stack {}\", self.steps, index, cur),
+            }
+        }
+    }
+
+    fn mark(&mut self, idx: usize, len: usize) {
+        if self.stats {
+            let high = self.high.entry(idx).or_insert(0);
+            if *high < len {
+                *high = len;
+            }
+        }
+    }
+
+    fn exit(&self, code: i32) -> ! {
+        if self.stats {
+            eprintln!(\"steps: {}\", self.steps);
+            let mut high = self.high.iter().collect::<Vec<_>>();
+            high.sort();
+            for (idx, len) in high {
+                eprintln!(\"stack {}: {}\", idx, len);
+            }
+        }
+        std::process::exit(code)
+    }
+}
+";
+
+/// Makes indent with 4 spaces
+fn make_indent(value: usize) -> String {
+    " ".repeat(value * 4)
+}
+
+/// Makes print function from string to print
+fn fn_print(indent: usize, s: String) -> String {
+    format!("\n{}print!({:?});", make_indent(indent), s)
+}
+
+/// Makes eprint function from string to print
+fn fn_eprint(indent: usize, s: String) -> String {
+    format!("\n{}eprint!({:?});", make_indent(indent), s)
+}
+
+/// Makes string literal of vector from vector of `Num`
+fn vec_to_str(v: &[Num]) -> String {
+    let mut res = String::new();
+    for i in v {
+        res.push_str(&format!("{:?}, ", i.to_string()));
+    }
+    res
+}
+
+/// Makes expression of `Num` from the value.
+/// Integers use `Num::from_num` not to parse string at runtime.
+fn num_literal(n: &Num) -> String {
+    match isize::try_from(n) {
+        Ok(v) => format!("Num::from_num({})", v),
+        Err(_) => format!(
+            "Num::from_string(String::from({:?})).unwrap()",
+            n.to_string()
+        ),
+    }
+}
+
+/// Makes expression of `Vec<Num>` from the values
+fn num_vec(v: &[Num]) -> String {
+    let mut res = String::from("vec![");
+    for (i, n) in v.iter().enumerate() {
+        if i > 0 {
+            res.push_str(", ");
+        }
+        res.push_str(&num_literal(n));
+    }
+    res.push(']');
+    res
+}
+
+/// Collects ids of hearts that the code and the points use, sorted.
+/// The point table of generated code is an array indexed by the position of id in it.
+fn hearts<C: Code>(states: &[Vec<C>], points: &[(u128, usize)]) -> Vec<u128> {
+    let mut res = points.iter().map(|p| p.0).collect::<Vec<_>>();
+    for c in states.iter().flatten() {
+        if let Some(a) = c.get_loop() {
+            res.push(a.id);
+        }
+        let mut st = vec![c.get_area()];
+        while let Some(a) = st.pop() {
+            if let Area::Val { type_, left, right } = a {
+                if *type_ <= 1 {
+                    st.push(left);
+                    st.push(right);
+                } else if *type_ < 13 {
+                    res.push(((c.get_area_count() as u128) << 4) + *type_ as u128);
+                }
+            }
+        }
+    }
+    res.sort_unstable();
+    res.dedup();
+    res
+}
+
+/// Index of heart `id` in the point table
+fn slot(hearts: &[u128], id: u128) -> usize {
+    hearts.binary_search(&id).unwrap()
+}
+
+/// Makes the code running loop in closed form, when it is in state `state`.
+/// The loop starts from state `head`, which has the heart,
+/// and the command jumping back is in the state after.
+fn accelerate(
+    indent: usize,
+    a: &Accel,
+    cur: usize,
+    state: usize,
+    head: usize,
+    hearts: &[u128],
+) -> String {
+    let l = &a.body;
+    let rows = l.step.rows().iter().map(|r| num_vec(r)).collect::<Vec<_>>();
+    let exit = l
+        .exit
+        .iter()
+        .map(|w| {
+            format!(
+                "vec![{}]",
+                w.iter().map(|r| num_vec(r)).collect::<Vec<_>>().join(", ")
+            )
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "\n{0}if cur == {1} && point[{2}] == {3} {{\
+         \n{0}    let l = AffineLoop::new(\
+         \n{0}        vec!{4:?},\
+         \n{0}        Affine::new(vec![{5}]),\
+         \n{0}        {6},\
+         \n{0}        {7},\
+         \n{0}        {8},\
+         \n{0}        {9},\
+         \n{0}        vec![{10}],\
+         \n{0}    )\
+         \n{0}    .unwrap();\
+         \n{0}    if let Option::Some(k) = l.run_stacks(&mut stack.data) {{\
+         \n{0}        if k != Num::zero() {{\
+         \n{0}            last = Option::Some({11});\
+         \n{0}        }}\
+         \n{0}        return ({12}, last, {13});\
+         \n{0}    }}\
+         \n{0}}}",
+        make_indent(indent),
+        cur,
+        slot(hearts, a.id),
+        head,
+        l.windows,
+        rows.join(", "),
+        num_vec(&l.counter),
+        num_literal(&l.value),
+        l.equal,
+        l.repeat_if,
+        exit.join(", "),
+        state + 1,
+        state + 2,
+        a.cur
+    )
+}
+
+/// Makes the code from command in state `state`.
+/// `head` is the state of the heart that the loop of `ACCELERATE` starts from.
+fn command(
+    indent: usize,
+    c: &impl Code,
+    state: usize,
+    head: Option<usize>,
+    hearts: &[u128],
+) -> String {
+    format!(
+        "{}{}",
+        match c.get_type() {
+            0 => {
+                format!(
+                    "\n{}stack.push(cur, Num::from_num({}));",
+                    make_indent(indent),
+                    c.get_hangul_count() * c.get_dot_count()
+                )
+            }
+            1 => {
+                format!(
+                    "\n{0}let mut n = Num::zero();\
+                     \n{0}for _ in 0..{1} {{\
+                     \n{0}    n += &stack.pop(cur);\
+                     \n{0}}}\
+                     \n{0}stack.push({2}, n);",
+                    make_indent(indent),
+                    c.get_hangul_count(),
+                    c.get_dot_count()
+                )
+            }
+            2 => {
+                format!(
+                    "\n{0}let mut n = Num::one();\
+                     \n{0}for _ in 0..{1} {{\
+                     \n{0}    n *= &stack.pop(cur);\
+                     \n{0}}}\
+                     \n{0}stack.push({2}, n);",
+                    make_indent(indent),
+                    c.get_hangul_count(),
+                    c.get_dot_count()
+                )
+            }
+            3 => {
+                format!(
+                    "\n{0}let mut n = Num::zero();\
+                     \n{0}let mut v = Vec::with_capacity({1});\
+                     \n{0}for _ in 0..{1} {{\
+                     \n{0}    v.push(stack.pop(cur));\
+                     \n{0}}}\
+                     \n{0}v.reverse();\
+                     \n{0}for mut x in v {{\
+                     \n{0}    x.minus();\
+                     \n{0}    n += &x;\
+                     \n{0}    stack.push(cur, x);\
+                     \n{0}}}\
+                     \n{0}stack.push({2}, n);",
+                    make_indent(indent),
+                    c.get_hangul_count(),
+                    c.get_dot_count()
+                )
+            }
+            4 => {
+                format!(
+                    "\n{0}let mut n = Num::one();\
+                     \n{0}let mut v = Vec::with_capacity({1});\
+                     \n{0}for _ in 0..{1} {{\
+                     \n{0}    v.push(stack.pop(cur));\
+                     \n{0}}}\
+                     \n{0}v.reverse();\
+                     \n{0}for mut x in v {{\
+                     \n{0}    x.flip();\
+                     \n{0}    n *= &x;\
+                     \n{0}    stack.push(cur, x);\
+                     \n{0}}}\
+                     \n{0}stack.push({2}, n);",
+                    make_indent(indent),
+                    c.get_hangul_count(),
+                    c.get_dot_count()
+                )
+            }
+            5 => {
+                format!(
+                    "\n{0}let n = stack.pop(cur);\
+                     \n{0}for _ in 0..{1} {{\
+                     \n{0}    stack.push({2}, n.clone());\
+                     \n{0}}}\
+                     \n{0}stack.push(cur, n);\
+                     \n{0}cur = {2};",
+                    make_indent(indent),
+                    c.get_hangul_count(),
+                    c.get_dot_count()
+                )
+            }
+            code::PUSH_CONST => {
+                format!(
+                    "\n{}stack.push({}, {});",
+                    make_indent(indent),
+                    c.get_dot_count(),
+                    num_literal(c.get_value().unwrap())
+                )
+            }
+            code::ACCELERATE => match head {
+                Some(head) => accelerate(
+                    indent,
+                    c.get_loop().unwrap(),
+                    c.get_dot_count(),
+                    state,
+                    head,
+                    hearts,
+                ),
+                None => String::new(),
+            },
+            // MOVE_TO
+            _ => {
+                format!("\n{}cur = {};", make_indent(indent), c.get_dot_count())
+            }
+        },
+        area(indent, c.get_area(), c.get_area_count(), state, hearts)
+    )
+}
+
+/// Makes code from area in state `state`.
+/// Since area consist of binary tree,
+/// we used match and recursively put content inside.
+/// (Didn't used recursive function)
+fn area(mut indent: usize, a: &Area, cnt: usize, state: usize, hearts: &[u128]) -> String {
+    let mut st = vec![(a, &Area::Nil, false)];
+    let mut res = String::new();
+    loop {
+        while let Area::Val { type_, left, right } = st.last().unwrap().0 {
+            if *type_ <= 1 {
+                st.push((left, right, false));
+                res.push_str(&format!(
+                    "\n{0}match stack.pop(cur).partial_cmp(&Num::from_num({1})) {{\
+                         \n{0}    Some(std::cmp::Ordering::{2}) => {{",
+                    make_indent(indent),
+                    cnt,
+                    if *type_ == 0 { "Less" } else { "Equal" }
+                ));
+                indent += 2;
+                continue;
+            } else {
+                if *type_ < 13 {
+                    res.push_str(&format!(
+                        "\n{0}if point[{1}] == usize::MAX {{\
+                             \n{0}    point[{1}] = {2};\
+                             \n{0}}} else if point[{1}] != {2} {{\
+                             \n{0}    return (point[{1}], Option::Some({2}), cur);\
+                             \n{0}}}",
+                        make_indent(indent),
+                        slot(hearts, ((cnt as u128) << 4) + *type_ as u128),
+                        state
+                    ));
+                } else {
+                    res.push_str(&format!(
+                        "\n{0}if let Option::Some(v) = last {{\
+                             \n{0}    return (v, last, cur);\
+                             \n{0}}}",
+                        make_indent(indent)
+                    ));
+                }
+                break;
+            }
+        }
+
+        while st.len() > 1 && st.last().unwrap().2 {
+            st.pop();
+            indent -= 2;
+            res.push_str(&format!(
+                "\n{0}    }}\
+                 \n{0}}}",
+                make_indent(indent)
+            ));
+        }
+
+        if st.len() > 1 {
+            let (left, right, _) = st.pop().unwrap();
+            st.push((right, left, true));
+            indent -= 1;
+            res.push_str(&format!(
+                "\n{0}}}\
+                 \n{0}_ => {{",
+                make_indent(indent)
+            ));
+            indent += 1;
+        } else {
+            break res;
+        }
+    }
+}
+
+/// Hyeong code split into states, which every backend dispatches on.
+/// A state ends with a command that has area, since the area decides the next state.
+/// From level 2, the state left by pre-executing is restored before running.
+///
+/// Every backend walks areas the same way, nesting a branch for each comparison.
+/// Only rust code runs `ACCELERATE` in closed form;
+/// the other backends leave it out, so the loop after it runs as usual.
+pub(crate) struct Layout<C> {
+    /// size of stacks when optimized
+    pub stack_size: usize,
+    /// output made while optimizing
+    pub output: Vec<Output>,
+    /// whether the state after optimizing is restored
+    pub restore: bool,
+    /// non-empty stacks after optimizing
+    pub stacks: Vec<(usize, Vec<Num>)>,
+    /// current stack after optimizing
+    pub cur: usize,
+    /// latest location after optimizing, in states
+    pub last: Option<usize>,
+    /// points after optimizing, in states
+    pub points: Vec<(u128, usize)>,
+    /// state to start from
+    pub start: usize,
+    /// commands of each state
+    pub states: Vec<Vec<C>>,
+    /// index of the parsed command that each command of `states` comes from, if it is known
+    pub origins: Vec<Vec<Option<usize>>>,
+    /// state of the heart that each `ACCELERATE` command of `states` loops back to
+    pub heads: Vec<Vec<Option<usize>>>,
+}
+
+impl<C: Code + Clone> Layout<C> {
+    /// Splits `code` into states, starting from `state`
+    ///
+    /// At level 0, `code` is the parsed code, so the origin of a command is its position.
+    pub(crate) fn new<T>(mut state: T, code: &[C], level: u8) -> Layout<C>
+    where
+        T: State<CodeType = C>,
+    {
+        let mut res = Layout {
+            stack_size: state.stack_size(),
+            output: state.get_output().to_vec(),
+            restore: level >= 2 && !code.is_empty(),
+            stacks: Vec::new(),
+            cur: 3,
+            last: None,
+            points: Vec::new(),
+            start: 0,
+            states: Vec::new(),
+            origins: Vec::new(),
+            heads: Vec::new(),
+        };
+        state.clear_output();
+
+        if code.is_empty() {
+            return res;
+        }
+
+        let mut codes: Vec<Vec<C>> = vec![Vec::new()];
+        let mut origins: Vec<Vec<Option<usize>>> = vec![Vec::new()];
+
+        if res.restore {
+            for i in state.get_all_stack_index() {
+                if !state.get_stack(i).is_empty() {
+                    res.stacks.push((i, state.get_stack(i).clone()));
+                }
+            }
+            res.cur = state.current_stack();
+            res.last = state.get_latest_loc();
+
+            let mut point = state.get_all_point();
+            point.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            let mut idx = 0;
+
+            for (i, c) in state.get_all_code().iter().enumerate() {
+                match c.get_area() {
+                    Area::Val {
+                        type_: _,
+                        left: _,
+                        right: _,
+                    } => {
+                        if !codes.last().unwrap().is_empty() {
+                            codes.push(vec![c.clone()]);
+                            origins.push(vec![c.get_origin()]);
+                        } else {
+                            codes.last_mut().unwrap().push(c.clone());
+                            origins.last_mut().unwrap().push(c.get_origin());
+                        }
+                        while idx < point.len() && point[idx].1 == i {
+                            point[idx].1 = codes.len() - 1;
+                            idx += 1;
+                        }
+                        codes.push(Vec::new());
+                        origins.push(Vec::new());
+                    }
+                    Area::Nil => {
+                        codes.last_mut().unwrap().push(c.clone());
+                        origins.last_mut().unwrap().push(c.get_origin());
+                    }
+                }
+            }
+
+            // commands left start from a new state
+            if !codes.last().unwrap().is_empty() {
+                codes.push(Vec::new());
+                origins.push(Vec::new());
+            }
+
+            res.points = point;
+            res.start = codes.len() - 1;
+        }
+
+        for (i, c) in code.iter().enumerate() {
+            let origin = if level == 0 { Some(i) } else { c.get_origin() };
+            match c.get_area() {
+                Area::Val {
+                    type_: _,
+                    left: _,
+                    right: _,
+                } => {
+                    if !codes.last().unwrap().is_empty() {
+                        codes.push(vec![c.clone()]);
+                        origins.push(vec![origin]);
+                    } else {
+                        codes.last_mut().unwrap().push(c.clone());
+                        origins.last_mut().unwrap().push(origin);
+                    }
+                    codes.push(Vec::new());
+                    origins.push(Vec::new());
+                }
+                Area::Nil => {
+                    codes.last_mut().unwrap().push(c.clone());
+                    origins.last_mut().unwrap().push(origin);
+                }
+            }
+        }
+
+        if codes.last().unwrap().is_empty() {
+            codes.pop().unwrap();
+            origins.pop().unwrap();
+        }
+
+        // commands pre-executed are kept in states, so the position counts from the start of code
+        let state_of = codes
+            .iter()
+            .enumerate()
+            .flat_map(|(i, c)| c.iter().map(move |_| i))
+            .collect::<Vec<_>>();
+        let mut pos = 0usize;
+        for c in &codes {
+            let mut heads = Vec::new();
+            for item in c {
+                heads.push(
+                    item.get_loop()
+                        .and_then(|a| pos.checked_sub(a.back))
+                        .map(|p| state_of[p]),
+                );
+                pos += 1;
+            }
+            res.heads.push(heads);
+        }
+
+        res.states = codes;
+        res.origins = origins;
+        res
+    }
+}
+
+/// Makes rust code from parsed hyeong code.
+/// It splits codes into states.
+/// Then, go through states deciding where to go next.
+/// States are dispatched with flat `match`, which rustc compiles to a jump table,
+/// so each movement takes `O(1)` and the code is not nested deeper with more states.
+/// Each state is a function, which rustc may inline when it is small.
+/// Hearts are resolved to indices at compile time,
+/// so the point table is an array with `usize::MAX` for hearts not met yet.
+/// Each command is preceded by a comment with the index of the parsed command it comes from,
+/// which [SourceMap](map/struct.SourceMap.html) reads.
+pub fn build_source<T>(state: T, code: &[T::CodeType], level: u8) -> String
+where
+    T: State,
+{
+    build_source_with_flags(state, code, level, false)
+}
+
+/// Makes rust code like `build_source`, with runtime flags in the binary if `flags` is true.
+///
+/// The binary takes `--trace` to print each command to stderr before it is executed,
+/// `--max-steps N` to stop with exit code 124 after `N` commands,
+/// and `--stats` to print the number of commands executed and the largest size of each stack on exit.
+/// Only commands with a known origin are counted, and an accelerated loop counts as one command.
+/// Stacks are numbered as in the optimized program, so they may differ from the source when `level` is not 0.
+/// Without `flags`, the code is the same as `build_source`.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::compile;
+/// use hyeong::core::parse;
+/// use hyeong::core::state::UnOptState;
+///
+/// let code = parse::parse(String::from("형... 흑."));
+/// let source = compile::build_source_with_flags(UnOptState::new(), &code, 0, true);
+///
+/// assert!(source.contains("\"--max-steps\""));
+/// assert!(source.contains("stack.flags.step(0, cur);"));
+/// assert_eq!(
+///     compile::build_source(UnOptState::new(), &code, 0),
+///     compile::build_source_with_flags(UnOptState::new(), &code, 0, false),
+/// );
+/// ```
+pub fn build_source_with_flags<T>(state: T, code: &[T::CodeType], level: u8, flags: bool) -> String
+where
+    T: State,
+{
+    let opt = level != 0;
+    let layout = Layout::new(state, code, level);
+    let hearts = hearts(&layout.states, &layout.points);
+    let exit = if flags {
+        "self.flags.exit"
+    } else {
+        "std::process::exit"
+    };
+    let mut res = format!(
+        "{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}",
+        "\
+#![allow(warnings)]
+use hyeong::number::affine::{Affine, AffineLoop};
+use hyeong::number::big_number::BigNum;
+use hyeong::number::num::Num;
+use std::collections::HashMap;
+
+struct Stack {
+    data: ",
+        if opt { "Vec<" } else { "HashMap<usize, " },
+        "Vec<Num>>,",
+        if flags { "\n    flags: Flags," } else { "" },
+        "
+}
+
+impl Stack {
+    fn new() -> Stack {
+        Stack {
+            data: ",
+        if opt {
+            format!("vec![Vec::new(); {}]", layout.stack_size)
+        } else {
+            String::from("HashMap::new()")
+        },
+        ",",
+        if flags {
+            "\n            flags: Flags::new(),"
+        } else {
+            ""
+        },
+        "
+        }
+    }
+
+    #[track_caller]
+    fn pop(&mut self, idx: usize) -> Num {
+        if idx == 1 {
+            ",
+        exit,
+        "(0);
+        }
+        if idx == 2 {
+            ",
+        exit,
+        "(1);
+        }
+        ",
+        if opt {
+            "if idx < self.data.len() {
+            match self.data[idx].pop() {
+                Some(n) => n,
+                None => {
+                    if idx == 0 {
+                        let mut s = String::new();
+                        std::io::stdin().read_line(&mut s).unwrap();
+                        for c in s.chars().rev() {
+                            self.data[0].push(Num::from_num(c as isize));
+                        }
+                        match self.data[0].pop() {
+                            Some(n) => n,
+                            None => Num::nan(),
+                        }
+                    } else {
+                        Num::nan()
+                    }
+                },
+            }
+        } else {
+            Num::nan()
+        }"
+        } else {
+            "match self.data.entry(idx).or_insert(Vec::new()).pop() {
+            Some(n) => n,
+            None => {
+                if idx == 0 {
+                    let mut s = String::new();
+                    std::io::stdin().read_line(&mut s).unwrap();
+                    for c in s.chars().rev() {
+                        self.data.get_mut(&0).unwrap().push(Num::from_num(c as isize));
+                    }
+                    match self.data.get_mut(&0).unwrap().pop() {
+                        Some(n) => n,
+                        None => Num::nan(),
+                    }
+                } else {
+                    Num::nan()
+                }
+            },
+        }"
+        },
+        "
+    }
+
+    #[track_caller]
+    fn push(&mut self, idx: usize, num: Num) {
+        if idx == 1 {
+            if num.is_pos() {
+                print!(\"{}\", std::char::from_u32(num.floor().to_int()).unwrap());
+            } else {
+                print!(\"{}\", -&num);
+            }
+            return;
+        }
+        if idx == 2 {
+            if num.is_pos() {
+                eprint!(\"{}\", std::char::from_u32(num.floor().to_int()).unwrap());
+            } else {
+                eprint!(\"{}\", -&num);
+            }
+            return;
+        }
+        ",
+        if opt {
+            "if idx < self.data.len() {
+            if !self.data[idx].is_empty() || !num.is_nan() {
+                self.data[idx].push(num);"
+        } else {
+            "let st = self.data.entry(idx).or_insert(Vec::new());
+        if !st.is_empty() || !num.is_nan() {
+            st.push(num);"
+        },
+        if !flags {
+            ""
+        } else if opt {
+            "\n                self.flags.mark(idx, self.data[idx].len());"
+        } else {
+            "\n            self.flags.mark(idx, st.len());"
+        },
+        if opt {
+            "
+            }
+         }"
+        } else {
+            "
+        }"
+        },
+        "
+    }
+}
+",
+        if flags { FLAGS } else { "" },
+        "
+fn main() {
+    set_hook();
+    let mut stack = Stack::new();
+    let mut point: Point = [usize::MAX; ",
+        hearts.len(),
+        "];
+    let mut state = 0usize;
+    let mut last = Option::<usize>::None;
+    let mut cur = 3usize;
+",
+    );
+
+    let indent = 1usize;
+
+    for (i, o) in layout.output.iter().enumerate() {
+        match o {
+            Output::Stdout(s) => {
+                res.push_str(&fn_print(indent, s.clone()));
+                if i + 1 < layout.output.len() {
+                    res.push_str(&format!(
+                        "\n{}std::io::Write::flush(&mut std::io::stdout()).unwrap();",
+                        make_indent(indent)
+                    ));
+                }
+            }
+            Output::Stderr(s) => res.push_str(&fn_eprint(indent, s.clone())),
+        }
+    }
+
+    let codes = &layout.states;
+    if !codes.is_empty() {
+        if layout.restore {
+            for (i, v) in &layout.stacks {
+                res.push_str(&format!(
+                    "
+    stack.data[{}] = vec![{}].iter().map(|x| Num::from_string(x.to_string()).unwrap()).collect();",
+                    i,
+                    vec_to_str(v)
+                ));
+            }
+
+            res.push_str(&format!(
+                "
+    cur = {};",
+                layout.cur
+            ));
+
+            res.push_str(&format!(
+                "
+    last = Option::{};",
+                match layout.last {
+                    Some(v) => format!("Some({})", v),
+                    None => String::from("None"),
+                }
+            ));
+
+            for (a, b) in &layout.points {
+                res.push_str(&format!(
+                    "
+    point[{}] = {};",
+                    slot(&hearts, *a),
+                    b
+                ));
+            }
+
+            res.push_str(&format!(
+                "
+    state = {};",
+                layout.start,
+            ));
+        }
+
+        res.push_str(&format!(
+            "
+    while state < {} {{
+        let next = match state {{",
+            codes.len()
+        ));
+        for i in 0..codes.len() {
+            res.push_str(&format!(
+                "\n            {0} => state_{0}(&mut stack, &mut point, last, cur),",
+                i
+            ));
+        }
+        res.push_str(
+            "
+            _ => unreachable!(),
+        };
+        state = next.0;
+        last = next.1;
+        cur = next.2;
+    }",
+        );
+    }
+    if flags {
+        res.push_str("\n    stack.flags.exit(0);");
+    }
+    res.push_str(&format!(
+        "
+}}
+
+type Point = [usize; {}];
+",
+        hearts.len()
+    ));
+
+    for (i, c) in codes.iter().enumerate() {
+        res.push_str(&format!(
+            "
+{}
+fn state_{}(
+    stack: &mut Stack,
+    point: &mut Point,
+    mut last: Option<usize>,
+    mut cur: usize,
+) -> (usize, Option<usize>, usize) {{",
+            if c.len() <= INLINE_COMMANDS {
+                "#[inline]"
+            } else {
+                "#[inline(never)]"
+            },
+            i
+        ));
+        for ((item, origin), head) in c.iter().zip(&layout.origins[i]).zip(&layout.heads[i]) {
+            if let Some(k) = origin {
+                res.push_str(&format!("\n    {}{}", COMMAND_MARK, k));
+                if flags {
+                    res.push_str(&format!("\n    stack.flags.step({}, cur);", k));
+                }
+            }
+            res.push_str(&command(1, item, i, *head, &hearts));
+        }
+        res.push_str(&format!(
+            "
+    ({}, last, cur)
+}}
+",
+            i + 1
+        ));
+    }
+    res.push_str(NO_HOOK);
+    res
+}
+
+/// Sources of the `number` module inlined by `build_standalone_source`
+const RUNTIME: [(&str, &str); 3] = [
+    ("affine", include_str!("../../number/affine.rs")),
+    ("big_number", include_str!("../../number/big_number.rs")),
+    ("num", include_str!("../../number/num.rs")),
+];
+
+/// Strips documents and module declarations from runtime source,
+/// and moves `crate::number` under the inlined `hyeong` module
+fn strip_runtime(source: &str) -> String {
+    let mut res = String::new();
+    for line in source.lines() {
+        let t = line.trim_start();
+        if t.starts_with("///") || t.starts_with("//!") || t.starts_with("pub mod ") {
+            continue;
+        }
+        res.push_str(&line.replace("crate::number::", "crate::hyeong::number::"));
+        res.push('\n');
+    }
+    res
+}
+
+/// Makes rust code that does not depend on the `hyeong` crate.
+///
+/// `source` is the code made by `build_source`.
+/// The rational and big number runtime is inlined as `mod hyeong` at the end,
+/// so the code is built with only `rustc -O main.rs` and the lines of `source` are not moved.
+pub fn build_standalone_source(source: String) -> String {
+    let mut runtime = strip_runtime(include_str!("../../number/mod.rs"));
+    for (name, content) in RUNTIME.iter() {
+        runtime.push_str(&format!(
+            "\npub mod {} {{\n{}}}\n",
+            name,
+            strip_runtime(content)
+        ));
+    }
+    format!(
+        "{}
+extern crate alloc;
+extern crate core;
+
+mod hyeong {{
+pub mod number {{
+{}}}
+}}
+",
+        source, runtime
+    )
+}
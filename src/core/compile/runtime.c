@@ -0,0 +1,760 @@
+#if defined(__GNUC__)
+#pragma GCC diagnostic ignored "-Wunused-function"
+#endif
+
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#define HY_NONE ((size_t)-1)
+
+/* Big integer in base 2^32, least significant limb first. Zero has no limbs. */
+typedef struct {
+    int neg;
+    size_t len;
+    uint32_t *d;
+} hy_big;
+
+/* Rational number, which is NaN when the denominator is zero */
+typedef struct {
+    hy_big up;
+    hy_big down;
+} hy_num;
+
+typedef struct {
+    size_t len;
+    size_t cap;
+    hy_num *d;
+} hy_stack;
+
+/* Open addressing hash map from 64-bit key to index */
+typedef struct {
+    size_t len;
+    size_t cap;
+    uint64_t *keys;
+    size_t *vals;
+    unsigned char *used;
+} hy_map;
+
+static void *hy_alloc(size_t n) {
+    void *p = malloc(n ? n : 1);
+    if (!p) {
+        fputs("out of memory\n", stderr);
+        exit(101);
+    }
+    return p;
+}
+
+static void *hy_realloc(void *p, size_t n) {
+    p = realloc(p, n ? n : 1);
+    if (!p) {
+        fputs("out of memory\n", stderr);
+        exit(101);
+    }
+    return p;
+}
+
+static hy_big big_new(size_t len) {
+    hy_big a;
+    a.neg = 0;
+    a.len = len;
+    a.d = hy_alloc(len * sizeof(uint32_t));
+    if (len) {
+        memset(a.d, 0, len * sizeof(uint32_t));
+    }
+    return a;
+}
+
+static void big_trim(hy_big *a) {
+    while (a->len && !a->d[a->len - 1]) {
+        a->len--;
+    }
+    if (!a->len) {
+        a->neg = 0;
+    }
+}
+
+static void big_drop(hy_big *a) {
+    free(a->d);
+    a->d = NULL;
+    a->len = 0;
+}
+
+static hy_big big_from_u64(uint64_t v) {
+    hy_big a = big_new(2);
+    a.d[0] = (uint32_t)v;
+    a.d[1] = (uint32_t)(v >> 32);
+    big_trim(&a);
+    return a;
+}
+
+static hy_big big_clone(const hy_big *a) {
+    hy_big r = big_new(a->len);
+    if (a->len) {
+        memcpy(r.d, a->d, a->len * sizeof(uint32_t));
+    }
+    r.neg = a->neg;
+    return r;
+}
+
+static int big_is_one(const hy_big *a) {
+    return !a->neg && a->len == 1 && a->d[0] == 1;
+}
+
+static int mag_cmp(const hy_big *a, const hy_big *b) {
+    size_t i;
+    if (a->len != b->len) {
+        return a->len < b->len ? -1 : 1;
+    }
+    for (i = a->len; i-- > 0;) {
+        if (a->d[i] != b->d[i]) {
+            return a->d[i] < b->d[i] ? -1 : 1;
+        }
+    }
+    return 0;
+}
+
+static int big_cmp(const hy_big *a, const hy_big *b) {
+    int c;
+    if (a->neg != b->neg) {
+        return a->neg ? -1 : 1;
+    }
+    c = mag_cmp(a, b);
+    return a->neg ? -c : c;
+}
+
+static hy_big mag_add(const hy_big *a, const hy_big *b) {
+    hy_big r;
+    uint64_t carry = 0;
+    size_t i;
+    if (a->len < b->len) {
+        const hy_big *t = a;
+        a = b;
+        b = t;
+    }
+    r = big_new(a->len + 1);
+    for (i = 0; i < a->len; i++) {
+        carry += (uint64_t)a->d[i] + (i < b->len ? b->d[i] : 0);
+        r.d[i] = (uint32_t)carry;
+        carry >>= 32;
+    }
+    r.d[a->len] = (uint32_t)carry;
+    big_trim(&r);
+    return r;
+}
+
+/* |a| - |b| where |a| >= |b| */
+static hy_big mag_sub(const hy_big *a, const hy_big *b) {
+    hy_big r = big_new(a->len);
+    uint64_t borrow = 0, t;
+    size_t i;
+    for (i = 0; i < a->len; i++) {
+        t = (uint64_t)a->d[i] - (i < b->len ? b->d[i] : 0) - borrow;
+        r.d[i] = (uint32_t)t;
+        borrow = t >> 63;
+    }
+    big_trim(&r);
+    return r;
+}
+
+static hy_big big_add(const hy_big *a, const hy_big *b) {
+    hy_big r;
+    if (a->neg == b->neg) {
+        r = mag_add(a, b);
+        r.neg = a->neg;
+    } else if (mag_cmp(a, b) >= 0) {
+        r = mag_sub(a, b);
+        r.neg = a->neg;
+    } else {
+        r = mag_sub(b, a);
+        r.neg = b->neg;
+    }
+    big_trim(&r);
+    return r;
+}
+
+static hy_big big_mul(const hy_big *a, const hy_big *b) {
+    hy_big r = big_new(a->len + b->len);
+    uint64_t t, carry;
+    size_t i, j;
+    for (i = 0; i < a->len; i++) {
+        carry = 0;
+        for (j = 0; j < b->len; j++) {
+            t = (uint64_t)a->d[i] * b->d[j] + r.d[i + j] + carry;
+            r.d[i + j] = (uint32_t)t;
+            carry = t >> 32;
+        }
+        r.d[i + b->len] = (uint32_t)carry;
+    }
+    r.neg = a->neg != b->neg;
+    big_trim(&r);
+    return r;
+}
+
+/* Divides |a| by a small number in place and returns the remainder */
+static uint32_t mag_div_small(hy_big *a, uint32_t m) {
+    uint64_t rem = 0, t;
+    size_t i;
+    for (i = a->len; i-- > 0;) {
+        t = (rem << 32) | a->d[i];
+        a->d[i] = (uint32_t)(t / m);
+        rem = t % m;
+    }
+    big_trim(a);
+    return (uint32_t)rem;
+}
+
+/* |a| = |a| * m + c */
+static void mag_mul_small_add(hy_big *a, uint32_t m, uint32_t c) {
+    uint64_t carry = c, t;
+    size_t i;
+    for (i = 0; i < a->len; i++) {
+        t = (uint64_t)a->d[i] * m + carry;
+        a->d[i] = (uint32_t)t;
+        carry = t >> 32;
+    }
+    if (carry) {
+        a->d = hy_realloc(a->d, (a->len + 1) * sizeof(uint32_t));
+        a->d[a->len++] = (uint32_t)carry;
+    }
+}
+
+/* Quotient and remainder of |a| / |b| with Knuth's algorithm D, where b is not zero */
+static void big_divmod(const hy_big *a, const hy_big *b, hy_big *q, hy_big *r) {
+    size_t n = b->len, m, i, j;
+    uint32_t *un, *vn, top;
+    unsigned s = 0;
+
+    if (mag_cmp(a, b) < 0) {
+        *q = big_new(0);
+        *r = big_clone(a);
+        r->neg = 0;
+        return;
+    }
+    if (n == 1) {
+        *q = big_clone(a);
+        q->neg = 0;
+        *r = big_from_u64(mag_div_small(q, b->d[0]));
+        return;
+    }
+
+    m = a->len - n;
+    for (top = b->d[n - 1]; !(top & 0x80000000u); top <<= 1) {
+        s++;
+    }
+    vn = hy_alloc(n * sizeof(uint32_t));
+    un = hy_alloc((a->len + 1) * sizeof(uint32_t));
+    for (i = n - 1; i > 0; i--) {
+        vn[i] = (b->d[i] << s) | (uint32_t)((uint64_t)b->d[i - 1] >> (32 - s));
+    }
+    vn[0] = b->d[0] << s;
+    un[a->len] = (uint32_t)((uint64_t)a->d[a->len - 1] >> (32 - s));
+    for (i = a->len - 1; i > 0; i--) {
+        un[i] = (a->d[i] << s) | (uint32_t)((uint64_t)a->d[i - 1] >> (32 - s));
+    }
+    un[0] = a->d[0] << s;
+
+    *q = big_new(m + 1);
+    for (j = m + 1; j-- > 0;) {
+        uint64_t num = ((uint64_t)un[j + n] << 32) | un[j + n - 1];
+        uint64_t qhat = num / vn[n - 1], rhat = num % vn[n - 1];
+        uint64_t carry = 0, borrow = 0, p, t;
+        while ((qhat >> 32) || qhat * vn[n - 2] > ((rhat << 32) | un[j + n - 2])) {
+            qhat--;
+            rhat += vn[n - 1];
+            if (rhat >> 32) {
+                break;
+            }
+        }
+        for (i = 0; i < n; i++) {
+            p = qhat * vn[i] + carry;
+            carry = p >> 32;
+            t = (uint64_t)un[i + j] - (p & 0xffffffffu) - borrow;
+            un[i + j] = (uint32_t)t;
+            borrow = t >> 63;
+        }
+        t = (uint64_t)un[j + n] - carry - borrow;
+        un[j + n] = (uint32_t)t;
+        if (t >> 63) {
+            qhat--;
+            carry = 0;
+            for (i = 0; i < n; i++) {
+                t = (uint64_t)un[i + j] + vn[i] + carry;
+                un[i + j] = (uint32_t)t;
+                carry = t >> 32;
+            }
+            un[j + n] = (uint32_t)(un[j + n] + carry);
+        }
+        q->d[j] = (uint32_t)qhat;
+    }
+
+    *r = big_new(n);
+    for (i = 0; i + 1 < n; i++) {
+        r->d[i] = (un[i] >> s) | (uint32_t)((uint64_t)un[i + 1] << (32 - s));
+    }
+    r->d[n - 1] = un[n - 1] >> s;
+    free(un);
+    free(vn);
+    big_trim(q);
+    big_trim(r);
+}
+
+static hy_big big_gcd(const hy_big *a, const hy_big *b) {
+    hy_big x = big_clone(a), y = big_clone(b), q, r;
+    x.neg = 0;
+    y.neg = 0;
+    while (y.len) {
+        big_divmod(&x, &y, &q, &r);
+        big_drop(&q);
+        big_drop(&x);
+        x = y;
+        y = r;
+    }
+    big_drop(&y);
+    return x;
+}
+
+static hy_big big_from_dec(const char *s) {
+    hy_big a = big_new(0);
+    int neg = 0;
+    if (*s == '-') {
+        neg = 1;
+        s++;
+    }
+    for (; *s; s++) {
+        mag_mul_small_add(&a, 10, (uint32_t)(*s - '0'));
+    }
+    a.neg = neg;
+    big_trim(&a);
+    return a;
+}
+
+static void big_print(FILE *f, const hy_big *a) {
+    hy_big t = big_clone(a);
+    uint32_t *parts = hy_alloc((a->len * 2 + 1) * sizeof(uint32_t));
+    size_t n = 0;
+    if (a->neg) {
+        fputc('-', f);
+    }
+    do {
+        parts[n++] = mag_div_small(&t, 1000000000u);
+    } while (t.len);
+    fprintf(f, "%lu", (unsigned long)parts[--n]);
+    while (n) {
+        fprintf(f, "%09lu", (unsigned long)parts[--n]);
+    }
+    free(parts);
+    big_drop(&t);
+}
+
+static int num_is_nan(const hy_num *a) {
+    return !a->down.len;
+}
+
+static void num_drop(hy_num *a) {
+    big_drop(&a->up);
+    big_drop(&a->down);
+}
+
+static hy_num num_clone(const hy_num *a) {
+    hy_num r;
+    r.up = big_clone(&a->up);
+    r.down = big_clone(&a->down);
+    return r;
+}
+
+static hy_num num_int(uint64_t v) {
+    hy_num r;
+    r.up = big_from_u64(v);
+    r.down = big_from_u64(1);
+    return r;
+}
+
+static hy_num num_nan(void) {
+    hy_num r;
+    r.up = big_from_u64(1);
+    r.down = big_new(0);
+    return r;
+}
+
+static hy_num num_const(const char *up, const char *down) {
+    hy_num r;
+    r.up = big_from_dec(up);
+    r.down = big_from_dec(down);
+    return r;
+}
+
+/* Divides by gcd and makes the denominator positive */
+static void num_reduce(hy_num *a) {
+    hy_big g, q, r;
+    if (num_is_nan(a)) {
+        return;
+    }
+    g = big_gcd(&a->up, &a->down);
+    if (!big_is_one(&g)) {
+        big_divmod(&a->up, &g, &q, &r);
+        q.neg = a->up.neg && q.len;
+        big_drop(&r);
+        big_drop(&a->up);
+        a->up = q;
+        big_divmod(&a->down, &g, &q, &r);
+        q.neg = a->down.neg;
+        big_drop(&r);
+        big_drop(&a->down);
+        a->down = q;
+    }
+    big_drop(&g);
+    if (a->down.neg) {
+        a->down.neg = 0;
+        a->up.neg = !a->up.neg && a->up.len;
+    }
+}
+
+static hy_num num_add(const hy_num *a, const hy_num *b) {
+    hy_num r;
+    hy_big x, y;
+    if (num_is_nan(a) || num_is_nan(b)) {
+        return num_nan();
+    }
+    x = big_mul(&a->up, &b->down);
+    y = big_mul(&a->down, &b->up);
+    r.up = big_add(&x, &y);
+    r.down = big_mul(&a->down, &b->down);
+    big_drop(&x);
+    big_drop(&y);
+    num_reduce(&r);
+    return r;
+}
+
+static hy_num num_mul(const hy_num *a, const hy_num *b) {
+    hy_num r;
+    if (num_is_nan(a) || num_is_nan(b)) {
+        return num_nan();
+    }
+    r.up = big_mul(&a->up, &b->up);
+    r.down = big_mul(&a->down, &b->down);
+    num_reduce(&r);
+    return r;
+}
+
+static void num_minus(hy_num *a) {
+    a->up.neg = !a->up.neg && a->up.len;
+}
+
+static void num_flip(hy_num *a) {
+    hy_big t;
+    if (num_is_nan(a)) {
+        return;
+    }
+    t = a->up;
+    a->up = a->down;
+    a->down = t;
+    if (a->down.neg) {
+        a->down.neg = 0;
+        a->up.neg = !a->up.neg && a->up.len;
+    }
+}
+
+/* Compares with integer, where NaN is greater than any number */
+static int num_cmp_int(const hy_num *a, uint64_t v) {
+    hy_big x, y;
+    int c;
+    if (num_is_nan(a)) {
+        return 1;
+    }
+    x = big_from_u64(v);
+    y = big_mul(&x, &a->down);
+    c = big_cmp(&a->up, &y);
+    big_drop(&x);
+    big_drop(&y);
+    return c;
+}
+
+/* Prints the character of the floor if positive, otherwise prints the negated number */
+static void num_print(FILE *f, const hy_num *a) {
+    hy_big q, r, t;
+    uint32_t c;
+    if (num_is_nan(a)) {
+        fputs("\353\204\210\353\254\264 \354\273\244\354\227\207...", f);
+    } else if (!a->up.neg) {
+        big_divmod(&a->up, &a->down, &q, &r);
+        c = q.len ? q.d[0] : 0;
+        big_drop(&q);
+        big_drop(&r);
+        if (c > 0x10ffff || (c >= 0xd800 && c <= 0xdfff)) {
+            fflush(stdout);
+            fprintf(stderr, "invalid character: %lu\n", (unsigned long)c);
+            exit(101);
+        }
+        if (c < 0x80) {
+            fputc((int)c, f);
+        } else if (c < 0x800) {
+            fputc((int)(0xc0 | (c >> 6)), f);
+            fputc((int)(0x80 | (c & 0x3f)), f);
+        } else if (c < 0x10000) {
+            fputc((int)(0xe0 | (c >> 12)), f);
+            fputc((int)(0x80 | ((c >> 6) & 0x3f)), f);
+            fputc((int)(0x80 | (c & 0x3f)), f);
+        } else {
+            fputc((int)(0xf0 | (c >> 18)), f);
+            fputc((int)(0x80 | ((c >> 12) & 0x3f)), f);
+            fputc((int)(0x80 | ((c >> 6) & 0x3f)), f);
+            fputc((int)(0x80 | (c & 0x3f)), f);
+        }
+    } else {
+        t = a->up;
+        t.neg = 0;
+        big_print(f, &t);
+        if (!big_is_one(&a->down)) {
+            fputc('/', f);
+            big_print(f, &a->down);
+        }
+    }
+}
+
+static size_t map_slot(const hy_map *m, uint64_t key) {
+    size_t i = (size_t)((key * 0x9e3779b97f4a7c15ULL) >> 32) & (m->cap - 1);
+    while (m->used[i] && m->keys[i] != key) {
+        i = (i + 1) & (m->cap - 1);
+    }
+    return i;
+}
+
+/* Value of `key`, inserting `val` if there is no key */
+static size_t *map_entry(hy_map *m, uint64_t key, size_t val) {
+    size_t i;
+    if (2 * (m->len + 1) > m->cap) {
+        hy_map n;
+        n.len = m->len;
+        n.cap = m->cap ? 2 * m->cap : 16;
+        n.keys = hy_alloc(n.cap * sizeof(uint64_t));
+        n.vals = hy_alloc(n.cap * sizeof(size_t));
+        n.used = hy_alloc(n.cap);
+        memset(n.used, 0, n.cap);
+        for (i = 0; i < m->cap; i++) {
+            if (m->used[i]) {
+                size_t j = map_slot(&n, m->keys[i]);
+                n.used[j] = 1;
+                n.keys[j] = m->keys[i];
+                n.vals[j] = m->vals[i];
+            }
+        }
+        free(m->keys);
+        free(m->vals);
+        free(m->used);
+        *m = n;
+    }
+    i = map_slot(m, key);
+    if (!m->used[i]) {
+        m->used[i] = 1;
+        m->keys[i] = key;
+        m->vals[i] = val;
+        m->len++;
+    }
+    return &m->vals[i];
+}
+
+static hy_map hy_points;
+
+#if HY_STACK_SIZE
+static hy_stack hy_stacks[HY_STACK_SIZE];
+
+static hy_stack *stack_get(size_t idx) {
+    return idx < HY_STACK_SIZE ? &hy_stacks[idx] : NULL;
+}
+#else
+/* Without optimization, stacks are not renumbered, so they are made when used */
+static hy_map hy_stack_map;
+static hy_stack **hy_stack_list;
+static size_t hy_stack_count;
+
+static hy_stack *stack_get(size_t idx) {
+    size_t i = *map_entry(&hy_stack_map, idx, hy_stack_count);
+    if (i == hy_stack_count) {
+        hy_stack_list = hy_realloc(hy_stack_list, (hy_stack_count + 1) * sizeof(hy_stack *));
+        hy_stack_list[i] = hy_alloc(sizeof(hy_stack));
+        memset(hy_stack_list[i], 0, sizeof(hy_stack));
+        hy_stack_count++;
+    }
+    return hy_stack_list[i];
+}
+#endif
+
+static void stack_push(hy_stack *s, hy_num n) {
+    if (s->len == s->cap) {
+        s->cap = s->cap ? 2 * s->cap : 8;
+        s->d = hy_realloc(s->d, s->cap * sizeof(hy_num));
+    }
+    s->d[s->len++] = n;
+}
+
+/* Reads a line from stdin and pushes its characters, so that the first one is on top */
+static void read_line(hy_stack *s) {
+    uint32_t *v = NULL;
+    size_t len = 0, cap = 0;
+    int c;
+    while ((c = getchar()) != EOF) {
+        uint32_t u = (uint32_t)c;
+        int more = 0;
+        if (u >= 0xf0) {
+            u &= 0x07;
+            more = 3;
+        } else if (u >= 0xe0) {
+            u &= 0x0f;
+            more = 2;
+        } else if (u >= 0xc0) {
+            u &= 0x1f;
+            more = 1;
+        }
+        for (; more > 0 && (c = getchar()) != EOF; more--) {
+            u = (u << 6) | ((uint32_t)c & 0x3f);
+        }
+        if (len == cap) {
+            cap = cap ? 2 * cap : 64;
+            v = hy_realloc(v, cap * sizeof(uint32_t));
+        }
+        v[len++] = u;
+        if (u == '\n') {
+            break;
+        }
+    }
+    while (len) {
+        stack_push(s, num_int(v[--len]));
+    }
+    free(v);
+}
+
+static hy_num hy_pop(size_t idx) {
+    hy_stack *s;
+    if (idx == 1) {
+        exit(0);
+    }
+    if (idx == 2) {
+        exit(1);
+    }
+    s = stack_get(idx);
+    if (!s) {
+        return num_nan();
+    }
+    if (!s->len && idx == 0) {
+        read_line(s);
+    }
+    if (!s->len) {
+        return num_nan();
+    }
+    return s->d[--s->len];
+}
+
+static void hy_push(size_t idx, hy_num n) {
+    hy_stack *s;
+    if (idx == 1 || idx == 2) {
+        num_print(idx == 1 ? stdout : stderr, &n);
+        num_drop(&n);
+        return;
+    }
+    s = stack_get(idx);
+    if (s && (s->len || !num_is_nan(&n))) {
+        stack_push(s, n);
+    } else {
+        num_drop(&n);
+    }
+}
+
+/* Puts the number left by optimizing */
+static void hy_restore(size_t idx, const char *up, const char *down) {
+    stack_push(stack_get(idx), num_const(up, down));
+}
+
+/* 형: pushes `h * d` to the current stack */
+static void hy_push_int(size_t cur, uint64_t v) {
+    hy_push(cur, num_int(v));
+}
+
+/* 항: pops `h` numbers and pushes the sum to stack `d` */
+static void hy_sum(size_t cur, size_t h, size_t d) {
+    hy_num n = num_int(0), x, t;
+    size_t i;
+    for (i = 0; i < h; i++) {
+        x = hy_pop(cur);
+        t = num_add(&n, &x);
+        num_drop(&x);
+        num_drop(&n);
+        n = t;
+    }
+    hy_push(d, n);
+}
+
+/* 핫: pops `h` numbers and pushes the product to stack `d` */
+static void hy_product(size_t cur, size_t h, size_t d) {
+    hy_num n = num_int(1), x, t;
+    size_t i;
+    for (i = 0; i < h; i++) {
+        x = hy_pop(cur);
+        t = num_mul(&n, &x);
+        num_drop(&x);
+        num_drop(&n);
+        n = t;
+    }
+    hy_push(d, n);
+}
+
+/* 흣: negates `h` numbers, and pushes the sum to stack `d` */
+static void hy_neg_sum(size_t cur, size_t h, size_t d) {
+    hy_num *v = hy_alloc(h * sizeof(hy_num)), n = num_int(0), t;
+    size_t i;
+    for (i = 0; i < h; i++) {
+        v[h - 1 - i] = hy_pop(cur);
+    }
+    for (i = 0; i < h; i++) {
+        num_minus(&v[i]);
+        t = num_add(&n, &v[i]);
+        num_drop(&n);
+        n = t;
+        hy_push(cur, v[i]);
+    }
+    free(v);
+    hy_push(d, n);
+}
+
+/* 흡: flips `h` numbers, and pushes the product to stack `d` */
+static void hy_flip_product(size_t cur, size_t h, size_t d) {
+    hy_num *v = hy_alloc(h * sizeof(hy_num)), n = num_int(1), t;
+    size_t i;
+    for (i = 0; i < h; i++) {
+        v[h - 1 - i] = hy_pop(cur);
+    }
+    for (i = 0; i < h; i++) {
+        num_flip(&v[i]);
+        t = num_mul(&n, &v[i]);
+        num_drop(&n);
+        n = t;
+        hy_push(cur, v[i]);
+    }
+    free(v);
+    hy_push(d, n);
+}
+
+/* 흑: pushes the top `h` times to stack `d` and moves to it */
+static size_t hy_dup(size_t cur, size_t h, size_t d) {
+    hy_num n = hy_pop(cur);
+    size_t i;
+    for (i = 0; i < h; i++) {
+        hy_push(d, num_clone(&n));
+    }
+    hy_push(cur, n);
+    return d;
+}
+
+/* Pops from the current stack and compares with the area count */
+static int hy_cmp_pop(size_t cur, uint64_t v) {
+    hy_num n = hy_pop(cur);
+    int c = num_cmp_int(&n, v);
+    num_drop(&n);
+    return c;
+}
+
+/* State of the heart, which is `state` if the heart is new */
+static size_t hy_point(uint64_t id, size_t state) {
+    return *map_entry(&hy_points, id, state);
+}
@@ -0,0 +1,197 @@
+// Rational number of BigInt, which is NaN when the denominator is zero
+function num(up, down = 1n) {
+    return { up, down };
+}
+
+const NAN = num(1n, 0n);
+
+function isNan(a) {
+    return a.down === 0n;
+}
+
+function gcd(a, b) {
+    if (a < 0n) a = -a;
+    if (b < 0n) b = -b;
+    while (b) [a, b] = [b, a % b];
+    return a;
+}
+
+// Divides by gcd and makes the denominator positive
+function reduce(up, down) {
+    const g = gcd(up, down);
+    if (g !== 0n) {
+        up /= g;
+        down /= g;
+    }
+    return down < 0n ? num(-up, -down) : num(up, down);
+}
+
+function add(a, b) {
+    if (isNan(a) || isNan(b)) return NAN;
+    return reduce(a.up * b.down + a.down * b.up, a.down * b.down);
+}
+
+function mul(a, b) {
+    if (isNan(a) || isNan(b)) return NAN;
+    return reduce(a.up * b.up, a.down * b.down);
+}
+
+function minus(a) {
+    return num(-a.up, a.down);
+}
+
+function flip(a) {
+    if (isNan(a)) return a;
+    return a.up < 0n ? num(-a.down, -a.up) : num(a.down, a.up);
+}
+
+// Compares with integer, where NaN is greater than any number
+function cmpInt(a, v) {
+    if (isNan(a)) return 1;
+    const r = v * a.down;
+    return a.up < r ? -1 : a.up > r ? 1 : 0;
+}
+
+// Thrown to stop the program, with message to stderr
+class Exit {
+    constructor(code, message = "") {
+        this.code = code;
+        this.message = message;
+    }
+}
+
+// Character of the floor if positive, otherwise the negated number
+function format(a) {
+    if (isNan(a)) return "너무 커엇...";
+    if (a.up >= 0n) {
+        const c = Number((a.up / a.down) & 0xffffffffn);
+        if (c > 0x10ffff || (c >= 0xd800 && c <= 0xdfff)) {
+            throw new Exit(101, `invalid character: ${c}\n`);
+        }
+        return String.fromCodePoint(c);
+    }
+    return a.down === 1n ? `${-a.up}` : `${-a.up}/${a.down}`;
+}
+
+class Machine {
+    constructor(size, input) {
+        this.size = size;
+        // without optimization, stacks are not renumbered, so they are made when used
+        this.stacks = size ? Array.from({ length: size }, () => []) : new Map();
+        this.points = new Map();
+        this.input = input;
+        this.pos = 0;
+        this.stdout = "";
+        this.stderr = "";
+    }
+
+    stack(idx) {
+        if (this.size) return idx < this.size ? this.stacks[idx] : null;
+        let s = this.stacks.get(idx);
+        if (!s) {
+            s = [];
+            this.stacks.set(idx, s);
+        }
+        return s;
+    }
+
+    // Reads a line of input and pushes its characters, so that the first one is on top
+    readLine(s) {
+        const i = this.input.indexOf("\n", this.pos);
+        const end = i < 0 ? this.input.length : i + 1;
+        const line = Array.from(this.input.slice(this.pos, end));
+        this.pos = end;
+        for (let j = line.length; j-- > 0; ) s.push(num(BigInt(line[j].codePointAt(0))));
+    }
+
+    pop(idx) {
+        if (idx === 1) throw new Exit(0);
+        if (idx === 2) throw new Exit(1);
+        const s = this.stack(idx);
+        if (!s) return NAN;
+        if (!s.length && idx === 0) this.readLine(s);
+        return s.length ? s.pop() : NAN;
+    }
+
+    push(idx, n) {
+        if (idx === 1) {
+            this.stdout += format(n);
+            return;
+        }
+        if (idx === 2) {
+            this.stderr += format(n);
+            return;
+        }
+        const s = this.stack(idx);
+        if (s && (s.length || !isNan(n))) s.push(n);
+    }
+
+    // Puts the number left by optimizing
+    restore(idx, n) {
+        this.stack(idx).push(n);
+    }
+
+    // 형: pushes `h * d` to the current stack
+    pushInt(cur, v) {
+        this.push(cur, num(v));
+    }
+
+    // 항: pops `h` numbers and pushes the sum to stack `d`
+    sum(cur, h, d) {
+        let n = num(0n);
+        for (let i = 0; i < h; i++) n = add(n, this.pop(cur));
+        this.push(d, n);
+    }
+
+    // 핫: pops `h` numbers and pushes the product to stack `d`
+    product(cur, h, d) {
+        let n = num(1n);
+        for (let i = 0; i < h; i++) n = mul(n, this.pop(cur));
+        this.push(d, n);
+    }
+
+    // 흣: negates `h` numbers, and pushes the sum to stack `d`
+    negSum(cur, h, d) {
+        const v = [];
+        for (let i = 0; i < h; i++) v.push(this.pop(cur));
+        let n = num(0n);
+        for (let i = h; i-- > 0; ) {
+            const x = minus(v[i]);
+            n = add(n, x);
+            this.push(cur, x);
+        }
+        this.push(d, n);
+    }
+
+    // 흡: flips `h` numbers, and pushes the product to stack `d`
+    flipProduct(cur, h, d) {
+        const v = [];
+        for (let i = 0; i < h; i++) v.push(this.pop(cur));
+        let n = num(1n);
+        for (let i = h; i-- > 0; ) {
+            const x = flip(v[i]);
+            n = mul(n, x);
+            this.push(cur, x);
+        }
+        this.push(d, n);
+    }
+
+    // 흑: pushes the top `h` times to stack `d` and moves to it
+    dup(cur, h, d) {
+        const n = this.pop(cur);
+        for (let i = 0; i < h; i++) this.push(d, n);
+        this.push(cur, n);
+        return d;
+    }
+
+    // Pops from the current stack and compares with the area count
+    cmpPop(cur, v) {
+        return cmpInt(this.pop(cur), v);
+    }
+
+    // State of the heart, which is `state` if the heart is new
+    point(id, state) {
+        if (!this.points.has(id)) this.points.set(id, state);
+        return this.points.get(id);
+    }
+}
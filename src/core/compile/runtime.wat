@@ -0,0 +1,805 @@
+  ;; Runtime of generated module
+  ;;
+  ;; Memory below 1024 keeps the lists of freed blocks, and constants are right after it.
+  ;; Blocks are allocated in power of two sizes, with the size class in the first word.
+  ;; Big integer is `[len, neg, limbs...]` with 32-bit limbs from the least significant one.
+  ;; Rational number is `[up, down]` of big integers, which is NaN when `down` is zero.
+  ;; Vector is `[data, len, cap, idx]` of 32-bit values, and a stack is a vector of numbers.
+
+  (global $stacks (mut i32) (i32.const 0))
+  (global $points (mut i32) (i32.const 0))
+  (global $point_len (mut i32) (i32.const 0))
+  (global $point_cap (mut i32) (i32.const 0))
+  ;; remainder of the last `$big_divmod`
+  (global $rem (mut i32) (i32.const 0))
+
+  (func $alloc (param $n i32) (result i32)
+    (local $k i32) (local $p i32)
+    (local.set $k (i32.const 4))
+    (block $found
+      (loop $next
+        (br_if $found
+          (i32.ge_u (i32.shl (i32.const 1) (local.get $k)) (i32.add (local.get $n) (i32.const 4))))
+        (local.set $k (i32.add (local.get $k) (i32.const 1)))
+        (br $next)))
+    (local.set $p (i32.load (i32.shl (local.get $k) (i32.const 2))))
+    (if (local.get $p)
+      (then
+        (i32.store (i32.shl (local.get $k) (i32.const 2)) (i32.load (local.get $p))))
+      (else
+        (local.set $p (global.get $heap))
+        (global.set $heap (i32.add (local.get $p) (i32.shl (i32.const 1) (local.get $k))))
+        (if (i32.gt_u (global.get $heap) (i32.shl (memory.size) (i32.const 16)))
+          (then
+            (if (i32.lt_s
+                  (memory.grow
+                    (i32.shr_u
+                      (i32.sub
+                        (i32.add (global.get $heap) (i32.const 0xffff))
+                        (i32.shl (memory.size) (i32.const 16)))
+                      (i32.const 16)))
+                  (i32.const 0))
+              (then
+                (call $exit (i32.const 101))
+                (unreachable)))))))
+    (i32.store (local.get $p) (local.get $k))
+    (i32.add (local.get $p) (i32.const 4)))
+
+  (func $free (param $q i32)
+    (local $p i32) (local $h i32)
+    (local.set $p (i32.sub (local.get $q) (i32.const 4)))
+    (local.set $h (i32.shl (i32.load (local.get $p)) (i32.const 2)))
+    (i32.store (local.get $p) (i32.load (local.get $h)))
+    (i32.store (local.get $h) (local.get $p)))
+
+  ;; Address of `i`th limb
+  (func $limb (param $p i32) (param $i i32) (result i32)
+    (i32.add (i32.add (local.get $p) (i32.const 8)) (i32.shl (local.get $i) (i32.const 2))))
+
+  (func $big_new (param $len i32) (result i32)
+    (local $p i32)
+    (local.set $p (call $alloc (i32.add (i32.const 8) (i32.shl (local.get $len) (i32.const 2)))))
+    (i32.store (local.get $p) (local.get $len))
+    (i32.store offset=4 (local.get $p) (i32.const 0))
+    (memory.fill
+      (i32.add (local.get $p) (i32.const 8))
+      (i32.const 0)
+      (i32.shl (local.get $len) (i32.const 2)))
+    (local.get $p))
+
+  (func $big_trim (param $p i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (i32.load (local.get $p))))
+        (br_if $done
+          (i32.load (call $limb (local.get $p) (i32.sub (i32.load (local.get $p)) (i32.const 1)))))
+        (i32.store (local.get $p) (i32.sub (i32.load (local.get $p)) (i32.const 1)))
+        (br $next)))
+    (if (i32.eqz (i32.load (local.get $p)))
+      (then
+        (i32.store offset=4 (local.get $p) (i32.const 0)))))
+
+  (func $big_from_u64 (param $v i64) (result i32)
+    (local $p i32)
+    (local.set $p (call $big_new (i32.const 2)))
+    (i32.store offset=8 (local.get $p) (i32.wrap_i64 (local.get $v)))
+    (i32.store offset=12 (local.get $p) (i32.wrap_i64 (i64.shr_u (local.get $v) (i64.const 32))))
+    (call $big_trim (local.get $p))
+    (local.get $p))
+
+  (func $big_clone (param $a i32) (result i32)
+    (local $p i32)
+    (local.set $p (call $big_new (i32.load (local.get $a))))
+    (memory.copy
+      (i32.add (local.get $p) (i32.const 4))
+      (i32.add (local.get $a) (i32.const 4))
+      (i32.add (i32.const 4) (i32.shl (i32.load (local.get $a)) (i32.const 2))))
+    (local.get $p))
+
+  (func $big_is_one (param $a i32) (result i32)
+    (i32.and
+      (i32.and
+        (i32.eq (i32.load (local.get $a)) (i32.const 1))
+        (i32.eqz (i32.load offset=4 (local.get $a))))
+      (i32.eq (i32.load offset=8 (local.get $a)) (i32.const 1))))
+
+  ;; Flips the sign unless zero
+  (func $negate (param $a i32)
+    (i32.store offset=4 (local.get $a)
+      (i32.and
+        (i32.eqz (i32.load offset=4 (local.get $a)))
+        (i32.ne (i32.load (local.get $a)) (i32.const 0)))))
+
+  (func $mag_cmp (param $a i32) (param $b i32) (result i32)
+    (local $i i32) (local $x i32) (local $y i32)
+    (if (i32.ne (i32.load (local.get $a)) (i32.load (local.get $b)))
+      (then
+        (return
+          (select (i32.const -1) (i32.const 1)
+            (i32.lt_u (i32.load (local.get $a)) (i32.load (local.get $b)))))))
+    (local.set $i (i32.load (local.get $a)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $i)))
+        (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+        (local.set $x (i32.load (call $limb (local.get $a) (local.get $i))))
+        (local.set $y (i32.load (call $limb (local.get $b) (local.get $i))))
+        (if (i32.ne (local.get $x) (local.get $y))
+          (then
+            (return (select (i32.const -1) (i32.const 1) (i32.lt_u (local.get $x) (local.get $y))))))
+        (br $next)))
+    (i32.const 0))
+
+  (func $big_cmp (param $a i32) (param $b i32) (result i32)
+    (local $c i32)
+    (if (i32.ne (i32.load offset=4 (local.get $a)) (i32.load offset=4 (local.get $b)))
+      (then
+        (return (select (i32.const -1) (i32.const 1) (i32.load offset=4 (local.get $a))))))
+    (local.set $c (call $mag_cmp (local.get $a) (local.get $b)))
+    (select
+      (i32.sub (i32.const 0) (local.get $c))
+      (local.get $c)
+      (i32.load offset=4 (local.get $a))))
+
+  (func $mag_add (param $a i32) (param $b i32) (result i32)
+    (local $t i32) (local $r i32) (local $i i32) (local $carry i64)
+    (if (i32.lt_u (i32.load (local.get $a)) (i32.load (local.get $b)))
+      (then
+        (local.set $t (local.get $a))
+        (local.set $a (local.get $b))
+        (local.set $b (local.get $t))))
+    (local.set $r (call $big_new (i32.add (i32.load (local.get $a)) (i32.const 1))))
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (i32.load (local.get $a))))
+        (local.set $carry
+          (i64.add (local.get $carry) (i64.load32_u (call $limb (local.get $a) (local.get $i)))))
+        (if (i32.lt_u (local.get $i) (i32.load (local.get $b)))
+          (then
+            (local.set $carry
+              (i64.add (local.get $carry) (i64.load32_u (call $limb (local.get $b) (local.get $i)))))))
+        (i32.store (call $limb (local.get $r) (local.get $i)) (i32.wrap_i64 (local.get $carry)))
+        (local.set $carry (i64.shr_u (local.get $carry) (i64.const 32)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (i32.store (call $limb (local.get $r) (local.get $i)) (i32.wrap_i64 (local.get $carry)))
+    (call $big_trim (local.get $r))
+    (local.get $r))
+
+  ;; r = |a| - |b| where |a| >= |b|, and `r` may be `a`
+  (func $mag_sub_into (param $r i32) (param $a i32) (param $b i32)
+    (local $i i32) (local $t i64) (local $borrow i64)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (i32.load (local.get $a))))
+        (local.set $t
+          (i64.sub (i64.load32_u (call $limb (local.get $a) (local.get $i))) (local.get $borrow)))
+        (if (i32.lt_u (local.get $i) (i32.load (local.get $b)))
+          (then
+            (local.set $t
+              (i64.sub (local.get $t) (i64.load32_u (call $limb (local.get $b) (local.get $i)))))))
+        (i32.store (call $limb (local.get $r) (local.get $i)) (i32.wrap_i64 (local.get $t)))
+        (local.set $borrow (i64.shr_u (local.get $t) (i64.const 63)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (call $big_trim (local.get $r)))
+
+  (func $mag_sub (param $a i32) (param $b i32) (result i32)
+    (local $r i32)
+    (local.set $r (call $big_new (i32.load (local.get $a))))
+    (call $mag_sub_into (local.get $r) (local.get $a) (local.get $b))
+    (local.get $r))
+
+  (func $big_add (param $a i32) (param $b i32) (result i32)
+    (local $r i32)
+    (if (i32.eq (i32.load offset=4 (local.get $a)) (i32.load offset=4 (local.get $b)))
+      (then
+        (local.set $r (call $mag_add (local.get $a) (local.get $b)))
+        (i32.store offset=4 (local.get $r) (i32.load offset=4 (local.get $a))))
+      (else
+        (if (i32.ge_s (call $mag_cmp (local.get $a) (local.get $b)) (i32.const 0))
+          (then
+            (local.set $r (call $mag_sub (local.get $a) (local.get $b)))
+            (i32.store offset=4 (local.get $r) (i32.load offset=4 (local.get $a))))
+          (else
+            (local.set $r (call $mag_sub (local.get $b) (local.get $a)))
+            (i32.store offset=4 (local.get $r) (i32.load offset=4 (local.get $b)))))))
+    (call $big_trim (local.get $r))
+    (local.get $r))
+
+  (func $big_mul (param $a i32) (param $b i32) (result i32)
+    (local $r i32) (local $i i32) (local $j i32) (local $x i64) (local $t i64) (local $carry i64)
+    (local.set $r
+      (call $big_new (i32.add (i32.load (local.get $a)) (i32.load (local.get $b)))))
+    (block $done_i
+      (loop $next_i
+        (br_if $done_i (i32.ge_u (local.get $i) (i32.load (local.get $a))))
+        (local.set $x (i64.load32_u (call $limb (local.get $a) (local.get $i))))
+        (local.set $carry (i64.const 0))
+        (local.set $j (i32.const 0))
+        (block $done_j
+          (loop $next_j
+            (br_if $done_j (i32.ge_u (local.get $j) (i32.load (local.get $b))))
+            (local.set $t
+              (i64.add
+                (i64.add
+                  (i64.mul (local.get $x) (i64.load32_u (call $limb (local.get $b) (local.get $j))))
+                  (i64.load32_u (call $limb (local.get $r) (i32.add (local.get $i) (local.get $j)))))
+                (local.get $carry)))
+            (i32.store
+              (call $limb (local.get $r) (i32.add (local.get $i) (local.get $j)))
+              (i32.wrap_i64 (local.get $t)))
+            (local.set $carry (i64.shr_u (local.get $t) (i64.const 32)))
+            (local.set $j (i32.add (local.get $j) (i32.const 1)))
+            (br $next_j)))
+        (i32.store
+          (call $limb (local.get $r) (i32.add (local.get $i) (i32.load (local.get $b))))
+          (i32.wrap_i64 (local.get $carry)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next_i)))
+    (i32.store offset=4 (local.get $r)
+      (i32.ne (i32.load offset=4 (local.get $a)) (i32.load offset=4 (local.get $b))))
+    (call $big_trim (local.get $r))
+    (local.get $r))
+
+  ;; Divides |a| by a small number in place and returns the remainder
+  (func $mag_div_small (param $a i32) (param $m i32) (result i32)
+    (local $i i32) (local $rem i64) (local $t i64)
+    (local.set $i (i32.load (local.get $a)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $i)))
+        (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+        (local.set $t
+          (i64.or
+            (i64.shl (local.get $rem) (i64.const 32))
+            (i64.load32_u (call $limb (local.get $a) (local.get $i)))))
+        (i32.store
+          (call $limb (local.get $a) (local.get $i))
+          (i32.wrap_i64 (i64.div_u (local.get $t) (i64.extend_i32_u (local.get $m)))))
+        (local.set $rem (i64.rem_u (local.get $t) (i64.extend_i32_u (local.get $m))))
+        (br $next)))
+    (call $big_trim (local.get $a))
+    (i32.wrap_i64 (local.get $rem)))
+
+  ;; r = r * 2 + bit, where `r` has room for one more limb
+  (func $mag_shl1 (param $r i32) (param $bit i32)
+    (local $i i32) (local $x i32) (local $carry i32)
+    (local.set $carry (local.get $bit))
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (i32.load (local.get $r))))
+        (local.set $x (i32.load (call $limb (local.get $r) (local.get $i))))
+        (i32.store
+          (call $limb (local.get $r) (local.get $i))
+          (i32.or (i32.shl (local.get $x) (i32.const 1)) (local.get $carry)))
+        (local.set $carry (i32.shr_u (local.get $x) (i32.const 31)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (if (local.get $carry)
+      (then
+        (i32.store (call $limb (local.get $r) (local.get $i)) (local.get $carry))
+        (i32.store (local.get $r) (i32.add (local.get $i) (i32.const 1))))))
+
+  ;; Quotient of |a| / |b| where b is not zero, and the remainder to `$rem`
+  (func $big_divmod (param $a i32) (param $b i32) (result i32)
+    (local $q i32) (local $r i32) (local $i i32)
+    (if (i32.lt_s (call $mag_cmp (local.get $a) (local.get $b)) (i32.const 0))
+      (then
+        (global.set $rem (call $big_clone (local.get $a)))
+        (i32.store offset=4 (global.get $rem) (i32.const 0))
+        (return (call $big_new (i32.const 0)))))
+    (if (i32.eq (i32.load (local.get $b)) (i32.const 1))
+      (then
+        (local.set $q (call $big_clone (local.get $a)))
+        (i32.store offset=4 (local.get $q) (i32.const 0))
+        (global.set $rem
+          (call $big_from_u64
+            (i64.extend_i32_u
+              (call $mag_div_small (local.get $q) (i32.load offset=8 (local.get $b))))))
+        (return (local.get $q))))
+    ;; shift and subtract bit by bit
+    (local.set $q (call $big_new (i32.load (local.get $a))))
+    (local.set $r (call $big_new (i32.add (i32.load (local.get $b)) (i32.const 1))))
+    (i32.store (local.get $r) (i32.const 0))
+    (local.set $i (i32.shl (i32.load (local.get $a)) (i32.const 5)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $i)))
+        (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+        (call $mag_shl1 (local.get $r)
+          (i32.and
+            (i32.shr_u
+              (i32.load (call $limb (local.get $a) (i32.shr_u (local.get $i) (i32.const 5))))
+              (local.get $i))
+            (i32.const 1)))
+        (if (i32.ge_s (call $mag_cmp (local.get $r) (local.get $b)) (i32.const 0))
+          (then
+            (call $mag_sub_into (local.get $r) (local.get $r) (local.get $b))
+            (i32.store
+              (call $limb (local.get $q) (i32.shr_u (local.get $i) (i32.const 5)))
+              (i32.or
+                (i32.load (call $limb (local.get $q) (i32.shr_u (local.get $i) (i32.const 5))))
+                (i32.shl (i32.const 1) (local.get $i))))))
+        (br $next)))
+    (call $big_trim (local.get $q))
+    (global.set $rem (local.get $r))
+    (local.get $q))
+
+  (func $big_gcd (param $a i32) (param $b i32) (result i32)
+    (local $x i32) (local $y i32)
+    (local.set $x (call $big_clone (local.get $a)))
+    (local.set $y (call $big_clone (local.get $b)))
+    (i32.store offset=4 (local.get $x) (i32.const 0))
+    (i32.store offset=4 (local.get $y) (i32.const 0))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (i32.load (local.get $y))))
+        (call $free (call $big_divmod (local.get $x) (local.get $y)))
+        (call $free (local.get $x))
+        (local.set $x (local.get $y))
+        (local.set $y (global.get $rem))
+        (br $next)))
+    (call $free (local.get $y))
+    (local.get $x))
+
+  (func $big_print (param $s i32) (param $a i32)
+    (local $t i32) (local $buf i32) (local $n i32)
+    (if (i32.load offset=4 (local.get $a))
+      (then
+        (call $write (local.get $s) (i32.const 45))))
+    (local.set $t (call $big_clone (local.get $a)))
+    (local.set $buf
+      (call $alloc (i32.add (i32.mul (i32.load (local.get $a)) (i32.const 10)) (i32.const 1))))
+    (loop $next
+      (i32.store8
+        (i32.add (local.get $buf) (local.get $n))
+        (i32.add (i32.const 48) (call $mag_div_small (local.get $t) (i32.const 10))))
+      (local.set $n (i32.add (local.get $n) (i32.const 1)))
+      (br_if $next (i32.load (local.get $t))))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $n)))
+        (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+        (call $write (local.get $s) (i32.load8_u (i32.add (local.get $buf) (local.get $n))))
+        (br $next)))
+    (call $free (local.get $buf))
+    (call $free (local.get $t)))
+
+  (func $num (param $up i32) (param $down i32) (result i32)
+    (local $p i32)
+    (local.set $p (call $alloc (i32.const 8)))
+    (i32.store (local.get $p) (local.get $up))
+    (i32.store offset=4 (local.get $p) (local.get $down))
+    (local.get $p))
+
+  (func $num_drop (param $n i32)
+    (call $free (i32.load (local.get $n)))
+    (call $free (i32.load offset=4 (local.get $n)))
+    (call $free (local.get $n)))
+
+  (func $num_clone (param $n i32) (result i32)
+    (call $num
+      (call $big_clone (i32.load (local.get $n)))
+      (call $big_clone (i32.load offset=4 (local.get $n)))))
+
+  (func $num_int (param $v i64) (result i32)
+    (call $num (call $big_from_u64 (local.get $v)) (call $big_from_u64 (i64.const 1))))
+
+  (func $num_nan (result i32)
+    (call $num (call $big_from_u64 (i64.const 1)) (call $big_new (i32.const 0))))
+
+  ;; Number from big integers in constants
+  (func $num_const (param $up i32) (param $down i32) (result i32)
+    (call $num (call $big_clone (local.get $up)) (call $big_clone (local.get $down))))
+
+  (func $num_is_nan (param $n i32) (result i32)
+    (i32.eqz (i32.load (i32.load offset=4 (local.get $n)))))
+
+  ;; Divides by gcd and makes the denominator positive
+  (func $num_reduce (param $n i32)
+    (local $g i32) (local $t i32)
+    (if (call $num_is_nan (local.get $n))
+      (then
+        (return)))
+    (local.set $g (call $big_gcd (i32.load (local.get $n)) (i32.load offset=4 (local.get $n))))
+    (if (i32.eqz (call $big_is_one (local.get $g)))
+      (then
+        (local.set $t (call $big_divmod (i32.load (local.get $n)) (local.get $g)))
+        (call $free (global.get $rem))
+        (i32.store offset=4 (local.get $t)
+          (i32.and
+            (i32.load offset=4 (i32.load (local.get $n)))
+            (i32.ne (i32.load (local.get $t)) (i32.const 0))))
+        (call $free (i32.load (local.get $n)))
+        (i32.store (local.get $n) (local.get $t))
+        (local.set $t (call $big_divmod (i32.load offset=4 (local.get $n)) (local.get $g)))
+        (call $free (global.get $rem))
+        (i32.store offset=4 (local.get $t) (i32.load offset=4 (i32.load offset=4 (local.get $n))))
+        (call $free (i32.load offset=4 (local.get $n)))
+        (i32.store offset=4 (local.get $n) (local.get $t))))
+    (call $free (local.get $g))
+    (if (i32.load offset=4 (i32.load offset=4 (local.get $n)))
+      (then
+        (i32.store offset=4 (i32.load offset=4 (local.get $n)) (i32.const 0))
+        (call $negate (i32.load (local.get $n))))))
+
+  (func $num_add (param $a i32) (param $b i32) (result i32)
+    (local $x i32) (local $y i32) (local $r i32)
+    (if (i32.or (call $num_is_nan (local.get $a)) (call $num_is_nan (local.get $b)))
+      (then
+        (return (call $num_nan))))
+    (local.set $x (call $big_mul (i32.load (local.get $a)) (i32.load offset=4 (local.get $b))))
+    (local.set $y (call $big_mul (i32.load offset=4 (local.get $a)) (i32.load (local.get $b))))
+    (local.set $r
+      (call $num
+        (call $big_add (local.get $x) (local.get $y))
+        (call $big_mul (i32.load offset=4 (local.get $a)) (i32.load offset=4 (local.get $b)))))
+    (call $free (local.get $x))
+    (call $free (local.get $y))
+    (call $num_reduce (local.get $r))
+    (local.get $r))
+
+  (func $num_mul (param $a i32) (param $b i32) (result i32)
+    (local $r i32)
+    (if (i32.or (call $num_is_nan (local.get $a)) (call $num_is_nan (local.get $b)))
+      (then
+        (return (call $num_nan))))
+    (local.set $r
+      (call $num
+        (call $big_mul (i32.load (local.get $a)) (i32.load (local.get $b)))
+        (call $big_mul (i32.load offset=4 (local.get $a)) (i32.load offset=4 (local.get $b)))))
+    (call $num_reduce (local.get $r))
+    (local.get $r))
+
+  (func $num_flip (param $n i32)
+    (local $t i32)
+    (if (call $num_is_nan (local.get $n))
+      (then
+        (return)))
+    (local.set $t (i32.load (local.get $n)))
+    (i32.store (local.get $n) (i32.load offset=4 (local.get $n)))
+    (i32.store offset=4 (local.get $n) (local.get $t))
+    (if (i32.load offset=4 (local.get $t))
+      (then
+        (i32.store offset=4 (local.get $t) (i32.const 0))
+        (call $negate (i32.load (local.get $n))))))
+
+  ;; Compares with integer, where NaN is greater than any number
+  (func $num_cmp_int (param $n i32) (param $v i64) (result i32)
+    (local $x i32) (local $y i32) (local $c i32)
+    (if (call $num_is_nan (local.get $n))
+      (then
+        (return (i32.const 1))))
+    (local.set $x (call $big_from_u64 (local.get $v)))
+    (local.set $y (call $big_mul (local.get $x) (i32.load offset=4 (local.get $n))))
+    (local.set $c (call $big_cmp (i32.load (local.get $n)) (local.get $y)))
+    (call $free (local.get $x))
+    (call $free (local.get $y))
+    (local.get $c))
+
+  ;; Prints the character of the floor if positive, otherwise prints the negated number
+  (func $num_print (param $s i32) (param $n i32)
+    (local $q i32) (local $c i32)
+    (if (call $num_is_nan (local.get $n))
+      (then
+        ;; 너무 커엇...
+        (call $write (local.get $s) (i32.const 0xb108))
+        (call $write (local.get $s) (i32.const 0xbb34))
+        (call $write (local.get $s) (i32.const 0x20))
+        (call $write (local.get $s) (i32.const 0xcee4))
+        (call $write (local.get $s) (i32.const 0xc5c7))
+        (call $write (local.get $s) (i32.const 0x2e))
+        (call $write (local.get $s) (i32.const 0x2e))
+        (call $write (local.get $s) (i32.const 0x2e))
+        (return)))
+    (if (i32.eqz (i32.load offset=4 (i32.load (local.get $n))))
+      (then
+        (local.set $q (call $big_divmod (i32.load (local.get $n)) (i32.load offset=4 (local.get $n))))
+        (call $free (global.get $rem))
+        (local.set $c (select (i32.load offset=8 (local.get $q)) (i32.const 0) (i32.load (local.get $q))))
+        (call $free (local.get $q))
+        (if (i32.or
+              (i32.gt_u (local.get $c) (i32.const 0x10ffff))
+              (i32.eq (i32.and (local.get $c) (i32.const 0xfffff800)) (i32.const 0xd800)))
+          (then
+            (call $exit (i32.const 101))
+            (unreachable)))
+        (call $write (local.get $s) (local.get $c))
+        (return)))
+    (call $negate (i32.load (local.get $n)))
+    (call $big_print (local.get $s) (i32.load (local.get $n)))
+    (call $negate (i32.load (local.get $n)))
+    (if (i32.eqz (call $big_is_one (i32.load offset=4 (local.get $n))))
+      (then
+        (call $write (local.get $s) (i32.const 47))
+        (call $big_print (local.get $s) (i32.load offset=4 (local.get $n))))))
+
+  (func $vec_new (result i32)
+    (local $v i32)
+    (local.set $v (call $alloc (i32.const 16)))
+    (memory.fill (local.get $v) (i32.const 0) (i32.const 16))
+    (local.get $v))
+
+  (func $vec_push (param $v i32) (param $x i32)
+    (local $d i32)
+    (if (i32.eq (i32.load offset=4 (local.get $v)) (i32.load offset=8 (local.get $v)))
+      (then
+        (i32.store offset=8 (local.get $v)
+          (select
+            (i32.shl (i32.load offset=8 (local.get $v)) (i32.const 1))
+            (i32.const 8)
+            (i32.load offset=8 (local.get $v))))
+        (local.set $d (call $alloc (i32.shl (i32.load offset=8 (local.get $v)) (i32.const 2))))
+        (if (i32.load (local.get $v))
+          (then
+            (memory.copy
+              (local.get $d)
+              (i32.load (local.get $v))
+              (i32.shl (i32.load offset=4 (local.get $v)) (i32.const 2)))
+            (call $free (i32.load (local.get $v)))))
+        (i32.store (local.get $v) (local.get $d))))
+    (i32.store
+      (i32.add (i32.load (local.get $v)) (i32.shl (i32.load offset=4 (local.get $v)) (i32.const 2)))
+      (local.get $x))
+    (i32.store offset=4 (local.get $v) (i32.add (i32.load offset=4 (local.get $v)) (i32.const 1))))
+
+  (func $vec_pop (param $v i32) (result i32)
+    (i32.store offset=4 (local.get $v) (i32.sub (i32.load offset=4 (local.get $v)) (i32.const 1)))
+    (i32.load
+      (i32.add (i32.load (local.get $v)) (i32.shl (i32.load offset=4 (local.get $v)) (i32.const 2)))))
+
+  (func $init
+    (if (global.get $size)
+      (then
+        (global.set $stacks (call $alloc (i32.shl (global.get $size) (i32.const 4))))
+        (memory.fill (global.get $stacks) (i32.const 0) (i32.shl (global.get $size) (i32.const 4))))))
+
+  (func $stack_get (param $idx i32) (result i32)
+    (local $i i32) (local $s i32)
+    (if (global.get $size)
+      (then
+        (if (i32.ge_u (local.get $idx) (global.get $size))
+          (then
+            (return (i32.const 0))))
+        (return (i32.add (global.get $stacks) (i32.shl (local.get $idx) (i32.const 4))))))
+    ;; without optimization, stacks are not renumbered, so they are made when used
+    (if (i32.eqz (global.get $stacks))
+      (then
+        (global.set $stacks (call $vec_new))))
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (i32.load offset=4 (global.get $stacks))))
+        (local.set $s
+          (i32.load (i32.add (i32.load (global.get $stacks)) (i32.shl (local.get $i) (i32.const 2)))))
+        (if (i32.eq (i32.load offset=12 (local.get $s)) (local.get $idx))
+          (then
+            (return (local.get $s))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (local.set $s (call $vec_new))
+    (i32.store offset=12 (local.get $s) (local.get $idx))
+    (call $vec_push (global.get $stacks) (local.get $s))
+    (local.get $s))
+
+  ;; Reads a line of input and pushes its characters, so that the first one is on top
+  (func $read_line (param $s i32)
+    (local $t i32) (local $c i32)
+    (local.set $t (call $vec_new))
+    (block $done
+      (loop $next
+        (local.set $c (call $read))
+        (br_if $done (i32.lt_s (local.get $c) (i32.const 0)))
+        (call $vec_push (local.get $t) (local.get $c))
+        (br_if $done (i32.eq (local.get $c) (i32.const 10)))
+        (br $next)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (i32.load offset=4 (local.get $t))))
+        (call $vec_push (local.get $s)
+          (call $num_int (i64.extend_i32_u (call $vec_pop (local.get $t)))))
+        (br $next)))
+    (if (i32.load (local.get $t))
+      (then
+        (call $free (i32.load (local.get $t)))))
+    (call $free (local.get $t)))
+
+  (func $pop (param $idx i32) (result i32)
+    (local $s i32)
+    (if (i32.eq (local.get $idx) (i32.const 1))
+      (then
+        (call $exit (i32.const 0))
+        (unreachable)))
+    (if (i32.eq (local.get $idx) (i32.const 2))
+      (then
+        (call $exit (i32.const 1))
+        (unreachable)))
+    (local.set $s (call $stack_get (local.get $idx)))
+    (if (i32.eqz (local.get $s))
+      (then
+        (return (call $num_nan))))
+    (if (i32.and (i32.eqz (i32.load offset=4 (local.get $s))) (i32.eqz (local.get $idx)))
+      (then
+        (call $read_line (local.get $s))))
+    (if (i32.eqz (i32.load offset=4 (local.get $s)))
+      (then
+        (return (call $num_nan))))
+    (call $vec_pop (local.get $s)))
+
+  (func $push (param $idx i32) (param $n i32)
+    (local $s i32)
+    (if (i32.or (i32.eq (local.get $idx) (i32.const 1)) (i32.eq (local.get $idx) (i32.const 2)))
+      (then
+        (call $num_print (local.get $idx) (local.get $n))
+        (call $num_drop (local.get $n))
+        (return)))
+    (local.set $s (call $stack_get (local.get $idx)))
+    (if (i32.eqz (local.get $s))
+      (then
+        (call $num_drop (local.get $n))
+        (return)))
+    (if (i32.or (i32.load offset=4 (local.get $s)) (i32.eqz (call $num_is_nan (local.get $n))))
+      (then
+        (call $vec_push (local.get $s) (local.get $n)))
+      (else
+        (call $num_drop (local.get $n)))))
+
+  ;; Puts the number left by optimizing
+  (func $restore (param $idx i32) (param $n i32)
+    (call $vec_push (call $stack_get (local.get $idx)) (local.get $n)))
+
+  ;; Writes `len` code points from `p`
+  (func $write_str (param $s i32) (param $p i32) (param $len i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $len)))
+        (call $write (local.get $s) (i32.load (local.get $p)))
+        (local.set $p (i32.add (local.get $p) (i32.const 4)))
+        (local.set $len (i32.sub (local.get $len) (i32.const 1)))
+        (br $next))))
+
+  ;; 형: pushes `h * d` to the current stack
+  (func $push_int (param $cur i32) (param $v i64)
+    (call $push (local.get $cur) (call $num_int (local.get $v))))
+
+  ;; 항: pops `h` numbers and pushes the sum to stack `d`
+  (func $sum (param $cur i32) (param $h i32) (param $d i32)
+    (local $n i32) (local $x i32) (local $t i32)
+    (local.set $n (call $num_int (i64.const 0)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $h)))
+        (local.set $x (call $pop (local.get $cur)))
+        (local.set $t (call $num_add (local.get $n) (local.get $x)))
+        (call $num_drop (local.get $x))
+        (call $num_drop (local.get $n))
+        (local.set $n (local.get $t))
+        (local.set $h (i32.sub (local.get $h) (i32.const 1)))
+        (br $next)))
+    (call $push (local.get $d) (local.get $n)))
+
+  ;; 핫: pops `h` numbers and pushes the product to stack `d`
+  (func $product (param $cur i32) (param $h i32) (param $d i32)
+    (local $n i32) (local $x i32) (local $t i32)
+    (local.set $n (call $num_int (i64.const 1)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $h)))
+        (local.set $x (call $pop (local.get $cur)))
+        (local.set $t (call $num_mul (local.get $n) (local.get $x)))
+        (call $num_drop (local.get $x))
+        (call $num_drop (local.get $n))
+        (local.set $n (local.get $t))
+        (local.set $h (i32.sub (local.get $h) (i32.const 1)))
+        (br $next)))
+    (call $push (local.get $d) (local.get $n)))
+
+  ;; 흣: negates `h` numbers, and pushes the sum to stack `d`
+  (func $neg_sum (param $cur i32) (param $h i32) (param $d i32)
+    (local $v i32) (local $i i32) (local $n i32) (local $x i32) (local $t i32)
+    (local.set $v (call $alloc (i32.shl (local.get $h) (i32.const 2))))
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $h)))
+        (i32.store
+          (i32.add (local.get $v) (i32.shl (local.get $i) (i32.const 2)))
+          (call $pop (local.get $cur)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (local.set $n (call $num_int (i64.const 0)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $i)))
+        (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+        (local.set $x (i32.load (i32.add (local.get $v) (i32.shl (local.get $i) (i32.const 2)))))
+        (call $negate (i32.load (local.get $x)))
+        (local.set $t (call $num_add (local.get $n) (local.get $x)))
+        (call $num_drop (local.get $n))
+        (local.set $n (local.get $t))
+        (call $push (local.get $cur) (local.get $x))
+        (br $next)))
+    (call $free (local.get $v))
+    (call $push (local.get $d) (local.get $n)))
+
+  ;; 흡: flips `h` numbers, and pushes the product to stack `d`
+  (func $flip_product (param $cur i32) (param $h i32) (param $d i32)
+    (local $v i32) (local $i i32) (local $n i32) (local $x i32) (local $t i32)
+    (local.set $v (call $alloc (i32.shl (local.get $h) (i32.const 2))))
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $h)))
+        (i32.store
+          (i32.add (local.get $v) (i32.shl (local.get $i) (i32.const 2)))
+          (call $pop (local.get $cur)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (local.set $n (call $num_int (i64.const 1)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $i)))
+        (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+        (local.set $x (i32.load (i32.add (local.get $v) (i32.shl (local.get $i) (i32.const 2)))))
+        (call $num_flip (local.get $x))
+        (local.set $t (call $num_mul (local.get $n) (local.get $x)))
+        (call $num_drop (local.get $n))
+        (local.set $n (local.get $t))
+        (call $push (local.get $cur) (local.get $x))
+        (br $next)))
+    (call $free (local.get $v))
+    (call $push (local.get $d) (local.get $n)))
+
+  ;; 흑: pushes the top `h` times to stack `d` and moves to it
+  (func $dup (param $cur i32) (param $h i32) (param $d i32) (result i32)
+    (local $n i32)
+    (local.set $n (call $pop (local.get $cur)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $h)))
+        (call $push (local.get $d) (call $num_clone (local.get $n)))
+        (local.set $h (i32.sub (local.get $h) (i32.const 1)))
+        (br $next)))
+    (call $push (local.get $cur) (local.get $n))
+    (local.get $d))
+
+  ;; Pops from the current stack and compares with the area count
+  (func $cmp_pop (param $cur i32) (param $v i64) (result i32)
+    (local $n i32) (local $c i32)
+    (local.set $n (call $pop (local.get $cur)))
+    (local.set $c (call $num_cmp_int (local.get $n) (local.get $v)))
+    (call $num_drop (local.get $n))
+    (local.get $c))
+
+  ;; State of the heart, which is `state` if the heart is new.
+  ;; Points are records of `[id: i64, state: i32]` in 16 bytes.
+  (func $point (param $id i64) (param $state i32) (result i32)
+    (local $i i32) (local $p i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (global.get $point_len)))
+        (local.set $p (i32.add (global.get $points) (i32.shl (local.get $i) (i32.const 4))))
+        (if (i64.eq (i64.load (local.get $p)) (local.get $id))
+          (then
+            (return (i32.load offset=8 (local.get $p)))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (if (i32.eq (global.get $point_len) (global.get $point_cap))
+      (then
+        (global.set $point_cap
+          (select
+            (i32.shl (global.get $point_cap) (i32.const 1))
+            (i32.const 8)
+            (global.get $point_cap)))
+        (local.set $p (call $alloc (i32.shl (global.get $point_cap) (i32.const 4))))
+        (if (global.get $points)
+          (then
+            (memory.copy
+              (local.get $p)
+              (global.get $points)
+              (i32.shl (global.get $point_len) (i32.const 4)))
+            (call $free (global.get $points))))
+        (global.set $points (local.get $p))))
+    (local.set $p (i32.add (global.get $points) (i32.shl (global.get $point_len) (i32.const 4))))
+    (i64.store (local.get $p) (local.get $id))
+    (i32.store offset=8 (local.get $p) (local.get $state))
+    (global.set $point_len (i32.add (global.get $point_len) (i32.const 1)))
+    (local.get $state))
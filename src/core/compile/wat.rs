@@ -0,0 +1,303 @@
+use crate::core::area::Area;
+use crate::core::code::{self, Code};
+use crate::core::compile::{make_indent, Layout};
+use crate::core::state::{Output, State};
+use crate::number::num::Num;
+
+/// Runtime of generated code: allocator, big integer, rational number, stacks and point table
+const RUNTIME: &str = include_str!("runtime.wat");
+
+/// Address of data segment, since the memory below is used by the allocator
+const DATA_START: usize = 1024;
+
+/// Constants and strings placed in the data segment of the module
+struct Data {
+    bytes: Vec<u8>,
+}
+
+impl Data {
+    /// Address of next value
+    fn next(&self) -> usize {
+        DATA_START + self.bytes.len()
+    }
+
+    fn push_u32(&mut self, v: u32) {
+        self.bytes.extend_from_slice(&v.to_le_bytes());
+    }
+
+    /// Puts big integer in decimal as `[len, neg, limbs...]`, returning the address
+    fn big(&mut self, s: &str) -> usize {
+        let (neg, digits) = match s.strip_prefix('-') {
+            Some(t) => (true, t),
+            None => (false, s),
+        };
+        let mut limbs = Vec::<u32>::new();
+        for c in digits.bytes() {
+            let mut carry = (c - b'0') as u64;
+            for l in limbs.iter_mut() {
+                let t = *l as u64 * 10 + carry;
+                *l = t as u32;
+                carry = t >> 32;
+            }
+            if carry != 0 {
+                limbs.push(carry as u32);
+            }
+        }
+        let res = self.next();
+        self.push_u32(limbs.len() as u32);
+        self.push_u32((neg && !limbs.is_empty()) as u32);
+        for l in limbs {
+            self.push_u32(l);
+        }
+        res
+    }
+
+    /// Puts code points of the string, returning the address and the length
+    fn string(&mut self, s: &str) -> (usize, usize) {
+        let res = self.next();
+        for c in s.chars() {
+            self.push_u32(c as u32);
+        }
+        (res, s.chars().count())
+    }
+
+    /// Makes data string literal, escaping every byte
+    fn literal(&self) -> String {
+        let mut res = String::from("\"");
+        for b in &self.bytes {
+            res.push_str(&format!("\\{:02x}", b));
+        }
+        res.push('"');
+        res
+    }
+}
+
+/// Makes expression of `Num` from the value, putting its parts in the data segment
+fn num_expr(data: &mut Data, n: &Num) -> String {
+    if n.is_nan() {
+        return String::from("(call $num_nan)");
+    }
+    let s = n.to_string();
+    let (up, down) = match s.split_once('/') {
+        Some((up, down)) => (data.big(up), data.big(down)),
+        None => (data.big(&s), data.big("1")),
+    };
+    format!("(call $num_const (i32.const {}) (i32.const {}))", up, down)
+}
+
+/// Makes WAT instructions from command, which call functions of the runtime.
+/// Constants are stored in `data` and built from there.
+fn command(data: &mut Data, indent: usize, c: &impl Code) -> String {
+    let h = c.get_hangul_count();
+    let d = c.get_dot_count();
+    let args = format!("(local.get $cur) (i32.const {}) (i32.const {})", h, d);
+    format!(
+        "{}{}",
+        match c.get_type() {
+            0 => format!(
+                "\n{}(call $push_int (local.get $cur) (i64.const {}))",
+                make_indent(indent),
+                h * d
+            ),
+            1 => format!("\n{}(call $sum {})", make_indent(indent), args),
+            2 => format!("\n{}(call $product {})", make_indent(indent), args),
+            3 => format!("\n{}(call $neg_sum {})", make_indent(indent), args),
+            4 => format!("\n{}(call $flip_product {})", make_indent(indent), args),
+            5 => format!(
+                "\n{}(local.set $cur (call $dup {}))",
+                make_indent(indent),
+                args
+            ),
+            code::PUSH_CONST => format!(
+                "\n{}(call $push (i32.const {}) {})",
+                make_indent(indent),
+                d,
+                num_expr(data, c.get_value().unwrap())
+            ),
+            code::ACCELERATE => String::new(),
+            // MOVE_TO
+            _ => format!(
+                "\n{}(local.set $cur (i32.const {}))",
+                make_indent(indent),
+                d
+            ),
+        },
+        area(indent, c.get_area(), c.get_area_count())
+    )
+}
+
+/// Makes code from area as `if` blocks on `$cmp_pop`,
+/// where jumping is a branch to the dispatching loop.
+fn area(mut indent: usize, a: &Area, cnt: usize) -> String {
+    let mut st = vec![(a, &Area::Nil, false)];
+    let mut res = String::new();
+    loop {
+        while let Area::Val { type_, left, right } = st.last().unwrap().0 {
+            if *type_ <= 1 {
+                st.push((left, right, false));
+                let cmp = format!("(call $cmp_pop (local.get $cur) (i64.const {}))", cnt);
+                res.push_str(&format!(
+                    "\n{}(if {} (then",
+                    make_indent(indent),
+                    if *type_ == 0 {
+                        format!("(i32.lt_s {} (i32.const 0))", cmp)
+                    } else {
+                        format!("(i32.eqz {})", cmp)
+                    }
+                ));
+                indent += 1;
+                continue;
+            } else {
+                if *type_ < 13 {
+                    res.push_str(&format!(
+                        "\n{0}(local.set $v (call $point (i64.const {1}) (local.get $state)))\
+                         \n{0}(if (i32.ne (local.get $v) (local.get $state)) (then\
+                         \n{0}    (local.set $last (local.get $state))\
+                         \n{0}    (local.set $state (local.get $v))\
+                         \n{0}    (br $dispatch)\
+                         \n{0}))",
+                        make_indent(indent),
+                        ((cnt as u64) << 4) + *type_ as u64
+                    ));
+                } else {
+                    res.push_str(&format!(
+                        "\n{0}(if (i32.ge_s (local.get $last) (i32.const 0)) (then\
+                         \n{0}    (local.set $state (local.get $last))\
+                         \n{0}    (br $dispatch)\
+                         \n{0}))",
+                        make_indent(indent)
+                    ));
+                }
+                break;
+            }
+        }
+
+        while st.len() > 1 && st.last().unwrap().2 {
+            st.pop();
+            indent -= 1;
+            res.push_str(&format!("\n{}))", make_indent(indent)));
+        }
+
+        if st.len() > 1 {
+            let (left, right, _) = st.pop().unwrap();
+            st.push((right, left, true));
+            res.push_str(&format!("\n{}) (else", make_indent(indent - 1)));
+        } else {
+            break res;
+        }
+    }
+}
+
+/// Makes WebAssembly text module from parsed hyeong code.
+/// States are dispatched with `br_table` over nested blocks, where each state falls through to the next one,
+/// and numbers are calculated with the runtime written in the same module.
+///
+/// The module imports `read` returning next code point of input or `-1` at the end,
+/// `write` taking the stream (1 for stdout, 2 for stderr) and a code point,
+/// and `exit` taking the exit code, which should not return, from `hyeong`.
+/// It exports `memory` and `main` running the program.
+pub fn build_source<T>(state: T, code: &[T::CodeType], level: u8) -> String
+where
+    T: State,
+{
+    let layout = Layout::new(state, code, level);
+    let mut data = Data { bytes: Vec::new() };
+    let mut body = String::from(
+        "
+  (func $main (export \"main\")
+    (local $state i32) (local $last i32) (local $cur i32) (local $v i32)
+    (local.set $last (i32.const -1))
+    (local.set $cur (i32.const 3))
+    (call $init)",
+    );
+
+    for o in &layout.output {
+        let (stream, s) = match o {
+            Output::Stdout(s) => (1, s),
+            Output::Stderr(s) => (2, s),
+        };
+        let (p, len) = data.string(s);
+        body.push_str(&format!(
+            "\n    (call $write_str (i32.const {}) (i32.const {}) (i32.const {}))",
+            stream, p, len
+        ));
+    }
+
+    let codes = &layout.states;
+    if !codes.is_empty() {
+        if layout.restore {
+            for (i, v) in &layout.stacks {
+                for n in v {
+                    body.push_str(&format!(
+                        "\n    (call $restore (i32.const {}) {})",
+                        i,
+                        num_expr(&mut data, n)
+                    ));
+                }
+            }
+            body.push_str(&format!(
+                "\n    (local.set $cur (i32.const {}))",
+                layout.cur
+            ));
+            if let Some(v) = layout.last {
+                body.push_str(&format!("\n    (local.set $last (i32.const {}))", v));
+            }
+            for (a, b) in &layout.points {
+                body.push_str(&format!(
+                    "\n    (drop (call $point (i64.const {}) (i32.const {})))",
+                    a, b
+                ));
+            }
+            body.push_str(&format!(
+                "\n    (local.set $state (i32.const {}))",
+                layout.start
+            ));
+        }
+
+        body.push_str("\n    (block $end\n      (loop $dispatch");
+        for i in (0..codes.len()).rev() {
+            body.push_str(&format!("\n        block $s{}", i));
+        }
+        body.push_str("\n        local.get $state\n        br_table");
+        for i in 0..codes.len() {
+            body.push_str(&format!(" $s{}", i));
+        }
+        body.push_str(" $end");
+
+        for (i, c) in codes.iter().enumerate() {
+            body.push_str(&format!(
+                "\n        end\
+                 \n        ;; state {0}\
+                 \n        (local.set $state (i32.const {0}))",
+                i
+            ));
+            for item in c {
+                body.push_str(&command(&mut data, 2, item));
+            }
+        }
+        body.push_str("))");
+    }
+    body.push_str(")\n");
+
+    let heap = data.next().div_ceil(16) * 16;
+    format!(
+        "(module
+  (import \"hyeong\" \"read\" (func $read (result i32)))
+  (import \"hyeong\" \"write\" (func $write (param i32 i32)))
+  (import \"hyeong\" \"exit\" (func $exit (param i32)))
+  (memory (export \"memory\") {})
+  (global $size i32 (i32.const {}))
+  (global $heap (mut i32) (i32.const {}))
+  (data (i32.const {}) {})
+
+{}{})
+",
+        heap / 65536 + 1,
+        if level != 0 { layout.stack_size } else { 0 },
+        heap,
+        DATA_START,
+        data.literal(),
+        RUNTIME,
+        body
+    )
+}
@@ -1,5 +1,5 @@
 use crate::core::area;
-use crate::core::code::Code;
+use crate::core::code::{self, Code};
 use crate::core::state::State;
 use crate::number::num::Num;
 use crate::util::error::Error;
@@ -109,6 +109,45 @@ where
     }
 }
 
+/// Runs the loop of `ACCELERATE` command at `loc` in closed form.
+/// Returns the next position of code, or `None` if the loop should run as usual.
+///
+/// The command is right before the command jumping back,
+/// so that command is the last one executed in the loop.
+pub fn run_loop<T>(state: &mut T, code: &T::CodeType, loc: usize) -> Option<usize>
+where
+    T: State,
+{
+    let a = code.get_loop()?;
+    if state.current_stack() != code.get_dot_count()
+        || state.get_point(a.id).map(|p| p + a.back) != Some(loc)
+    {
+        return None;
+    }
+    let l = &a.body;
+
+    let mut x = Vec::new();
+    for &(s, w) in &l.windows {
+        let st = state.get_stack(s);
+        if st.len() < w {
+            return None;
+        }
+        x.extend(st[st.len() - w..].iter().cloned());
+    }
+
+    let (k, res) = l.run(&x)?;
+    for (&(s, w), v) in l.windows.iter().zip(res) {
+        let st = state.get_stack(s);
+        st.truncate(st.len() - w);
+        st.extend(v);
+    }
+    if k != Num::zero() {
+        state.set_latest_loc(loc + 1);
+    }
+    state.set_current_stack(a.cur);
+    Some(loc + 2)
+}
+
 /// Executes only one line of code and return next position of code
 ///
 /// # Examples
@@ -139,14 +178,54 @@ where
     T: State,
 {
     let code = (*state.get_code(cur_loc)).clone();
-    let mut cur_stack = state.current_stack();
+    if let Some(next) = execute_command(ipt, out, err, &mut state, &code, cur_loc)? {
+        return Ok((state, next));
+    }
+    let next = execute_area(ipt, out, err, &mut state, &code, cur_loc)?;
+    Ok((state, next))
+}
 
+/// Executes the command of `code` at `cur_loc`, without its area
+///
+/// It returns the next position of code if the command moves there by itself,
+/// which only happens when a loop runs in closed form.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::util::io::{CustomReader, CustomWriter};
+/// use hyeong::core::state::{UnOptState, State};
+/// use hyeong::core::{parse, execute};
+///
+/// let mut a = CustomReader::new(String::from("0"));
+/// let mut b = CustomWriter::new(|_| Result::Ok(()));
+/// let mut c = CustomWriter::new(|_| Result::Ok(()));
+/// let mut s = UnOptState::new();
+/// let t = parse::parse(String::from("형... 흑....♥"));
+///
+/// execute::execute_command(&mut a, &mut b, &mut c, &mut s, &t[0], 0).unwrap();
+/// assert_eq!(None, execute::execute_command(&mut a, &mut b, &mut c, &mut s, &t[1], 1).unwrap());
+/// assert_eq!(4, s.current_stack());
+/// assert_eq!(None, s.get_point((4 << 4) + 2));
+/// ```
+pub fn execute_command<T>(
+    ipt: &mut impl ReadLine,
+    out: &mut impl Write,
+    err: &mut impl Write,
+    state: &mut T,
+    code: &T::CodeType,
+    cur_loc: usize,
+) -> Result<Option<usize>, Error>
+where
+    T: State,
+{
+    let cur_stack = state.current_stack();
     match code.get_type() {
         0 => {
             push_stack_wrap(
                 out,
                 err,
-                &mut state,
+                state,
                 cur_stack,
                 &Num::from_num(code.get_hangul_count() as isize)
                     * &Num::from_num(code.get_dot_count() as isize),
@@ -155,23 +234,23 @@ where
         1 => {
             let mut n = Num::zero();
             for _ in 0..code.get_hangul_count() {
-                n += &pop_stack_wrap(ipt, out, err, &mut state, cur_stack)?;
+                n += &pop_stack_wrap(ipt, out, err, state, cur_stack)?;
             }
-            push_stack_wrap(out, err, &mut state, code.get_dot_count(), n)?;
+            push_stack_wrap(out, err, state, code.get_dot_count(), n)?;
         }
         2 => {
             let mut n = Num::one();
             for _ in 0..code.get_hangul_count() {
-                n *= &pop_stack_wrap(ipt, out, err, &mut state, cur_stack)?;
+                n *= &pop_stack_wrap(ipt, out, err, state, cur_stack)?;
             }
-            push_stack_wrap(out, err, &mut state, code.get_dot_count(), n)?;
+            push_stack_wrap(out, err, state, code.get_dot_count(), n)?;
         }
         3 => {
             let mut n = Num::zero();
             let mut v = Vec::with_capacity(code.get_hangul_count());
 
             for _ in 0..code.get_hangul_count() {
-                v.push(pop_stack_wrap(ipt, out, err, &mut state, cur_stack)?);
+                v.push(pop_stack_wrap(ipt, out, err, state, cur_stack)?);
             }
 
             v.reverse();
@@ -179,17 +258,17 @@ where
             for mut x in v {
                 x.minus();
                 n += &x;
-                push_stack_wrap(out, err, &mut state, cur_stack, x)?;
+                push_stack_wrap(out, err, state, cur_stack, x)?;
             }
 
-            push_stack_wrap(out, err, &mut state, code.get_dot_count(), n)?;
+            push_stack_wrap(out, err, state, code.get_dot_count(), n)?;
         }
         4 => {
             let mut n = Num::one();
             let mut v = Vec::with_capacity(code.get_hangul_count());
 
             for _ in 0..code.get_hangul_count() {
-                v.push(pop_stack_wrap(ipt, out, err, &mut state, cur_stack)?);
+                v.push(pop_stack_wrap(ipt, out, err, state, cur_stack)?);
             }
 
             v.reverse();
@@ -197,25 +276,57 @@ where
             for mut x in v {
                 x.flip();
                 n *= &x;
-                push_stack_wrap(out, err, &mut state, cur_stack, x)?;
+                push_stack_wrap(out, err, state, cur_stack, x)?;
             }
 
-            push_stack_wrap(out, err, &mut state, code.get_dot_count(), n)?;
+            push_stack_wrap(out, err, state, code.get_dot_count(), n)?;
         }
-        // 5
-        _ => {
-            let n = pop_stack_wrap(ipt, out, err, &mut state, cur_stack)?;
+        5 => {
+            let n = pop_stack_wrap(ipt, out, err, state, cur_stack)?;
             for _ in 0..code.get_hangul_count() {
-                push_stack_wrap(out, err, &mut state, code.get_dot_count(), n.clone())?;
+                push_stack_wrap(out, err, state, code.get_dot_count(), n.clone())?;
             }
-            push_stack_wrap(out, err, &mut state, cur_stack, n)?;
+            push_stack_wrap(out, err, state, cur_stack, n)?;
             state.set_current_stack(code.get_dot_count());
         }
+        code::PUSH_CONST => {
+            push_stack_wrap(
+                out,
+                err,
+                state,
+                code.get_dot_count(),
+                code.get_value().unwrap().clone(),
+            )?;
+        }
+        code::ACCELERATE => {
+            if let Some(next) = run_loop(state, code, cur_loc) {
+                return Ok(Some(next));
+            }
+        }
+        // MOVE_TO
+        _ => state.set_current_stack(code.get_dot_count()),
     }
+    Ok(None)
+}
 
-    cur_stack = state.current_stack();
+/// Evaluates the area of `code` at `cur_loc` and returns the next position of code
+///
+/// It pops from the current stack when the area needs a value,
+/// and sets or jumps to the point of the heart.
+pub fn execute_area<T>(
+    ipt: &mut impl ReadLine,
+    out: &mut impl Write,
+    err: &mut impl Write,
+    state: &mut T,
+    code: &T::CodeType,
+    cur_loc: usize,
+) -> Result<usize, Error>
+where
+    T: State,
+{
+    let cur_stack = state.current_stack();
     let area_type = area::calc(code.get_area(), code.get_area_count(), || {
-        pop_stack_wrap(ipt, out, err, &mut state, cur_stack)
+        pop_stack_wrap(ipt, out, err, state, cur_stack)
     })?;
 
     if area_type != 0 {
@@ -225,17 +336,17 @@ where
                 Some(value) => {
                     if cur_loc != value {
                         state.set_latest_loc(cur_loc);
-                        return Ok((state, value));
+                        return Ok(value);
                     }
                 }
                 None => state.set_point(id, cur_loc),
             }
         } else if let Some(loc) = state.get_latest_loc() {
-            return Ok((state, loc));
+            return Ok(loc);
         }
     }
 
-    Ok((state, cur_loc + 1))
+    Ok(cur_loc + 1)
 }
 
 /// Execute from new code until needs new code or finish
@@ -269,6 +380,14 @@ where
     let mut cur_loc = state.push_code((*code).clone());
     let length = cur_loc + 1;
 
+    // `ACCELERATE` skips the command after it, so it waits for that command
+    if code.get_type() == code::ACCELERATE {
+        return Ok(state);
+    }
+    if cur_loc > 0 && state.get_code(cur_loc - 1).get_type() == code::ACCELERATE {
+        cur_loc -= 1;
+    }
+
     while cur_loc < length {
         let (new_state, new_loc) = execute_one(ipt, out, err, state, cur_loc)?;
         state = new_state;
@@ -5,3 +5,4 @@ pub mod execute;
 pub mod optimize;
 pub mod parse;
 pub mod state;
+pub mod verify;
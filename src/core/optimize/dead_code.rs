@@ -0,0 +1,282 @@
+use crate::core::area::Area;
+use crate::core::code::{self, Code, OptCode};
+use crate::core::optimize::{Pass, PassStats, Program};
+use crate::core::state::State;
+use crate::util::error::Error;
+use std::collections::{BTreeSet, HashMap};
+
+/// Collect the results of area: `0` for `Nil`, otherwise the type of heart
+fn area_results(area: &Area, res: &mut BTreeSet<u8>) {
+    match area {
+        Area::Val { type_, left, right } => {
+            if *type_ <= 1 {
+                area_results(left, res);
+                area_results(right, res);
+            } else {
+                res.insert(*type_);
+            }
+        }
+        Area::Nil => {
+            res.insert(0);
+        }
+    }
+}
+
+/// Check if area pops from the current stack
+pub(crate) fn area_pops(area: &Area) -> bool {
+    matches!(area, Area::Val { type_, .. } if *type_ <= 1)
+}
+
+/// Check if command pops from the current stack
+pub(crate) fn command_pops(code: &impl Code) -> bool {
+    code.get_type() == 5 || (code.get_type() != 0 && code.get_hangul_count() > 0)
+}
+
+/// Id of point table, same as the one in `execute`
+fn point_id(code: &OptCode, type_: u8) -> u128 {
+    ((code.get_area_count() as u128) << 4) + type_ as u128
+}
+
+/// Replace hearts in `ids` to `Nil`. Returns true if anything is replaced.
+fn remove_hearts(area: &mut Area, area_count: usize, ids: &BTreeSet<u128>) -> bool {
+    match area {
+        Area::Val { type_, left, right } => {
+            if *type_ <= 1 {
+                let l = remove_hearts(left, area_count, ids);
+                let r = remove_hearts(right, area_count, ids);
+                l || r
+            } else if ids.contains(&(((area_count as u128) << 4) + *type_ as u128)) {
+                *area = Area::Nil;
+                true
+            } else {
+                false
+            }
+        }
+        Area::Nil => false,
+    }
+}
+
+/// Result of reachability analysis
+pub(super) struct Reach {
+    /// possible current stacks when each command starts
+    pub(super) cur: Vec<BTreeSet<usize>>,
+    /// whether each command can move on to the next command
+    pub(super) falls: Vec<bool>,
+    /// commands that can run right after each command
+    pub(super) next: Vec<BTreeSet<usize>>,
+}
+
+/// Reachability analysis over the heart-jump graph
+///
+/// Jump edges are over-approximated: a heart can jump to any command with the same point id,
+/// and `♡` can jump to any command that has a heart.
+/// A point that is surely set before a command is reached makes that heart always jump.
+pub(super) fn analyze(state: &impl State, code: &[OptCode], start: usize) -> Reach {
+    let n = code.len();
+    let results = code
+        .iter()
+        .map(|c| {
+            let mut res = BTreeSet::new();
+            area_results(c.get_area(), &mut res);
+            res
+        })
+        .collect::<Vec<_>>();
+
+    let mut targets: HashMap<u128, Vec<usize>> = HashMap::new();
+    let mut jumps = Vec::new();
+    for (i, c) in code.iter().enumerate() {
+        let mut has_heart = false;
+        for &t in &results[i] {
+            if (2..13).contains(&t) {
+                targets.entry(point_id(c, t)).or_default().push(i);
+                has_heart = true;
+            }
+        }
+        if has_heart {
+            jumps.push(i);
+        }
+    }
+
+    // point that is surely set for commands after the location
+    let mut sure: HashMap<u128, usize> = HashMap::new();
+    for (i, c) in code.iter().enumerate().skip(start) {
+        if results[i].len() == 1 {
+            let t = *results[i].iter().next().unwrap();
+            if (2..13).contains(&t) {
+                sure.entry(point_id(c, t)).or_insert(i);
+            }
+        }
+    }
+    let always_jumps = |i: usize, id: u128| {
+        state.get_point(id).is_some_and(|p| p != i) || sure.get(&id).is_some_and(|&p| p < i)
+    };
+
+    let mut reach = Reach {
+        cur: vec![BTreeSet::new(); n + 1],
+        falls: vec![false; n],
+        next: vec![BTreeSet::new(); n],
+    };
+    if start < n {
+        reach.cur[start].insert(state.current_stack());
+    }
+
+    let mut work = vec![start];
+    while let Some(i) = work.pop() {
+        if i >= n {
+            continue;
+        }
+        let c = &code[i];
+
+        let mut out = reach.cur[i]
+            .iter()
+            .filter(|&&x| !(command_pops(c) && (x == 1 || x == 2)))
+            .map(|&x| match c.get_type() {
+                5 | code::MOVE_TO => c.get_dot_count(),
+                _ => x,
+            })
+            .collect::<BTreeSet<_>>();
+        if area_pops(c.get_area()) {
+            out.retain(|&x| x != 1 && x != 2);
+        }
+        if out.is_empty() {
+            continue;
+        }
+
+        let mut next = Vec::new();
+        let mut falls = false;
+        for &t in &results[i] {
+            if t == 0 {
+                falls = true;
+            } else if t == 13 {
+                falls = true;
+                next.extend(&jumps);
+            } else {
+                let id = point_id(c, t);
+                falls |= !always_jumps(i, id);
+                next.extend(targets[&id].iter().filter(|&&j| j != i));
+            }
+        }
+        reach.falls[i] |= falls;
+        if falls {
+            next.push(i + 1);
+        }
+        for j in next {
+            reach.next[i].insert(j);
+            let len = reach.cur[j].len();
+            reach.cur[j].extend(&out);
+            if reach.cur[j].len() != len {
+                work.push(j);
+            }
+        }
+
+        // closed form of loop moves on to the command after the jump
+        if c.get_type() == code::ACCELERATE && i + 1 < n {
+            reach.next[i].insert(i + 2);
+            let b = &code[i + 1];
+            let len = reach.cur[i + 2].len();
+            reach.cur[i + 2].extend(out.iter().map(|&x| match b.get_type() {
+                5 | code::MOVE_TO => b.get_dot_count(),
+                _ => x,
+            }));
+            if reach.cur[i + 2].len() != len {
+                work.push(i + 2);
+            }
+        }
+    }
+
+    reach
+}
+
+/// Dead code elimination pass (level 3)
+///
+/// Commands are reached only by moving on from the previous command, or by jumping to a point
+/// that is already set. So, every command after a command that never moves on is unreachable.
+/// It happens when the command always pops stack 1 or 2, which terminates the program,
+/// or when it always jumps back.
+///
+/// Jump ids do not change, since only the commands at the end are removed.
+/// After that, hearts that never jump (the only command with its point id) are removed
+/// so that the code does not have to be split into blocks there.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::optimize::{dead_code, Pass, PassStats, Program};
+/// use hyeong::core::parse;
+///
+/// let mut p = Program::new(parse::parse(String::from("흑 항 흑. 항 형 형 형")));
+/// dead_code::DeadCode.run(&mut p, &mut PassStats::default()).unwrap();
+///
+/// assert_eq!(4, p.code.len());
+/// ```
+pub struct DeadCode;
+
+impl Pass for DeadCode {
+    fn name(&self) -> &'static str {
+        "dead-code"
+    }
+
+    fn run(&self, program: &mut Program, stats: &mut PassStats) -> Result<(), Error> {
+        let start = program.state.get_all_code().len();
+        let mut code = program.state.get_all_code();
+        code.extend(program.code.iter().cloned());
+
+        // removing code makes the analysis more precise, so repeat until nothing changes
+        loop {
+            let reach = analyze(&program.state, &code, start);
+            let mut end = code.len();
+            for i in start..code.len() {
+                if reach.cur[i].is_empty() {
+                    end = i;
+                    break;
+                }
+                if !reach.falls[i] {
+                    end = i + 1;
+                    break;
+                }
+            }
+            if end == code.len() {
+                break;
+            }
+            code.truncate(end);
+        }
+
+        let mut count: HashMap<u128, usize> = HashMap::new();
+        for c in &code {
+            let mut res = BTreeSet::new();
+            area_results(c.get_area(), &mut res);
+            for t in res {
+                if t != 0 {
+                    *count.entry(point_id(c, t)).or_insert(0) += 1;
+                }
+            }
+        }
+        let unique = count
+            .into_iter()
+            .filter(|&(id, cnt)| id & 15 != 13 && cnt == 1 && program.state.get_point(id).is_none())
+            .map(|(id, _)| id)
+            .collect::<BTreeSet<_>>();
+
+        let mut removed = 0;
+        for c in code.iter_mut().skip(start) {
+            let mut area = c.get_area().clone();
+            if remove_hearts(&mut area, c.get_area_count(), &unique) {
+                removed += 1;
+                *c = OptCode::new(
+                    c.get_type(),
+                    c.get_hangul_count(),
+                    c.get_dot_count(),
+                    c.get_area_count(),
+                    area,
+                )
+                .origin(c.get_origin());
+            }
+        }
+        if removed > 0 {
+            stats.notes.push(format!("{} areas simplified", removed));
+        }
+
+        program.code = code.split_off(start);
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+use crate::core::area::Area;
+use crate::core::code::{self, Code, OptCode};
+use crate::core::optimize::{dead_code, Pass, PassStats, Program};
+use crate::core::state::State;
+use crate::number::num::Num;
+use crate::util::error::Error;
+use std::collections::BTreeSet;
+
+/// Longest constant to fold, in the length of string
+///
+/// Values that grow too much are left to runtime,
+/// since repeated product in a few commands makes a huge number.
+const MAX_CONST_LEN: usize = 100;
+
+/// Commands folded in a row
+#[derive(Default)]
+struct Run {
+    /// original commands
+    code: Vec<OptCode>,
+    /// constants pushed so far, in order
+    pushes: Vec<(usize, Num)>,
+    /// current stack at the start of the run
+    start: usize,
+    /// current stack at the end of the run
+    end: usize,
+}
+
+impl Run {
+    /// Put the run to `res`, folded only if it gets shorter.
+    /// Folded commands come from the first command of the run.
+    /// Returns true if it is folded.
+    fn flush(&mut self, res: &mut Vec<OptCode>) -> bool {
+        let origin = self.code.first().and_then(|c| c.get_origin());
+        let mut folded = self
+            .pushes
+            .drain(..)
+            .map(|(stack, n)| OptCode::push_const(stack, n).origin(origin))
+            .collect::<Vec<_>>();
+        if self.start != self.end {
+            folded.push(OptCode::move_to(self.end).origin(origin));
+        }
+
+        let ok = folded.len() < self.code.len();
+        if ok {
+            res.append(&mut folded);
+        } else {
+            res.append(&mut self.code);
+        }
+        self.code.clear();
+        ok
+    }
+}
+
+/// Pop `n` constants of `stack` from `pushes`, in the order of popping
+fn pop_consts(pushes: &mut Vec<(usize, Num)>, stack: usize, n: usize) -> Option<Vec<Num>> {
+    if stack == 1 || stack == 2 {
+        return None;
+    }
+    let mut res = Vec::with_capacity(n);
+    for _ in 0..n {
+        let idx = pushes.iter().rposition(|(s, _)| *s == stack)?;
+        res.push(pushes.remove(idx).1);
+    }
+    Some(res)
+}
+
+/// Execute `c` on the constants pushed so far.
+/// Returns new constants and current stack, or `None` when it needs values not known.
+fn fold(
+    pushes: &[(usize, Num)],
+    cur: usize,
+    c: &OptCode,
+    size: usize,
+) -> Option<(Vec<(usize, Num)>, usize)> {
+    let mut pushes = pushes.to_vec();
+    let mut new = Vec::new();
+    let mut next = cur;
+
+    match c.get_type() {
+        0 => new.push((
+            cur,
+            &Num::from_num(c.get_hangul_count() as isize)
+                * &Num::from_num(c.get_dot_count() as isize),
+        )),
+        1 => {
+            let mut n = Num::zero();
+            for x in pop_consts(&mut pushes, cur, c.get_hangul_count())? {
+                n += &x;
+            }
+            new.push((c.get_dot_count(), n));
+        }
+        2 => {
+            let mut n = Num::one();
+            for x in pop_consts(&mut pushes, cur, c.get_hangul_count())? {
+                n *= &x;
+            }
+            new.push((c.get_dot_count(), n));
+        }
+        3 => {
+            let mut n = Num::zero();
+            for mut x in pop_consts(&mut pushes, cur, c.get_hangul_count())?
+                .into_iter()
+                .rev()
+            {
+                x.minus();
+                n += &x;
+                new.push((cur, x));
+            }
+            new.push((c.get_dot_count(), n));
+        }
+        4 => {
+            let mut n = Num::one();
+            for mut x in pop_consts(&mut pushes, cur, c.get_hangul_count())?
+                .into_iter()
+                .rev()
+            {
+                x.flip();
+                n *= &x;
+                new.push((cur, x));
+            }
+            new.push((c.get_dot_count(), n));
+        }
+        5 => {
+            let n = pop_consts(&mut pushes, cur, 1)?.pop().unwrap();
+            for _ in 0..c.get_hangul_count() {
+                new.push((c.get_dot_count(), n.clone()));
+            }
+            new.push((cur, n));
+            next = c.get_dot_count();
+        }
+        code::PUSH_CONST => new.push((c.get_dot_count(), c.get_value().unwrap().clone())),
+        code::MOVE_TO => next = c.get_dot_count(),
+        _ => return None,
+    }
+
+    // pushing NaN to empty stack does nothing, and pushing to unknown stack is ignored
+    if new
+        .iter()
+        .any(|(s, n)| *s >= size || n.is_nan() || n.to_string().len() > MAX_CONST_LEN)
+    {
+        return None;
+    }
+    pushes.append(&mut new);
+    Some((pushes, next))
+}
+
+/// Current stack after `c`, when it is `cur` before
+fn after(c: &OptCode, cur: Option<usize>) -> Option<usize> {
+    match c.get_type() {
+        5 | code::MOVE_TO => Some(c.get_dot_count()),
+        _ => cur,
+    }
+}
+
+/// Returns the only element of the set
+fn single(set: &BTreeSet<usize>) -> Option<usize> {
+    if set.len() == 1 {
+        set.iter().next().copied()
+    } else {
+        None
+    }
+}
+
+/// Constant folding pass (level 3)
+///
+/// Commands with area are the only commands that can be jumped to,
+/// so commands between them run in a row.
+/// In those basic blocks, it executes commands on the constants pushed before,
+/// as long as the current stack is known and no popped value comes from outside.
+/// Then the commands are replaced with [push_const](../../code/struct.OptCode.html#method.push_const)
+/// of the values left, and [move_to](../../code/struct.OptCode.html#method.move_to)
+/// if current stack is changed.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::code::{self, Code};
+/// use hyeong::core::optimize::{fold, Pass, PassStats, Program};
+/// use hyeong::core::parse;
+///
+/// let mut p = Program::new(parse::parse(String::from("형.. 형... 하앗. 형")));
+/// fold::ConstantFold.run(&mut p, &mut PassStats::default()).unwrap();
+///
+/// assert_eq!(2, p.code.len());
+/// assert_eq!(code::PUSH_CONST, p.code[0].get_type());
+/// assert_eq!(1, p.code[0].get_dot_count());
+/// assert_eq!("6", p.code[0].get_value().unwrap().to_string());
+/// ```
+pub struct ConstantFold;
+
+impl Pass for ConstantFold {
+    fn name(&self) -> &'static str {
+        "constant-fold"
+    }
+
+    fn run(&self, program: &mut Program, stats: &mut PassStats) -> Result<(), Error> {
+        let start = program.state.get_all_code().len();
+        let size = program.state.stack_size();
+        let mut all = program.state.get_all_code();
+        all.extend(program.code.iter().cloned());
+        let reach = dead_code::analyze(&program.state, &all, start);
+
+        let mut res = Vec::with_capacity(program.code.len());
+        let mut run = Run::default();
+        let mut cur = None;
+        let mut folded = 0;
+
+        for (i, c) in all.iter().enumerate().skip(start) {
+            if let Area::Nil = c.get_area() {
+                cur = cur.or_else(|| single(&reach.cur[i]));
+                if let Some(s) = cur {
+                    if let Some((pushes, next)) = fold(&run.pushes, s, c, size) {
+                        if run.code.is_empty() {
+                            run.start = s;
+                        }
+                        run.code.push(c.clone());
+                        run.pushes = pushes;
+                        run.end = next;
+                        cur = Some(next);
+                        continue;
+                    }
+                }
+            } else {
+                // it can be jumped to, so the current stack from the analysis is used
+                cur = single(&reach.cur[i]);
+            }
+
+            if run.flush(&mut res) {
+                folded += 1;
+            }
+            cur = after(c, cur);
+            res.push(c.clone());
+        }
+        if run.flush(&mut res) {
+            folded += 1;
+        }
+
+        if folded > 0 {
+            stats.notes.push(format!("{} blocks folded", folded));
+        }
+        program.code = res;
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+use crate::core::code::{self, Code, OptCode};
+use crate::core::optimize::{dead_code, Pass, PassStats, Program};
+use crate::core::state::{OptState, State};
+use crate::util::error::Error;
+use std::collections::BTreeSet;
+
+/// Height that is not known
+const INF: usize = usize::MAX;
+
+/// Times a command is visited before heights growing in a loop are widened
+const WIDEN_AFTER: usize = 8;
+
+/// Range of the number of values in a stack
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Height {
+    lo: usize,
+    hi: usize,
+}
+
+impl Height {
+    fn exact(n: usize) -> Height {
+        Height { lo: n, hi: n }
+    }
+
+    fn join(self, other: Height) -> Height {
+        Height {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+        }
+    }
+
+    /// Pushing NaN does nothing on empty stack
+    fn push(self, n: usize, v: Value) -> Height {
+        Height {
+            lo: if v != Value::Num && self.lo == 0 {
+                0
+            } else {
+                self.lo + n
+            },
+            hi: if v == Value::NaN && self.hi == 0 {
+                0
+            } else {
+                self.hi.saturating_add(n)
+            },
+        }
+    }
+
+    fn pop(self, n: usize) -> Height {
+        Height {
+            lo: self.lo.saturating_sub(n),
+            hi: if self.hi == INF {
+                INF
+            } else {
+                self.hi.saturating_sub(n)
+            },
+        }
+    }
+}
+
+/// What is known about a value pushed
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Value {
+    /// surely a number
+    Num,
+    /// may be NaN
+    Any,
+    /// surely NaN
+    NaN,
+}
+
+/// Stacks used by a command
+#[derive(Default)]
+struct Access {
+    /// stacks popped
+    read: BTreeSet<usize>,
+    /// stacks popped or pushed
+    all: BTreeSet<usize>,
+}
+
+impl Access {
+    fn pop(&mut self, h: &mut [Height], s: usize, n: usize) {
+        if s >= 3 && s < h.len() {
+            self.read.insert(s);
+            self.all.insert(s);
+            h[s] = h[s].pop(n);
+        }
+    }
+
+    fn push(&mut self, h: &mut [Height], s: usize, n: usize, v: Value) {
+        if s >= 3 && s < h.len() {
+            self.all.insert(s);
+            h[s] = h[s].push(n, v);
+        }
+    }
+}
+
+/// Heights after `c` when the current stack is `cur`.
+/// Stacks used are added to `access`.
+fn step(h: &[Height], c: &OptCode, cur: usize, access: &mut Access) -> Vec<Height> {
+    let mut h = h.to_vec();
+    let (n, d) = (c.get_hangul_count(), c.get_dot_count());
+    let mut after = cur;
+    // value popped from empty stack or stack not used is NaN
+    let popped = |k: usize| {
+        if cur >= h.len() || (cur >= 3 && h[cur].hi < k) {
+            Value::NaN
+        } else {
+            Value::Any
+        }
+    };
+    match c.get_type() {
+        0 => access.push(&mut h, cur, 1, Value::Num),
+        1 | 2 => {
+            let v = popped(n);
+            access.pop(&mut h, cur, n);
+            access.push(&mut h, d, 1, v);
+        }
+        3 | 4 => {
+            let v = popped(n);
+            access.pop(&mut h, cur, n);
+            access.push(&mut h, cur, n, Value::Any);
+            access.push(&mut h, d, 1, v);
+        }
+        5 => {
+            let v = popped(1);
+            access.pop(&mut h, cur, 1);
+            access.push(&mut h, d, n, v);
+            access.push(&mut h, cur, 1, v);
+            after = d;
+        }
+        code::PUSH_CONST => access.push(&mut h, d, 1, Value::Num),
+        // MOVE_TO
+        _ => after = d,
+    }
+    if dead_code::area_pops(c.get_area()) {
+        access.pop(&mut h, after, 1);
+    }
+    h
+}
+
+/// Stack index for `s` in the new numbering
+fn map_stack(map: &[usize], s: usize, sink: usize) -> usize {
+    if s < 3 {
+        s
+    } else {
+        *map.get(s).unwrap_or(&sink)
+    }
+}
+
+/// Command with stack index changed
+fn renumber(c: &OptCode, map: &[usize], sink: usize) -> OptCode {
+    let d = map_stack(map, c.get_dot_count(), sink);
+    match c.get_type() {
+        0 => c.clone(),
+        code::PUSH_CONST => OptCode::push_const(d, c.get_value().unwrap().clone()),
+        code::MOVE_TO => OptCode::move_to(d),
+        t => OptCode::new(
+            t,
+            c.get_hangul_count(),
+            d,
+            c.get_area_count(),
+            c.get_area().clone(),
+        ),
+    }
+    .origin(c.get_origin())
+}
+
+/// Stack liveness pass (level 3)
+///
+/// It finds the possible range of the height of every stack at each command,
+/// with the current stacks from the reachability analysis.
+/// Stacks that are never popped are dropped,
+/// by moving them to an index without a stack where pushing does nothing.
+/// Two stacks interfere if both can be non-empty or used at the same command.
+/// Stacks that do not interfere share the same index,
+/// since at most one of them has values at any time.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::code::Code;
+/// use hyeong::core::optimize::{liveness, renumber, Pass, PassStats, Program};
+/// use hyeong::core::parse;
+/// use hyeong::core::state::State;
+///
+/// let code = "형. 흑.... 항.. 흑... 항.. 흑..... 항.. 흑... 항..";
+/// let mut p = Program::new(parse::parse(String::from(code)));
+/// renumber::RenumberStacks.run(&mut p, &mut PassStats::default()).unwrap();
+/// assert_eq!(7, p.state.stack_size());
+///
+/// // stack 6 is never read, and stack 5 is used after stack 4 is empty
+/// liveness::MergeStacks.run(&mut p, &mut PassStats::default()).unwrap();
+/// assert_eq!(5, p.state.stack_size());
+/// assert_eq!(4, p.code[5].get_dot_count());
+/// ```
+pub struct MergeStacks;
+
+impl Pass for MergeStacks {
+    fn name(&self) -> &'static str {
+        "merge-stacks"
+    }
+
+    fn run(&self, program: &mut Program, stats: &mut PassStats) -> Result<(), Error> {
+        let mut all = program.state.get_all_code();
+        let start = all.len();
+        all.extend(program.code.iter().cloned());
+        if all.iter().any(|c| c.get_type() == code::ACCELERATE) {
+            return Ok(());
+        }
+        let n = all.len();
+        let size = program.state.stack_size();
+        let reach = dead_code::analyze(&program.state, &all, start);
+
+        let mut heights: Vec<Option<Vec<Height>>> = vec![None; n + 1];
+        let mut visits = vec![0; n + 1];
+        heights[start] = Some(
+            (0..size)
+                .map(|s| Height::exact(program.state.get_stack(s).len()))
+                .collect(),
+        );
+        let mut access = (0..n).map(|_| Access::default()).collect::<Vec<_>>();
+
+        let mut work = vec![start];
+        while let Some(i) = work.pop() {
+            if i >= n {
+                continue;
+            }
+            let h = heights[i].clone().unwrap();
+            let mut out: Option<Vec<Height>> = None;
+            for &cur in &reach.cur[i] {
+                let r = step(&h, &all[i], cur, &mut access[i]);
+                out = Some(match out {
+                    Some(o) => o.iter().zip(&r).map(|(a, b)| a.join(*b)).collect(),
+                    None => r,
+                });
+            }
+            let out = match out {
+                Some(o) => o,
+                None => continue,
+            };
+
+            for &j in &reach.next[i] {
+                let new = match &heights[j] {
+                    Some(old) => {
+                        visits[j] += 1;
+                        old.iter()
+                            .zip(&out)
+                            .map(|(a, b)| {
+                                let mut h = a.join(*b);
+                                if visits[j] > WIDEN_AFTER && h != *a {
+                                    h = Height { lo: 0, hi: INF };
+                                }
+                                h
+                            })
+                            .collect()
+                    }
+                    None => out.clone(),
+                };
+                if heights[j].as_ref() != Some(&new) {
+                    heights[j] = Some(new);
+                    work.push(j);
+                }
+            }
+        }
+
+        // stacks that can be non-empty or used at the same command
+        let mut read = vec![false; size];
+        let mut interfere = vec![BTreeSet::<usize>::new(); size];
+        for i in 0..n {
+            let h = match &heights[i] {
+                Some(h) => h,
+                None => continue,
+            };
+            for &s in &access[i].read {
+                read[s] = true;
+            }
+            let live = (3..size)
+                .filter(|&s| h[s].hi > 0 || access[i].all.contains(&s))
+                .collect::<Vec<_>>();
+            for &s in &live {
+                interfere[s].extend(live.iter().filter(|&&t| t != s));
+            }
+        }
+
+        let mut map = (0..size).collect::<Vec<_>>();
+        let mut new_size = 3;
+        let mut dropped = Vec::new();
+        for s in 3..size {
+            if !read[s] {
+                dropped.push(s);
+                continue;
+            }
+            map[s] = (3..)
+                .find(|&k| {
+                    !interfere[s]
+                        .iter()
+                        .any(|&t| t < s && read[t] && map[t] == k)
+                })
+                .unwrap();
+            new_size = new_size.max(map[s] + 1);
+        }
+        let sink = new_size;
+        for &s in &dropped {
+            map[s] = sink;
+        }
+        if new_size == size {
+            return Ok(());
+        }
+
+        let mut state = OptState::new(new_size);
+        for c in program.state.get_all_code() {
+            state.push_code(renumber(&c, &map, sink));
+        }
+        for (id, loc) in program.state.get_all_point() {
+            state.set_point(id, loc);
+        }
+        if let Some(loc) = program.state.get_latest_loc() {
+            state.set_latest_loc(loc);
+        }
+        for output in program.state.get_output() {
+            state.push_output(output.clone());
+        }
+        for s in 0..size {
+            let t = map_stack(&map, s, sink);
+            if t < new_size {
+                let v = program.state.get_stack(s).clone();
+                state.get_stack(t).extend(v);
+            }
+        }
+        state.set_current_stack(map_stack(&map, program.state.current_stack(), sink));
+
+        program.state = state;
+        for s in program.stack_map.values_mut() {
+            *s = map_stack(&map, *s, sink);
+        }
+        for c in program.code.iter_mut() {
+            *c = renumber(c, &map, sink);
+        }
+
+        let shared = size - new_size - dropped.len();
+        if !dropped.is_empty() {
+            stats
+                .notes
+                .push(format!("{} stacks dropped", dropped.len()));
+        }
+        if shared > 0 {
+            stats.notes.push(format!("{} stacks shared", shared));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,369 @@
+use crate::core::area::Area;
+use crate::core::code::{self, Accel, Code, OptCode};
+use crate::core::optimize::{dead_code, Pass, PassStats, Program};
+use crate::core::state::State;
+use crate::number::affine::{Affine, AffineLoop};
+use crate::number::num::Num;
+use crate::util::error::Error;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Most variables in a loop to run in closed form
+const MAX_VARS: usize = 16;
+
+/// Value at the top of stack before the loop; stack and depth from the top
+type Var = (usize, usize);
+
+/// Affine expression of variables
+#[derive(Clone)]
+struct Expr {
+    coef: BTreeMap<Var, Num>,
+    constant: Num,
+}
+
+impl Expr {
+    fn constant(n: Num) -> Expr {
+        Expr {
+            coef: BTreeMap::new(),
+            constant: n,
+        }
+    }
+
+    fn var(v: Var) -> Expr {
+        let mut coef = BTreeMap::new();
+        coef.insert(v, Num::one());
+        Expr {
+            coef,
+            constant: Num::zero(),
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        self.coef.is_empty()
+    }
+
+    fn add(&mut self, other: &Expr) {
+        for (v, n) in &other.coef {
+            let c = self.coef.entry(*v).or_insert_with(Num::zero);
+            *c += n;
+            if *c == Num::zero() {
+                self.coef.remove(v);
+            }
+        }
+        self.constant += &other.constant;
+    }
+
+    fn scale(&mut self, n: &Num) {
+        if *n == Num::zero() {
+            self.coef.clear();
+        }
+        for c in self.coef.values_mut() {
+            *c *= n;
+        }
+        self.constant *= n;
+    }
+
+    /// Coefficients of `vars` followed by the constant term
+    fn row(&self, vars: &[Var]) -> Vec<Num> {
+        let mut res = vars
+            .iter()
+            .map(|v| self.coef.get(v).cloned().unwrap_or_else(Num::zero))
+            .collect::<Vec<_>>();
+        res.push(self.constant.clone());
+        res
+    }
+}
+
+/// Symbolic execution on the top of stacks
+#[derive(Clone)]
+struct Machine {
+    /// values pushed on each stack, above the values not touched
+    stacks: BTreeMap<usize, Vec<Expr>>,
+    /// number of values popped below the pushed ones
+    depth: BTreeMap<usize, usize>,
+    cur: usize,
+    size: usize,
+}
+
+impl Machine {
+    fn new(cur: usize, size: usize) -> Machine {
+        Machine {
+            stacks: BTreeMap::new(),
+            depth: BTreeMap::new(),
+            cur,
+            size,
+        }
+    }
+
+    /// Pop from `stack`; input and output stacks are not allowed
+    fn pop(&mut self, stack: usize) -> Option<Expr> {
+        if stack <= 2 || stack >= self.size {
+            return None;
+        }
+        match self.stacks.entry(stack).or_default().pop() {
+            Some(e) => Some(e),
+            None => {
+                let d = self.depth.entry(stack).or_insert(0);
+                *d += 1;
+                Some(Expr::var((stack, *d - 1)))
+            }
+        }
+    }
+
+    fn pop_n(&mut self, stack: usize, n: usize) -> Option<Vec<Expr>> {
+        (0..n).map(|_| self.pop(stack)).collect()
+    }
+
+    fn push(&mut self, stack: usize, e: Expr) -> Option<()> {
+        if stack <= 2 || stack >= self.size {
+            return None;
+        }
+        self.stacks.entry(stack).or_default().push(e);
+        Some(())
+    }
+
+    /// Execute the command except for the area.
+    /// Returns `None` when the result is not affine.
+    fn step(&mut self, c: &OptCode) -> Option<()> {
+        let cur = self.cur;
+        let (h, d) = (c.get_hangul_count(), c.get_dot_count());
+        match c.get_type() {
+            0 => self.push(cur, Expr::constant(Num::from_num((h * d) as isize))),
+            1 => {
+                let mut n = Expr::constant(Num::zero());
+                for e in self.pop_n(cur, h)? {
+                    n.add(&e);
+                }
+                self.push(d, n)
+            }
+            2 => {
+                // at most one of them can be a variable
+                let v = self.pop_n(cur, h)?;
+                let mut n = Expr::constant(Num::one());
+                for e in &v {
+                    if e.is_constant() {
+                        n.scale(&e.constant);
+                    } else if n.is_constant() {
+                        let mut e = e.clone();
+                        e.scale(&n.constant);
+                        n = e;
+                    } else {
+                        return None;
+                    }
+                }
+                self.push(d, n)
+            }
+            3 => {
+                let mut n = Expr::constant(Num::zero());
+                for mut e in self.pop_n(cur, h)?.into_iter().rev() {
+                    e.scale(&Num::from_num(-1));
+                    n.add(&e);
+                    self.push(cur, e)?;
+                }
+                self.push(d, n)
+            }
+            4 => {
+                let mut n = Num::one();
+                for e in self.pop_n(cur, h)?.into_iter().rev() {
+                    if !e.is_constant() || e.constant == Num::zero() {
+                        return None;
+                    }
+                    let mut x = e.constant;
+                    x.flip();
+                    n *= &x;
+                    self.push(cur, Expr::constant(x))?;
+                }
+                self.push(d, Expr::constant(n))
+            }
+            5 => {
+                let e = self.pop(cur)?;
+                for _ in 0..h {
+                    self.push(d, e.clone())?;
+                }
+                self.push(cur, e)?;
+                self.cur = d;
+                Some(())
+            }
+            code::PUSH_CONST => self.push(d, Expr::constant(c.get_value().unwrap().clone())),
+            code::MOVE_TO => {
+                self.cur = d;
+                Some(())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Id of the heart, if area is only one heart
+fn single_heart(area: &Area, area_count: usize) -> Option<u128> {
+    match area {
+        Area::Val { type_, left, right }
+            if (2..13).contains(type_)
+                && matches!(**left, Area::Nil)
+                && matches!(**right, Area::Nil) =>
+        {
+            Some(((area_count as u128) << 4) + *type_ as u128)
+        }
+        _ => None,
+    }
+}
+
+/// Comparison of area that jumps on one side only.
+/// Returns `(equal, repeat_if, id)` where the loop goes on when the comparison gives `repeat_if`.
+fn back_edge(c: &OptCode) -> Option<(bool, bool, u128)> {
+    match c.get_area() {
+        Area::Val { type_, left, right } if *type_ <= 1 => {
+            let n = c.get_area_count();
+            match (&**left, &**right) {
+                (l, Area::Nil) => single_heart(l, n).map(|id| (*type_ == 1, true, id)),
+                (Area::Nil, r) => single_heart(r, n).map(|id| (*type_ == 1, false, id)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Find the loop that jumps back at `b`, and make its closed form
+///
+/// The loop starts from the command with a single heart of the same id,
+/// and every command between them has no area.
+/// Since it is seen from right before `b`, one iteration is `b` followed by the rest of the loop.
+fn find_loop(code: &[OptCode], b: usize, cur: usize, size: usize) -> Option<Accel> {
+    let (equal, repeat_if, id) = back_edge(&code[b])?;
+    let a = (0..b)
+        .rev()
+        .find(|&i| !matches!(code[i].get_area(), Area::Nil))?;
+    if single_heart(code[a].get_area(), code[a].get_area_count())? != id {
+        return None;
+    }
+
+    let mut m = Machine::new(cur, size);
+    m.step(&code[b])?;
+    let counter = m.pop(m.cur)?;
+    let exit = m.clone();
+    let after = exit.cur;
+    for c in &code[a..b] {
+        m.step(c)?;
+    }
+    if m.cur != cur {
+        return None;
+    }
+
+    let stacks = m
+        .depth
+        .keys()
+        .chain(m.stacks.keys())
+        .chain(exit.stacks.keys())
+        .copied()
+        .collect::<BTreeSet<_>>();
+    let mut windows = Vec::new();
+    let mut vars = Vec::new();
+    for &s in &stacks {
+        let w = *m.depth.get(&s).unwrap_or(&0);
+        if m.stacks.get(&s).map_or(0, |v| v.len()) != w {
+            return None;
+        }
+        windows.push((s, w));
+        vars.extend((0..w).rev().map(|d| (s, d)));
+    }
+    if vars.len() > MAX_VARS {
+        return None;
+    }
+
+    let step = Affine::new(
+        windows
+            .iter()
+            .flat_map(|(s, _)| m.stacks.get(s).into_iter().flatten())
+            .map(|e| e.row(&vars))
+            .collect(),
+    );
+    let exit = windows
+        .iter()
+        .map(|&(s, w)| {
+            let u = *exit.depth.get(&s).unwrap_or(&0);
+            (u..w)
+                .rev()
+                .map(|d| Expr::var((s, d)))
+                .chain(exit.stacks.get(&s).into_iter().flatten().cloned())
+                .map(|e| e.row(&vars))
+                .collect()
+        })
+        .collect();
+
+    let body = AffineLoop::new(
+        windows,
+        step,
+        counter.row(&vars),
+        Num::from_num(code[b].get_area_count() as isize),
+        equal,
+        repeat_if,
+        exit,
+    )?;
+    Some(Accel {
+        id,
+        back: b - a,
+        body,
+        cur: after,
+    })
+}
+
+/// Loop acceleration pass (level 3)
+///
+/// It finds loops made by a command with a single heart
+/// and a later command that compares a value and jumps back to it,
+/// with no area in between.
+/// When the loop does not read input or write output, and only makes affine updates
+/// on the stacks while the compared value changes by a constant,
+/// [accelerate](../../code/struct.OptCode.html#method.accelerate) command
+/// is put right before the command that jumps back.
+/// At runtime, it computes the number of iterations and the values after the loop directly.
+/// If the values are not there or the loop never ends, the loop runs as usual.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::code::{self, Code};
+/// use hyeong::core::optimize::{loops, Pass, PassStats, Program};
+/// use hyeong::core::parse;
+///
+/// let mut p = Program::new(parse::parse(String::from("형 흑.... 형....💕 하앙.... 흑....!💕")));
+/// loops::AccelerateLoops.run(&mut p, &mut PassStats::default()).unwrap();
+///
+/// assert_eq!(6, p.code.len());
+/// assert_eq!(code::ACCELERATE, p.code[4].get_type());
+/// ```
+pub struct AccelerateLoops;
+
+impl Pass for AccelerateLoops {
+    fn name(&self) -> &'static str {
+        "accelerate-loops"
+    }
+
+    fn run(&self, program: &mut Program, stats: &mut PassStats) -> Result<(), Error> {
+        let start = program.state.get_all_code().len();
+        let size = program.state.stack_size();
+        let mut all = program.state.get_all_code();
+        all.extend(program.code.iter().cloned());
+        let reach = dead_code::analyze(&program.state, &all, start);
+
+        let mut res = Vec::with_capacity(program.code.len());
+        let mut count = 0;
+        for (i, c) in all.iter().enumerate().skip(start) {
+            // the first entry may come with another stack, which runs as usual
+            for &cur in &reach.cur[i] {
+                if let Some(l) = find_loop(&all, i, cur, size) {
+                    res.push(OptCode::accelerate(cur, l).origin(c.get_origin()));
+                    count += 1;
+                    break;
+                }
+            }
+            res.push(c.clone());
+        }
+
+        if count > 0 {
+            stats.notes.push(format!("{} loops accelerated", count));
+        }
+        program.code = res;
+        Ok(())
+    }
+}
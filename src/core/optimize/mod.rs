@@ -0,0 +1,481 @@
+use crate::core::code::{self, Code, OptCode, UnOptCode};
+use crate::core::parse;
+use crate::core::state::{OptState, Output, State};
+use crate::util::error::Error;
+use std::cmp::max;
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub mod dead_code;
+pub mod fold;
+pub mod liveness;
+pub mod loops;
+pub mod pre_execute;
+pub mod renumber;
+
+/// Program that optimization passes work on
+///
+/// `state` is the state after the commands that are already executed while optimizing,
+/// and `code` is the rest of the commands that should be executed at runtime.
+/// `stack_map` maps each stack index in the source to the index in `state`,
+/// which is updated by the passes that renumber stacks.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::optimize::Program;
+/// use hyeong::core::parse;
+/// use hyeong::core::state::State;
+///
+/// let p = Program::new(parse::parse(String::from("형... 흑.......")));
+///
+/// assert_eq!(2, p.code.len());
+/// assert_eq!(8, p.state.stack_size());
+///
+/// let q = Program::new(parse::parse(String::from("형.................... 흑.")));
+///
+/// assert_eq!(4, q.state.stack_size());
+/// ```
+#[derive(Clone)]
+pub struct Program {
+    pub state: OptState,
+    pub code: Vec<OptCode>,
+    pub stack_map: BTreeMap<usize, usize>,
+}
+
+impl Program {
+    /// Make new `Program` from parsed code
+    ///
+    /// Stack indices are kept as is, so the state has every stack up to the largest index.
+    /// Dots of `형` are a value, not a stack, so they do not make the state larger.
+    pub fn new(code: Vec<UnOptCode>) -> Program {
+        let size = code
+            .iter()
+            .filter(|c| c.get_type() != 0)
+            .fold(4, |acc, c| max(acc, c.get_dot_count() + 1));
+        let stack_map = (0..4)
+            .chain(
+                code.iter()
+                    .filter(|c| c.get_type() != 0)
+                    .map(|c| c.get_dot_count()),
+            )
+            .map(|s| (s, s))
+            .collect();
+        Program {
+            state: OptState::new(size),
+            stack_map,
+            code: code
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    OptCode::new(
+                        c.get_type(),
+                        c.get_hangul_count(),
+                        c.get_dot_count(),
+                        c.get_area_count(),
+                        c.get_area().clone(),
+                    )
+                    .origin(Some(i))
+                })
+                .collect(),
+        }
+    }
+
+    /// Report of what the optimizer did
+    ///
+    /// It shows where each stack of the source went,
+    /// the number of commands executed while optimizing,
+    /// the values left on the stacks, the output written, and the point table.
+    /// Stacks that are dropped have no index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::core::optimize::{PassManager, Program};
+    /// use hyeong::core::parse;
+    ///
+    /// let code = parse::parse(String::from("형... 흑....... 형.. 항."));
+    /// let (p, _) = PassManager::from_level(2).run(Program::new(code)).unwrap();
+    ///
+    /// assert_eq!(
+    ///     "stack map:\n  0 -> 0\n  1 -> 1\n  2 -> 2\n  3 -> 3\n  7 -> 4\n\
+    ///      pre-executed: 4 commands\n\
+    ///      stack 3: [3]\n\
+    ///      stack 4: [3]\n\
+    ///      output: stdout \"\\u{2}\"\n\
+    ///      points: none\n",
+    ///     p.explain()
+    /// );
+    /// ```
+    pub fn explain(&self) -> String {
+        let mut state = self.state.clone();
+        let mut res = String::from("stack map:\n");
+        for (from, to) in &self.stack_map {
+            if *to < state.stack_size() {
+                res.push_str(&format!("  {} -> {}\n", from, to));
+            } else {
+                res.push_str(&format!("  {} -> dropped\n", from));
+            }
+        }
+
+        res.push_str(&format!(
+            "pre-executed: {} commands\n",
+            state.get_all_code().len()
+        ));
+        for i in state.get_all_stack_index() {
+            let st = state.get_stack(i);
+            if !st.is_empty() {
+                res.push_str(&format!("stack {}: {:?}\n", i, st));
+            }
+        }
+        for output in state.get_output() {
+            match output {
+                Output::Stdout(s) => res.push_str(&format!("output: stdout {:?}\n", s)),
+                Output::Stderr(s) => res.push_str(&format!("output: stderr {:?}\n", s)),
+            }
+        }
+
+        let mut point = state.get_all_point();
+        point.sort_unstable();
+        if point.is_empty() {
+            res.push_str("points: none\n");
+        }
+        for (id, loc) in point {
+            res.push_str(&format!("point {}: {}\n", id, loc));
+        }
+        if let Some(loc) = state.get_latest_loc() {
+            res.push_str(&format!("latest: {}\n", loc));
+        }
+        res
+    }
+}
+
+impl fmt::Display for Program {
+    /// Prints the state and the commands left
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::core::optimize::Program;
+    /// use hyeong::core::parse;
+    ///
+    /// let p = Program::new(parse::parse(String::from("형... 흑.")));
+    ///
+    /// assert_eq!(
+    ///     "stacks: 4, current stack: 3\nexecuted: 0 commands\n0 | 형_1_3 _\n1 | 흑_1_1 _\n",
+    ///     p.to_string()
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut state = self.state.clone();
+        writeln!(
+            f,
+            "stacks: {}, current stack: {}",
+            state.stack_size(),
+            state.current_stack()
+        )?;
+        for i in state.get_all_stack_index() {
+            let st = state.get_stack(i);
+            if !st.is_empty() {
+                writeln!(f, "stack {}: {:?}", i, st)?;
+            }
+        }
+
+        writeln!(f, "executed: {} commands", state.get_all_code().len())?;
+        let mut point = state.get_all_point();
+        point.sort_unstable();
+        for (id, loc) in point {
+            writeln!(f, "point {}: {}", id, loc)?;
+        }
+        if let Some(loc) = state.get_latest_loc() {
+            writeln!(f, "latest: {}", loc)?;
+        }
+        for output in state.get_output() {
+            match output {
+                Output::Stdout(s) => writeln!(f, "stdout: {:?}", s)?,
+                Output::Stderr(s) => writeln!(f, "stderr: {:?}", s)?,
+            }
+        }
+
+        for (i, c) in self.code.iter().enumerate() {
+            writeln!(f, "{} | {}", i, command_string(c))?;
+        }
+        Ok(())
+    }
+}
+
+/// Command in the form of `Program` dump
+pub(crate) fn command_string(c: &OptCode) -> String {
+    match c.get_type() {
+        code::PUSH_CONST => format!("push {} to {}", c.get_value().unwrap(), c.get_dot_count()),
+        code::MOVE_TO => format!("move to {}", c.get_dot_count()),
+        code::ACCELERATE => format!(
+            "accelerate loop on {}, {} variables",
+            c.get_dot_count(),
+            c.get_loop().unwrap().body.step.rows().len()
+        ),
+        t => format!(
+            "{}_{}_{} {}",
+            parse::COMMANDS[t as usize],
+            c.get_hangul_count(),
+            c.get_dot_count(),
+            c.get_area()
+        ),
+    }
+}
+
+/// Statistics of one pass
+///
+/// `commands_removed` and `stacks_removed` are filled by [PassManager](struct.PassManager.html).
+/// Stacks that are merged into another stack also count as removed.
+#[derive(Clone, Debug, Default)]
+pub struct PassStats {
+    pub pass: &'static str,
+    pub commands_removed: usize,
+    pub stacks_removed: usize,
+    pub notes: Vec<String>,
+}
+
+impl fmt::Display for PassStats {
+    /// Prints the statistics in one line
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::core::optimize::PassStats;
+    ///
+    /// let mut a = PassStats::default();
+    /// a.pass = "foo";
+    /// a.commands_removed = 3;
+    /// a.notes.push(String::from("bar"));
+    ///
+    /// assert_eq!("foo: 3 commands removed, 0 stacks removed (bar)", a.to_string());
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} commands removed, {} stacks removed",
+            self.pass, self.commands_removed, self.stacks_removed
+        )?;
+        if !self.notes.is_empty() {
+            write!(f, " ({})", self.notes.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Optimization pass
+///
+/// Each pass transforms the `Program` keeping the behavior of the program.
+pub trait Pass {
+    /// Name of the pass, used in `--dump-after`
+    fn name(&self) -> &'static str;
+
+    /// Run the pass; notes can be added to `stats`
+    fn run(&self, program: &mut Program, stats: &mut PassStats) -> Result<(), Error>;
+}
+
+/// Result of running `PassManager`
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    pub stats: Vec<PassStats>,
+    pub dumps: Vec<(&'static str, String)>,
+}
+
+/// Configuration of optimization pipeline
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::optimize::Config;
+///
+/// let a = Config::new(2).pre_exec_steps(500);
+///
+/// assert_eq!(2, a.level);
+/// assert_eq!(500, a.pre_exec_steps);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Config {
+    pub level: u8,
+    pub pre_exec_steps: usize,
+}
+
+impl Config {
+    /// Make new `Config` with default values
+    pub fn new(level: u8) -> Config {
+        Config {
+            level,
+            pre_exec_steps: pre_execute::DEFAULT_STEPS,
+        }
+    }
+
+    /// Set number of commands to execute in `pre-execute` pass
+    #[must_use]
+    pub fn pre_exec_steps(mut self, steps: usize) -> Config {
+        self.pre_exec_steps = steps;
+        self
+    }
+}
+
+/// Runs passes in order
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::optimize::{PassManager, Program};
+/// use hyeong::core::parse;
+///
+/// let pm = PassManager::from_level(2).dump_after(String::from("renumber-stacks"));
+/// let (p, report) = pm.run(Program::new(parse::parse(String::from("형... 항.")))).unwrap();
+///
+/// assert_eq!(vec!["renumber-stacks", "pre-execute"], pm.pass_names());
+/// assert_eq!(0, p.code.len());
+/// assert_eq!(2, report.stats[1].commands_removed);
+/// assert_eq!("renumber-stacks", report.dumps[0].0);
+/// ```
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Box<dyn Pass>>,
+    dump_after: Option<String>,
+}
+
+impl PassManager {
+    /// Make new `PassManager` without any pass
+    pub fn new() -> PassManager {
+        PassManager {
+            passes: Vec::new(),
+            dump_after: None,
+        }
+    }
+
+    /// Make `PassManager` with the pipeline of optimization level
+    ///
+    /// - level 0: nothing
+    /// - level 1: `renumber-stacks`
+    /// - level 2: `renumber-stacks`, `pre-execute`
+    /// - level 3: `renumber-stacks`, `pre-execute`, `dead-code`, `constant-fold`,
+    ///   `merge-stacks`, `accelerate-loops`
+    pub fn from_level(level: u8) -> PassManager {
+        PassManager::from_config(&Config::new(level))
+    }
+
+    /// Make `PassManager` with the pipeline of `config.level`
+    pub fn from_config(config: &Config) -> PassManager {
+        let mut pm = PassManager::new();
+        if config.level >= 1 {
+            pm = pm.add_pass(renumber::RenumberStacks);
+        }
+        if config.level >= 2 {
+            pm = pm.add_pass(pre_execute::PreExecute::new(config.pre_exec_steps));
+        }
+        if config.level >= 3 {
+            pm = pm
+                .add_pass(dead_code::DeadCode)
+                .add_pass(fold::ConstantFold)
+                .add_pass(liveness::MergeStacks)
+                .add_pass(loops::AccelerateLoops);
+        }
+        pm
+    }
+
+    /// Add pass at the end of pipeline
+    #[must_use]
+    pub fn add_pass(mut self, pass: impl Pass + 'static) -> PassManager {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Dump the program after the pass named `name`
+    #[must_use]
+    pub fn dump_after(mut self, name: String) -> PassManager {
+        self.dump_after = Some(name);
+        self
+    }
+
+    /// Return names of passes in order
+    pub fn pass_names(&self) -> Vec<&'static str> {
+        self.passes.iter().map(|p| p.name()).collect()
+    }
+
+    /// Run all passes
+    ///
+    /// # Errors
+    ///
+    /// - when the pass to dump is not in the pipeline
+    /// - when one of the passes fails
+    pub fn run(&self, mut program: Program) -> Result<(Program, Report), Error> {
+        if let Some(name) = &self.dump_after {
+            if !self.pass_names().contains(&&name[..]) {
+                return Err(Error::new(
+                    format!("pass \"{}\" is not in the pipeline", name),
+                    format!("passes: {}", self.pass_names().join(", ")),
+                ));
+            }
+        }
+
+        let mut report = Report::default();
+        for pass in &self.passes {
+            let commands = program.code.len();
+            let stacks = program.state.stack_size();
+            let mut stats = PassStats {
+                pass: pass.name(),
+                ..PassStats::default()
+            };
+
+            pass.run(&mut program, &mut stats)?;
+
+            stats.commands_removed = commands.saturating_sub(program.code.len());
+            stats.stacks_removed = stacks.saturating_sub(program.state.stack_size());
+            report.stats.push(stats);
+
+            if self.dump_after.as_deref() == Some(pass.name()) {
+                report.dumps.push((pass.name(), program.to_string()));
+            }
+        }
+        Ok((program, report))
+    }
+}
+
+/// Optimization function
+///
+/// It runs the pipeline of [PassManager::from_level](struct.PassManager.html#method.from_level).
+///
+/// ## Level 1
+///
+/// In level 1, it analyzes the hyeong code and collect only used index of stack.
+/// Then, re-number the indices not to make `HashMap`; using `Vec`
+///
+/// ## Level 2
+///
+/// In level 2, it runs code until it gets input, terminates, or runs out of step budget.
+/// So, `Hello, World!` code compiles to `print!("Hello, World!")` when unused code is removed.
+///
+/// ## Level 3
+///
+/// In level 3, it removes commands that are never reached and hearts that never jump.
+/// Then, commands between hearts are folded into pushing constants when the values are known.
+/// Stacks that are never read are dropped, and stacks whose lifetimes never overlap share an index.
+/// Simple counting loops that only make affine updates on stacks are run in closed form.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::{parse, optimize};
+/// use hyeong::core::state::{Output, State};
+///
+/// let a = parse::parse(String::from("형... 항. 형.. 흣.. 형... 항."));
+/// let (s, c) = optimize::optimize(a, 2).unwrap();
+///
+/// assert_eq!(
+///     &[
+///         Output::Stdout(String::from("\u{3}")),
+///         Output::Stderr(String::from("2")),
+///         Output::Stdout(String::from("\u{3}")),
+///     ],
+///     s.get_output()
+/// );
+/// ```
+pub fn optimize(code: Vec<UnOptCode>, level: u8) -> Result<(OptState, Vec<OptCode>), Error> {
+    let (program, _) = PassManager::from_level(level).run(Program::new(code))?;
+    Ok((program.state, program.code))
+}
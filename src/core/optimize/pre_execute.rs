@@ -0,0 +1,390 @@
+use crate::core::area;
+use crate::core::code::{self, Code};
+use crate::core::execute::{pop_stack_wrap, push_stack_wrap, run_loop};
+use crate::core::optimize::{Pass, PassStats, Program};
+use crate::core::state::{Output, State};
+use crate::number::num::Num;
+use crate::util::error::Error;
+use std::cell::RefCell;
+use std::io::{stdin, Write};
+use std::{fmt, io};
+
+/// Default number of commands to execute in pre-execution
+pub const DEFAULT_STEPS: usize = 10000;
+
+/// Reason why pre-execution stopped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// command pops from stack 0
+    Input,
+    /// too many commands are executed
+    StepBudget,
+    /// command pops from stack 1 or 2, which terminates the program
+    Exit,
+    /// area pops from stack 0, so where to jump depends on input
+    UnknownJump,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::Input => write!(f, "input needed"),
+            StopReason::StepBudget => write!(f, "step budget exceeded"),
+            StopReason::Exit => write!(f, "exit stack popped"),
+            StopReason::UnknownJump => write!(f, "unknown jump"),
+        }
+    }
+}
+
+/// Where and why pre-execution stopped
+///
+/// Both locations are the index of command in the whole program.
+/// `at` is the command that could not be executed,
+/// and `resume` is the first command that is left to runtime.
+/// They differ when the program jumped back to `at`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stop {
+    pub at: usize,
+    pub resume: usize,
+    pub reason: StopReason,
+}
+
+impl fmt::Display for Stop {
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::core::optimize::pre_execute::{Stop, StopReason};
+    ///
+    /// let a = Stop { at: 3, resume: 5, reason: StopReason::Input };
+    ///
+    /// assert_eq!("input needed at command 3, runtime starts at command 5", a.to_string());
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at command {}, runtime starts at command {}",
+            self.reason, self.at, self.resume
+        )
+    }
+}
+
+/// Writer that records the output in order
+struct LogWriter<'a> {
+    log: &'a RefCell<Vec<Output>>,
+    err: bool,
+}
+
+impl Write for LogWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let s = String::from_utf8(buf.to_vec())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.log.borrow_mut().push(if self.err {
+            Output::Stderr(s)
+        } else {
+            Output::Stdout(s)
+        });
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reason of stop when popping from `stack` is not possible in pre-execution
+fn blocked(stack: usize) -> Option<StopReason> {
+    match stack {
+        0 => Some(StopReason::Input),
+        1 | 2 => Some(StopReason::Exit),
+        _ => None,
+    }
+}
+
+/// Optimization helper function for level 2 optimization
+///
+/// It executes `code` on the clone of `state`, decreasing `steps` for each command executed.
+/// When it fails, it returns the location of command and the reason.
+fn opt_execute<T>(
+    out: &mut impl Write,
+    err: &mut impl Write,
+    state: &T,
+    code: &T::CodeType,
+    steps: &mut usize,
+) -> Result<Result<T, (usize, StopReason)>, Error>
+where
+    T: State + Clone,
+{
+    let mut state = state.clone();
+    let mut cur_loc = state.push_code((*code).clone());
+    let length = cur_loc + 1;
+    while cur_loc < length {
+        if *steps == 0 {
+            return Ok(Err((cur_loc, StopReason::StepBudget)));
+        }
+        *steps -= 1;
+
+        let code = (*state.get_code(cur_loc)).clone();
+        let mut cur_stack = state.current_stack();
+
+        if code.get_type() != 0 && (code.get_type() == 5 || code.get_hangul_count() > 0) {
+            if let Some(reason) = blocked(cur_stack) {
+                return Ok(Err((cur_loc, reason)));
+            }
+        }
+
+        match code.get_type() {
+            0 => {
+                push_stack_wrap(
+                    out,
+                    err,
+                    &mut state,
+                    cur_stack,
+                    &Num::from_num(code.get_hangul_count() as isize)
+                        * &Num::from_num(code.get_dot_count() as isize),
+                )?;
+            }
+            1 => {
+                let mut n = Num::zero();
+                for _ in 0..code.get_hangul_count() {
+                    n += &pop_stack_wrap(&mut stdin(), out, err, &mut state, cur_stack)?;
+                }
+                push_stack_wrap(out, err, &mut state, code.get_dot_count(), n)?;
+            }
+            2 => {
+                let mut n = Num::one();
+                for _ in 0..code.get_hangul_count() {
+                    n *= &pop_stack_wrap(&mut stdin(), out, err, &mut state, cur_stack)?;
+                }
+                push_stack_wrap(out, err, &mut state, code.get_dot_count(), n)?;
+            }
+            3 => {
+                let mut n = Num::zero();
+                let mut v = Vec::with_capacity(code.get_hangul_count());
+
+                for _ in 0..code.get_hangul_count() {
+                    v.push(pop_stack_wrap(
+                        &mut stdin(),
+                        out,
+                        err,
+                        &mut state,
+                        cur_stack,
+                    )?);
+                }
+
+                v.reverse();
+
+                for mut x in v {
+                    x.minus();
+                    n += &x;
+                    push_stack_wrap(out, err, &mut state, cur_stack, x)?;
+                }
+
+                push_stack_wrap(out, err, &mut state, code.get_dot_count(), n)?;
+            }
+            4 => {
+                let mut n = Num::one();
+                let mut v = Vec::with_capacity(code.get_hangul_count());
+
+                for _ in 0..code.get_hangul_count() {
+                    v.push(pop_stack_wrap(
+                        &mut stdin(),
+                        out,
+                        err,
+                        &mut state,
+                        cur_stack,
+                    )?);
+                }
+
+                v.reverse();
+
+                for mut x in v {
+                    x.flip();
+                    n *= &x;
+                    push_stack_wrap(out, err, &mut state, cur_stack, x)?;
+                }
+
+                push_stack_wrap(out, err, &mut state, code.get_dot_count(), n)?;
+            }
+            5 => {
+                let n = pop_stack_wrap(&mut stdin(), out, err, &mut state, cur_stack)?;
+                for _ in 0..code.get_hangul_count() {
+                    push_stack_wrap(out, err, &mut state, code.get_dot_count(), n.clone())?;
+                }
+                push_stack_wrap(out, err, &mut state, cur_stack, n)?;
+                state.set_current_stack(code.get_dot_count());
+            }
+            code::PUSH_CONST => {
+                push_stack_wrap(
+                    out,
+                    err,
+                    &mut state,
+                    code.get_dot_count(),
+                    code.get_value().unwrap().clone(),
+                )?;
+            }
+            code::ACCELERATE => {
+                if let Some(next) = run_loop(&mut state, &code, cur_loc) {
+                    cur_loc = next;
+                    continue;
+                }
+            }
+            // MOVE_TO
+            _ => state.set_current_stack(code.get_dot_count()),
+        }
+
+        cur_stack = state.current_stack();
+        let mut stop = None;
+        let area_type = area::calc(code.get_area(), code.get_area_count(), || {
+            if let Some(reason) = blocked(cur_stack) {
+                stop = Some(match reason {
+                    StopReason::Input => StopReason::UnknownJump,
+                    t => t,
+                });
+                Err(Error::new("", ""))
+            } else {
+                pop_stack_wrap(&mut stdin(), out, err, &mut state, cur_stack)
+            }
+        });
+        let area_type = match (area_type, stop) {
+            (Ok(value), _) => value,
+            (Err(_), Some(reason)) => return Ok(Err((cur_loc, reason))),
+            (Err(e), None) => return Err(e),
+        };
+
+        if area_type != 0 {
+            if area_type != 13 {
+                let id = ((code.get_area_count() as u128) << 4) + area_type as u128;
+                match state.get_point(id) {
+                    Some(value) => {
+                        if cur_loc != value {
+                            state.set_latest_loc(cur_loc);
+                            cur_loc = value;
+                            continue;
+                        }
+                    }
+                    None => state.set_point(id, cur_loc),
+                }
+            } else if let Some(loc) = state.get_latest_loc() {
+                cur_loc = loc;
+                continue;
+            }
+        }
+
+        cur_loc += 1;
+    }
+
+    Ok(Ok(state))
+}
+
+/// Pre-execute `program` as far as possible
+///
+/// It runs code until it gets input, terminates, or executes more than `steps` commands.
+/// Each command is either executed completely, or left to runtime with everything after it.
+/// The output is saved in the output log of state, keeping the order of stdout and stderr.
+/// Returns where and why it stopped, or `None` if every command is executed.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::optimize::pre_execute::{self, StopReason};
+/// use hyeong::core::optimize::Program;
+/// use hyeong::core::parse;
+///
+/// let mut p = Program::new(parse::parse(String::from("형... 항. 흑 항")));
+/// let stop = pre_execute::pre_execute(&mut p, 100).unwrap().unwrap();
+///
+/// assert_eq!(1, p.code.len());
+/// assert_eq!(StopReason::Input, stop.reason);
+/// assert_eq!(3, stop.at);
+/// ```
+pub fn pre_execute(program: &mut Program, mut steps: usize) -> Result<Option<Stop>, Error> {
+    let base = program.state.get_all_code().len();
+
+    let mut stop = None;
+    let mut idx = program.code.len();
+    for (i, opt_code) in program.code.iter().enumerate() {
+        // output of failed command should not be saved
+        let log = RefCell::new(Vec::new());
+        let mut out = LogWriter {
+            log: &log,
+            err: false,
+        };
+        let mut err = LogWriter {
+            log: &log,
+            err: true,
+        };
+        match opt_execute(&mut out, &mut err, &program.state, opt_code, &mut steps)? {
+            Ok(state) => {
+                program.state = state;
+                for output in log.into_inner() {
+                    program.state.push_output(output);
+                }
+            }
+            Err((at, reason)) => {
+                stop = Some(Stop {
+                    at,
+                    resume: base + i,
+                    reason,
+                });
+                idx = i;
+                break;
+            }
+        }
+    }
+    program.code = program.code[idx..].to_vec();
+    Ok(stop)
+}
+
+/// Pre-execution pass (level 2)
+///
+/// It runs [pre_execute](fn.pre_execute.html) with the step budget,
+/// and notes where and why it stopped.
+/// So, `Hello, World!` code compiles to `print!("Hello, World!")` when unused code is removed.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::optimize::{pre_execute, Pass, PassStats, Program};
+/// use hyeong::core::parse;
+/// use hyeong::core::state::{Output, State};
+///
+/// let mut p = Program::new(parse::parse(String::from("형... 항. 흑 항")));
+/// let mut stats = PassStats::default();
+/// pre_execute::PreExecute::new(100).run(&mut p, &mut stats).unwrap();
+///
+/// assert_eq!(1, p.code.len());
+/// assert_eq!(&[Output::Stdout(String::from("\u{3}"))], p.state.get_output());
+/// assert_eq!(
+///     vec!["input needed at command 3, runtime starts at command 3"],
+///     stats.notes
+/// );
+/// ```
+pub struct PreExecute {
+    steps: usize,
+}
+
+impl PreExecute {
+    /// Make new `PreExecute` pass executing at most `steps` commands
+    pub fn new(steps: usize) -> PreExecute {
+        PreExecute { steps }
+    }
+}
+
+impl Default for PreExecute {
+    fn default() -> Self {
+        Self::new(DEFAULT_STEPS)
+    }
+}
+
+impl Pass for PreExecute {
+    fn name(&self) -> &'static str {
+        "pre-execute"
+    }
+
+    fn run(&self, program: &mut Program, stats: &mut PassStats) -> Result<(), Error> {
+        if let Some(stop) = pre_execute(program, self.steps)? {
+            stats.notes.push(stop.to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use crate::core::code::{Code, OptCode};
+use crate::core::optimize::{Pass, PassStats, Program};
+use crate::core::state::{OptState, State};
+use crate::util::error::Error;
+use std::collections::HashMap;
+
+/// Stack renumbering pass (level 1)
+///
+/// It analyzes the hyeong code and collect only used index of stack.
+/// Then, re-number the indices not to make `HashMap`; using `Vec`.
+/// Stacks that never become the current stack are only written,
+/// so they are all merged into one stack.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::optimize::{renumber, Pass, PassStats, Program};
+/// use hyeong::core::code::Code;
+/// use hyeong::core::parse;
+/// use hyeong::core::state::State;
+///
+/// let mut p = Program::new(parse::parse(String::from("형 흑.......... 형 항..... 형 항.......")));
+/// renumber::RenumberStacks.run(&mut p, &mut PassStats::default()).unwrap();
+///
+/// assert_eq!(6, p.state.stack_size());
+/// assert_eq!(4, p.code[1].get_dot_count());
+/// assert_eq!(5, p.code[3].get_dot_count());
+/// assert_eq!(5, p.code[5].get_dot_count());
+/// ```
+pub struct RenumberStacks;
+
+impl Pass for RenumberStacks {
+    fn name(&self) -> &'static str {
+        "renumber-stacks"
+    }
+
+    fn run(&self, program: &mut Program, _stats: &mut PassStats) -> Result<(), Error> {
+        if !program.state.get_all_code().is_empty() {
+            return Err(Error::new(
+                "renumber-stacks should run before executing any command",
+                "move renumber-stacks to the front of the pipeline",
+            ));
+        }
+
+        let mut dot_map: HashMap<usize, usize> = HashMap::new();
+        let mut max: usize = 4;
+        let mut now = 3;
+        let mut chk = Vec::new();
+
+        for code in &program.code {
+            if code.get_type() == 0 {
+                continue;
+            }
+            chk.push(now);
+            if code.get_type() == 5 {
+                now = code.get_dot_count();
+            }
+        }
+
+        chk.sort_unstable();
+        for i in chk {
+            if i <= 3 {
+                continue;
+            }
+            let temp = dot_map.entry(i).or_insert(0);
+            if *temp == 0 {
+                *temp = max;
+                max += 1;
+            }
+        }
+
+        for code in program.code.iter_mut() {
+            if code.get_type() == 0 || code.get_dot_count() <= 3 {
+                continue;
+            }
+            let temp = dot_map.entry(code.get_dot_count()).or_insert(0);
+            if *temp == 0 {
+                *temp = max;
+            }
+            *code = OptCode::new(
+                code.get_type(),
+                code.get_hangul_count(),
+                *temp,
+                code.get_area_count(),
+                code.get_area().clone(),
+            )
+            .origin(code.get_origin());
+        }
+
+        for s in program.stack_map.values_mut() {
+            if let Some(&t) = dot_map.get(s) {
+                *s = t;
+            }
+        }
+        program.state = OptState::new(max + 1);
+        Ok(())
+    }
+}
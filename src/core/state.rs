@@ -1,8 +1,17 @@
 use crate::core::code::{Code, OptCode, UnOptCode};
-use crate::number::num::Num;
+use crate::number::num::{Format, Num};
 use std::collections::HashMap;
 use std::fmt;
 
+/// Output written before runtime, such as the output of pre-execution
+///
+/// The order of `Output`s is the order they should be printed in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Output {
+    Stdout(String),
+    Stderr(String),
+}
+
 /// State trait
 ///
 /// It defines methods that state structure needs.
@@ -49,6 +58,21 @@ pub trait State {
     fn set_latest_loc(&mut self, loc: usize);
 
     fn get_latest_loc(&self) -> Option<usize>;
+
+    fn get_output(&self) -> &[Output];
+
+    fn push_output(&mut self, output: Output);
+
+    fn clear_output(&mut self);
+}
+
+/// Push `output` to `v`, merging with the last one if they are written to the same stream
+fn push_output_to(v: &mut Vec<Output>, output: Output) {
+    match (v.last_mut(), output) {
+        (Some(Output::Stdout(a)), Output::Stdout(b))
+        | (Some(Output::Stderr(a)), Output::Stderr(b)) => a.push_str(&b),
+        (_, output) => v.push(output),
+    }
 }
 
 /// State structure for optimized code
@@ -70,6 +94,7 @@ pub struct OptState {
     point: HashMap<u128, usize>,
     cur: usize,
     latest: Option<usize>,
+    output: Vec<Output>,
 }
 
 impl OptState {
@@ -90,6 +115,7 @@ impl OptState {
             point: HashMap::new(),
             cur: 3,
             latest: None,
+            output: Vec::new(),
         }
     }
 }
@@ -185,6 +211,21 @@ impl State for OptState {
     fn get_latest_loc(&self) -> Option<usize> {
         self.latest
     }
+
+    /// Return output log
+    fn get_output(&self) -> &[Output] {
+        &self.output
+    }
+
+    /// Push to output log
+    fn push_output(&mut self, output: Output) {
+        push_output_to(&mut self.output, output);
+    }
+
+    /// Clear output log
+    fn clear_output(&mut self) {
+        self.output.clear();
+    }
 }
 
 /// State structure for unoptimized state
@@ -203,6 +244,7 @@ pub struct UnOptState {
     point: HashMap<u128, usize>,
     cur: usize,
     latest: Option<usize>,
+    output: Vec<Output>,
 }
 
 impl UnOptState {
@@ -222,8 +264,39 @@ impl UnOptState {
             point: HashMap::new(),
             cur: 3,
             latest: None,
+            output: Vec::new(),
         }
     }
+
+    /// Make string of current stack and all stacks, printing numbers in `format`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::core::state::{UnOptState, State};
+    /// use hyeong::number::num::{Format, Num};
+    ///
+    /// let mut a = UnOptState::new();
+    /// a.push_stack(3, Num::new(10, 3));
+    /// a.push_stack(3, Num::one());
+    /// assert_eq!(
+    ///     "current stack: 3\nstack 3: [3.(3), 1]\n",
+    ///     a.to_string_format(Format::Repeating(10))
+    /// );
+    /// ```
+    pub fn to_string_format(&self, format: Format) -> String {
+        let mut s = format!("current stack: {}\n", self.cur);
+        let mut v = self.stack.iter().collect::<Vec<_>>();
+        v.sort_by(|x, y| x.0.cmp(y.0));
+        for (a, b) in v {
+            let nums = b
+                .iter()
+                .map(|x| x.to_string_format(format))
+                .collect::<Vec<_>>();
+            s.push_str(&format!("stack {}: [{}]\n", a, nums.join(", ")));
+        }
+        s
+    }
 }
 
 impl Default for UnOptState {
@@ -261,7 +334,7 @@ impl State for UnOptState {
 
     /// Return stack
     fn get_stack(&mut self, idx: usize) -> &mut Vec<Num> {
-        self.stack.entry(idx).or_insert_with(Vec::new)
+        self.stack.entry(idx).or_default()
     }
 
     /// Return code
@@ -308,6 +381,21 @@ impl State for UnOptState {
     fn get_latest_loc(&self) -> Option<usize> {
         self.latest
     }
+
+    /// Return output log
+    fn get_output(&self) -> &[Output] {
+        &self.output
+    }
+
+    /// Push to output log
+    fn push_output(&mut self, output: Output) {
+        push_output_to(&mut self.output, output);
+    }
+
+    /// Clear output log
+    fn clear_output(&mut self) {
+        self.output.clear();
+    }
 }
 
 impl fmt::Debug for UnOptState {
@@ -324,12 +412,6 @@ impl fmt::Debug for UnOptState {
     /// assert_eq!("current stack: 3\nstack 3: [1]\n", format!("{:?}", a));
     /// ```
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut s = format!("current stack: {}\n", self.cur);
-        let mut v = self.stack.iter().collect::<Vec<_>>();
-        v.sort_by(|x, y| x.0.cmp(y.0));
-        for (a, b) in v {
-            s.push_str(&*format!("stack {}: {:?}\n", a, b));
-        }
-        write!(f, "{}", s)
+        write!(f, "{}", self.to_string_format(Format::Fraction))
     }
 }
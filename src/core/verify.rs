@@ -0,0 +1,433 @@
+use crate::core::area::Area;
+use crate::core::code::{Code, OptCode, UnOptCode};
+use crate::core::execute;
+use crate::core::optimize::dead_code::{area_pops, command_pops};
+use crate::core::optimize::{self, PassManager, Program};
+use crate::core::state::{OptState, Output, State, UnOptState};
+use crate::util::error::Error;
+use crate::util::io::ReadLine;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+/// Default number of commands executed on each side before giving up
+pub const DEFAULT_STEPS: usize = 1_000_000;
+
+/// Number of commands kept to show where the runs diverged
+const HISTORY: usize = 8;
+
+/// How a run ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// exited with the status code, including running off the end of code
+    Exit(i32),
+    /// stopped with the error message
+    Error(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Exit(c) => write!(f, "exit with status {}", c),
+            Outcome::Error(e) => write!(f, "error \"{}\"", e),
+        }
+    }
+}
+
+/// Where the optimized run differs from the unoptimized run
+///
+/// Both lists of commands end with the last command executed before the difference was found.
+#[derive(Clone, Debug)]
+pub struct Divergence {
+    /// what differs
+    pub reason: String,
+    /// commands executed at level 0 since the last point where both runs matched
+    pub un_opt: Vec<String>,
+    /// commands executed by the optimized program since then
+    pub opt: Vec<String>,
+}
+
+impl fmt::Display for Divergence {
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::core::verify::Divergence;
+    ///
+    /// let d = Divergence {
+    ///     reason: String::from("stdout differs"),
+    ///     un_opt: vec![String::from("1 | 항. at 1:4")],
+    ///     opt: vec![String::from("1 | push 3 to 1")],
+    /// };
+    ///
+    /// assert_eq!(
+    ///     "stdout differs\nlevel 0 ran:\n  1 | 항. at 1:4\noptimized ran:\n  1 | push 3 to 1\n",
+    ///     d.to_string()
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.reason)?;
+        writeln!(f, "level 0 ran:")?;
+        for c in &self.un_opt {
+            writeln!(f, "  {}", c)?;
+        }
+        writeln!(f, "optimized ran:")?;
+        for c in &self.opt {
+            writeln!(f, "  {}", c)?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of verification
+#[derive(Clone, Debug)]
+pub enum Verdict {
+    /// both runs ended in the same way
+    Same(Outcome),
+    /// runs differ
+    Diverged(Divergence),
+    /// one of the runs did not end within the step limit
+    Unknown,
+}
+
+/// Input given line by line, keeping the line break like standard input
+struct Input {
+    lines: Vec<String>,
+    idx: usize,
+}
+
+impl ReadLine for Input {
+    fn read_line_(&mut self) -> Result<String, Error> {
+        self.idx += 1;
+        Ok(self.lines.get(self.idx - 1).cloned().unwrap_or_default())
+    }
+}
+
+/// Program run step by step, which records how it ended instead of exiting the process
+struct Runner<T: State> {
+    state: T,
+    loc: usize,
+    len: usize,
+    input: Input,
+    out: Vec<u8>,
+    err: Vec<u8>,
+    end: Option<Outcome>,
+    history: VecDeque<usize>,
+}
+
+impl<T: State> Runner<T> {
+    fn new(state: T, loc: usize, input: &str) -> Runner<T> {
+        Runner {
+            len: state.get_all_code().len(),
+            state,
+            loc,
+            input: Input {
+                lines: input.split_inclusive('\n').map(String::from).collect(),
+                idx: 0,
+            },
+            out: Vec::new(),
+            err: Vec::new(),
+            end: None,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Execute one command.
+    /// Popping stack 1 or 2 ends the run before the value is popped.
+    fn step(&mut self) {
+        if self.end.is_some() {
+            return;
+        }
+        if self.loc >= self.len {
+            self.end = Some(Outcome::Exit(0));
+            return;
+        }
+        if self.history.len() == HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.loc);
+
+        let c = self.state.get_code(self.loc).clone();
+        let cur = self.state.current_stack();
+        if command_pops(&c) && (cur == 1 || cur == 2) {
+            self.end = Some(Outcome::Exit(cur as i32 - 1));
+            return;
+        }
+
+        let res = execute::execute_command(
+            &mut self.input,
+            &mut self.out,
+            &mut self.err,
+            &mut self.state,
+            &c,
+            self.loc,
+        )
+        .and_then(|next| match next {
+            Some(next) => Ok(next),
+            None => {
+                let cur = self.state.current_stack();
+                if area_pops(c.get_area()) && (cur == 1 || cur == 2) {
+                    self.end = Some(Outcome::Exit(cur as i32 - 1));
+                    return Ok(self.loc);
+                }
+                execute::execute_area(
+                    &mut self.input,
+                    &mut self.out,
+                    &mut self.err,
+                    &mut self.state,
+                    &c,
+                    self.loc,
+                )
+            }
+        });
+        match res {
+            Ok(next) => self.loc = next,
+            Err(e) => self.end = Some(Outcome::Error(e.get_msg())),
+        }
+    }
+}
+
+/// Check if area `o` is area `u` with some hearts removed
+fn same_area(u: &Area, o: &Area) -> bool {
+    match (u, o) {
+        (_, Area::Nil) => true,
+        (
+            Area::Val {
+                type_: a,
+                left: ul,
+                right: ur,
+            },
+            Area::Val {
+                type_: b,
+                left: ol,
+                right: or,
+            },
+        ) => a == b && same_area(ul, ol) && same_area(ur, or),
+        _ => false,
+    }
+}
+
+/// Check if optimized command `o` is unoptimized command `u` with stacks renumbered
+fn same_command(u: &UnOptCode, o: &OptCode, stack_map: &BTreeMap<usize, usize>) -> bool {
+    o.get_type() <= 5
+        && u.get_type() == o.get_type()
+        && u.get_hangul_count() == o.get_hangul_count()
+        && u.get_area_count() == o.get_area_count()
+        && same_area(u.get_area(), o.get_area())
+        && if u.get_type() == 0 {
+            u.get_dot_count() == o.get_dot_count()
+        } else {
+            stack_map.get(&u.get_dot_count()) == Some(&o.get_dot_count())
+        }
+}
+
+/// Unoptimized command that each optimized command corresponds to
+///
+/// Commands executed while optimizing are the first ones of the source.
+/// The rest are matched in order, and a command changed by optimization has no match.
+/// Commands made by optimization may replace several commands of the source,
+/// so the command after them is searched ahead.
+fn align(code: &[UnOptCode], program: &Program, start: usize) -> Vec<Option<usize>> {
+    let mut res = (0..start).map(Some).collect::<Vec<_>>();
+    let mut s = start;
+    let mut replaced = false;
+    for o in &program.code {
+        if o.get_type() > 5 {
+            res.push(None);
+            replaced = true;
+            continue;
+        }
+        if replaced {
+            let found = (s..code.len()).find(|&i| same_command(&code[i], o, &program.stack_map));
+            res.push(found);
+            if let Some(i) = found {
+                s = i + 1;
+                replaced = false;
+            }
+        } else {
+            let ok = s < code.len() && same_command(&code[s], o, &program.stack_map);
+            res.push(Some(s).filter(|_| ok));
+            s += 1;
+        }
+    }
+    res
+}
+
+/// Compare two runs at corresponding commands
+///
+/// Stacks merged into one by optimization are not compared,
+/// since they are only written or have values at different times.
+/// If they are wrong, it shows up later in output or exit status.
+fn compare(
+    u: &mut Runner<UnOptState>,
+    o: &mut Runner<OptState>,
+    stack_map: &BTreeMap<usize, usize>,
+) -> Option<String> {
+    if u.out != o.out {
+        return Some(format!(
+            "stdout differs: {:?} at level 0, {:?} optimized",
+            String::from_utf8_lossy(&u.out),
+            String::from_utf8_lossy(&o.out)
+        ));
+    }
+    if u.err != o.err {
+        return Some(format!(
+            "stderr differs: {:?} at level 0, {:?} optimized",
+            String::from_utf8_lossy(&u.err),
+            String::from_utf8_lossy(&o.err)
+        ));
+    }
+    if u.end != o.end {
+        let show = |e: &Option<Outcome>| match e {
+            Some(e) => e.to_string(),
+            None => String::from("still running"),
+        };
+        return Some(format!(
+            "{} at level 0, {} optimized",
+            show(&u.end),
+            show(&o.end)
+        ));
+    }
+    if u.end.is_some() {
+        return None;
+    }
+
+    let size = o.state.stack_size();
+    let cur = u.state.current_stack();
+    if let Some(&t) = stack_map.get(&cur) {
+        if t < size && t != o.state.current_stack() {
+            return Some(format!(
+                "current stack differs: {} at level 0 is {}, but {} optimized",
+                cur,
+                t,
+                o.state.current_stack()
+            ));
+        }
+    }
+
+    let mut sources: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (&s, &t) in stack_map {
+        if t < size {
+            sources.entry(t).or_default().push(s);
+        }
+    }
+    for (t, v) in sources {
+        if let [s] = v[..] {
+            let expected = u.state.get_stack(s).clone();
+            let actual = o.state.get_stack(t).clone();
+            if expected != actual {
+                return Some(format!(
+                    "stack {} differs: {:?} at level 0, {:?} optimized as stack {}",
+                    s, expected, actual, t
+                ));
+            }
+        }
+    }
+    None
+}
+
+/// Run `code` at level 0 and optimized with `pm`, comparing the two runs
+///
+/// The optimized program starts from the state left by pre-execution,
+/// so level 0 first runs until it reaches the first command left to runtime.
+/// After that, each time the optimized run gets to a command that corresponds to a command of the source,
+/// level 0 runs until it gets to that command.
+/// There, output, exit status, current stack and stacks are compared.
+/// Each run executes at most `max_steps` commands.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::optimize::PassManager;
+/// use hyeong::core::parse;
+/// use hyeong::core::verify::{self, Outcome, Verdict};
+///
+/// let code = parse::parse(String::from("흑. 흑... 형 형 형 형 하앙.. 흑 흑."));
+/// let pm = PassManager::from_level(3);
+/// let res = verify::verify(&code, &pm, "a\n", verify::DEFAULT_STEPS).unwrap();
+///
+/// assert!(matches!(res, Verdict::Same(Outcome::Exit(0))));
+/// ```
+pub fn verify(
+    code: &[UnOptCode],
+    pm: &PassManager,
+    input: &str,
+    max_steps: usize,
+) -> Result<Verdict, Error> {
+    let (program, _) = pm.run(Program::new(code.to_vec()))?;
+
+    let mut state = UnOptState::new();
+    for c in code {
+        state.push_code(c.clone());
+    }
+    let mut u = Runner::new(state, 0, input);
+
+    let mut state = program.state.clone();
+    let start = state.get_all_code().len();
+    for c in &program.code {
+        state.push_code(c.clone());
+    }
+    let mut o = Runner::new(state, start, input);
+    for output in program.state.get_output() {
+        match output {
+            Output::Stdout(s) => o.out.extend(s.as_bytes()),
+            Output::Stderr(s) => o.err.extend(s.as_bytes()),
+        }
+    }
+    o.state.clear_output();
+    let origin = align(code, &program, start);
+
+    let mut target = Some(start);
+    let (mut u_steps, mut o_steps) = (0, 0);
+    let mut must_step = false;
+    loop {
+        // after the first match, level 0 has to run the command that matched
+        while u.end.is_none() && (must_step || target != Some(u.loc)) {
+            must_step = false;
+            if u_steps == max_steps {
+                return Ok(Verdict::Unknown);
+            }
+            u.step();
+            u_steps += 1;
+        }
+
+        if let Some(reason) = compare(&mut u, &mut o, &program.stack_map) {
+            return Ok(Verdict::Diverged(Divergence {
+                reason,
+                un_opt: u
+                    .history
+                    .iter()
+                    .map(|&i| {
+                        let c = &code[i];
+                        let (line, col) = c.get_location();
+                        format!("{} | {} at {}:{}", i, c.get_raw(), line, col)
+                    })
+                    .collect(),
+                opt: o
+                    .history
+                    .iter()
+                    .map(|&i| format!("{} | {}", i, optimize::command_string(o.state.get_code(i))))
+                    .collect(),
+            }));
+        }
+        if let Some(end) = &u.end {
+            return Ok(Verdict::Same(end.clone()));
+        }
+        u.history.clear();
+        o.history.clear();
+        must_step = true;
+
+        loop {
+            if o_steps == max_steps {
+                return Ok(Verdict::Unknown);
+            }
+            o.step();
+            o_steps += 1;
+            target = origin.get(o.loc).copied().flatten();
+            if o.end.is_some() || target.is_some() {
+                break;
+            }
+        }
+        if o.end.is_some() {
+            target = None;
+        }
+    }
+}
@@ -1,3 +1,7 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 #[cfg(not(feature = "number"))]
 pub mod app;
 #[cfg(not(feature = "number"))]
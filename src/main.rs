@@ -1,7 +1,7 @@
 #[cfg(not(feature = "number"))]
 use clap::{App, ArgMatches};
 #[cfg(not(feature = "number"))]
-use hyeong::app::{build, check, debug, init, interpreter, run};
+use hyeong::app::{build, check, debug, init, interpreter, run, verify};
 #[cfg(not(feature = "number"))]
 use hyeong::util::{error::Error, io, option, option::HyeongOption};
 #[cfg(not(feature = "number"))]
@@ -22,22 +22,56 @@ fn sub_main(
         build::run(
             stdout,
             &hy_opt
+                .backend(option::parse_backend(matches))
                 .build_path(option::parse_build_path(matches)?)
+                .dump_after(option::parse_dump_after(matches))
+                .emit(option::parse_emit(matches))
+                .emit_only(option::parse_emit_only(matches))
+                .explain_opt(option::parse_explain_opt(matches))
                 .input(input)
                 .optimize(option::parse_optimize(matches)?)
-                .output(output),
+                .pre_exec_steps(option::parse_pre_exec_steps(matches)?)
+                .output(output)
+                .profile(option::parse_profile(matches))
+                .opt_level(option::parse_opt_level(matches))
+                .lto(option::parse_lto(matches))
+                .panic_abort(option::parse_panic_abort(matches))
+                .runtime_flags(option::parse_runtime_flags(matches))
+                .rustflags(option::parse_rustflags(matches)),
         )
     } else if let Some(matches) = matches.subcommand_matches("check") {
-        check::run(stdout, &hy_opt.input(option::parse_input(matches)?))
+        check::run(
+            stdout,
+            &hy_opt
+                .input(option::parse_input(matches)?)
+                .num_format(option::parse_num_format(matches)?),
+        )
     } else if let Some(matches) = matches.subcommand_matches("debug") {
-        debug::run(stdout, &hy_opt.input(option::parse_input(matches)?))
+        debug::run(
+            stdout,
+            &hy_opt
+                .input(option::parse_input(matches)?)
+                .num_format(option::parse_num_format(matches)?)
+                .precision(option::parse_precision(matches)?),
+        )
     } else if let Some(matches) = matches.subcommand_matches("run") {
         run::run(
             stdout,
             stderr,
+            &hy_opt
+                .dump_after(option::parse_dump_after(matches))
+                .explain_opt(option::parse_explain_opt(matches))
+                .input(option::parse_input(matches)?)
+                .optimize(option::parse_optimize(matches)?)
+                .pre_exec_steps(option::parse_pre_exec_steps(matches)?),
+        )
+    } else if let Some(matches) = matches.subcommand_matches("verify") {
+        verify::run(
+            stdout,
             &hy_opt
                 .input(option::parse_input(matches)?)
-                .optimize(option::parse_optimize(matches)?),
+                .pre_exec_steps(option::parse_pre_exec_steps(matches)?)
+                .stdin(option::parse_stdin(matches)),
         )
     } else if let Some(matches) = matches.subcommand_matches("install") {
         init::install_run(
@@ -79,6 +113,7 @@ fn sub_main(
 ///     install      Install hyeong before build (need once)
 ///     run          Run hyeong code directly
 ///     uninstall    Uninstall hyeong temporary build path
+///     verify       Check that optimized code runs the same as the source
 /// ```
 #[cfg(not(tarpaulin_include))]
 #[cfg(not(feature = "number"))]
@@ -92,6 +127,7 @@ fn main() {
         .subcommand(check::app())
         .subcommand(debug::app())
         .subcommand(run::app())
+        .subcommand(verify::app())
         .subcommand(init::install_app())
         .subcommand(init::uninstall_app())
         .get_matches();
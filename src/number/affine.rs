@@ -0,0 +1,299 @@
+use crate::number::num::Num;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Affine map on vectors of `Num`
+///
+/// Each row has the coefficients of the input values followed by the constant term,
+/// so the map of `n` values has `n` rows of length `n + 1`.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::number::affine::Affine;
+/// use hyeong::number::num::Num;
+///
+/// // (x, y) -> (x + 1, x + y)
+/// let a = Affine::new(vec![
+///     vec![Num::one(), Num::zero(), Num::one()],
+///     vec![Num::one(), Num::one(), Num::zero()],
+/// ]);
+///
+/// assert_eq!(
+///     vec![Num::from_num(3), Num::from_num(3)],
+///     a.pow(3).apply(&[Num::zero(), Num::zero()])
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Affine {
+    rows: Vec<Vec<Num>>,
+}
+
+/// Value of affine `row` on `x`; the last element of `row` is the constant term
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::number::affine;
+/// use hyeong::number::num::Num;
+///
+/// let row = [Num::from_num(2), Num::from_num(3), Num::from_num(1)];
+/// let x = [Num::from_num(10), Num::from_num(100)];
+///
+/// assert_eq!(Num::from_num(321), affine::eval(&row, &x));
+/// ```
+pub fn eval(row: &[Num], x: &[Num]) -> Num {
+    let mut res = row[x.len()].clone();
+    for (a, b) in row.iter().zip(x) {
+        res += &(a * b);
+    }
+    res
+}
+
+impl Affine {
+    /// Make new `Affine` from rows
+    pub fn new(rows: Vec<Vec<Num>>) -> Affine {
+        Affine { rows }
+    }
+
+    /// Identity map of `n` values
+    pub fn identity(n: usize) -> Affine {
+        Affine {
+            rows: (0..n)
+                .map(|i| {
+                    (0..=n)
+                        .map(|j| if i == j { Num::one() } else { Num::zero() })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Return rows of the map
+    pub fn rows(&self) -> &[Vec<Num>] {
+        &self.rows
+    }
+
+    /// Apply the map to `x`
+    pub fn apply(&self, x: &[Num]) -> Vec<Num> {
+        self.rows.iter().map(|row| eval(row, x)).collect()
+    }
+
+    /// Map that applies `other` first, then `self`
+    pub fn compose(&self, other: &Affine) -> Affine {
+        let n = other.rows.len();
+        let m = other.rows.first().map_or(0, |row| row.len() - 1);
+        Affine {
+            rows: self
+                .rows
+                .iter()
+                .map(|row| {
+                    let mut res = (0..=m).map(|_| Num::zero()).collect::<Vec<_>>();
+                    res[m] = row[n].clone();
+                    for (k, a) in row.iter().take(n).enumerate() {
+                        for (r, b) in res.iter_mut().zip(&other.rows[k]) {
+                            *r += &(a * b);
+                        }
+                    }
+                    res
+                })
+                .collect(),
+        }
+    }
+
+    /// Map applied `n` times, computed by repeated squaring
+    pub fn pow(&self, n: u64) -> Affine {
+        self.pow_num(&Num::from(n))
+    }
+
+    /// Map applied `n` times for nonnegative integer `n` of any size
+    pub fn pow_num(&self, n: &Num) -> Affine {
+        let two = Num::from_num(2);
+        let mut n = n.clone();
+        let mut res = Affine::identity(self.rows.len());
+        let mut base = self.clone();
+        while n > Num::zero() {
+            let half = Num::from((&n / &two).floor());
+            if n != &half * &two {
+                res = base.compose(&res);
+            }
+            base = base.compose(&base);
+            n = half;
+        }
+        res
+    }
+}
+
+/// Loop that only makes affine updates, run in closed form
+///
+/// Variables are the values at the top of some stacks.
+/// The loop repeats `x = step(x)` while comparing `counter(x)` with `value` holds `repeat_if`,
+/// and the comparison is `==` if `equal` is true, otherwise `<`.
+/// Since the counter changes by a constant in each iteration,
+/// the number of iterations is computed directly.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::number::affine::{Affine, AffineLoop};
+/// use hyeong::number::num::Num;
+///
+/// // while x < 10 { x = x + 3 }
+/// let a = AffineLoop::new(
+///     vec![(3, 1)],
+///     Affine::new(vec![vec![Num::one(), Num::from_num(3)]]),
+///     vec![Num::one(), Num::zero()],
+///     Num::from_num(10),
+///     false,
+///     true,
+///     vec![vec![vec![Num::one(), Num::zero()]]],
+/// )
+/// .unwrap();
+///
+/// let mut stacks = vec![Vec::new(); 4];
+/// stacks[3].push(Num::from_num(1));
+///
+/// assert_eq!(Some(Num::from_num(3)), a.run_stacks(&mut stacks));
+/// assert_eq!(vec![Num::from_num(10)], stacks[3]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AffineLoop {
+    /// stack and the number of values at the top used as variables
+    pub windows: Vec<(usize, usize)>,
+    /// one iteration
+    pub step: Affine,
+    /// value compared in each iteration
+    pub counter: Vec<Num>,
+    /// value that counter is compared with
+    pub value: Num,
+    /// `==` if true, otherwise `<`
+    pub equal: bool,
+    /// the loop goes on when the comparison gives this
+    pub repeat_if: bool,
+    /// values at the top of each stack after the loop, bottom first
+    pub exit: Vec<Vec<Vec<Num>>>,
+    diff: Num,
+}
+
+impl AffineLoop {
+    /// Make new `AffineLoop`
+    ///
+    /// Variables are ordered by `windows`, and values in each window are bottom first.
+    /// Returns `None` if the counter does not change by a nonzero constant in each iteration.
+    pub fn new(
+        windows: Vec<(usize, usize)>,
+        step: Affine,
+        counter: Vec<Num>,
+        value: Num,
+        equal: bool,
+        repeat_if: bool,
+        exit: Vec<Vec<Vec<Num>>>,
+    ) -> Option<AffineLoop> {
+        let next = Affine::new(vec![counter.clone()]).compose(&step);
+        let n = counter.len() - 1;
+        if (0..n).any(|i| next.rows[0][i] != counter[i]) {
+            return None;
+        }
+        let diff = &next.rows[0][n] - &counter[n];
+        if diff == Num::zero() {
+            return None;
+        }
+        Some(AffineLoop {
+            windows,
+            step,
+            counter,
+            value,
+            equal,
+            repeat_if,
+            exit,
+            diff,
+        })
+    }
+
+    /// Number of iterations before the comparison fails, or `None` if it never fails
+    pub fn iterations(&self, x: &[Num]) -> Option<Num> {
+        let start = eval(&self.counter, x);
+        let holds = |v: &Num| {
+            if self.equal {
+                *v == self.value
+            } else {
+                *v < self.value
+            }
+        };
+        if holds(&start) != self.repeat_if {
+            return Some(Num::zero());
+        }
+
+        let zero = Num::zero();
+        let k = match (self.equal, self.repeat_if) {
+            // it was equal, and the next one differs
+            (true, true) => return Some(Num::one()),
+            (true, false) => {
+                let k = &(&self.value - &start) / &self.diff;
+                if k < zero || Num::from(k.floor()) != k {
+                    return None;
+                }
+                k
+            }
+            (false, true) => {
+                if self.diff < zero {
+                    return None;
+                }
+                let k = &(&self.value - &start) / &self.diff;
+                let f = Num::from(k.floor());
+                if f == k {
+                    f
+                } else {
+                    &f + &Num::one()
+                }
+            }
+            (false, false) => {
+                if self.diff > zero {
+                    return None;
+                }
+                let k = &(&start - &self.value) / &-&self.diff;
+                &Num::from(k.floor()) + &Num::one()
+            }
+        };
+        Some(k)
+    }
+
+    /// Run the loop on variables `x`.
+    /// Returns the number of iterations and new values of each window,
+    /// or `None` when it cannot run in closed form.
+    pub fn run(&self, x: &[Num]) -> Option<(Num, Vec<Vec<Num>>)> {
+        if x.iter().any(|n| n.is_nan()) {
+            return None;
+        }
+        let k = self.iterations(x)?;
+        let x = self.step.pow_num(&k).apply(x);
+        Some((
+            k,
+            self.exit
+                .iter()
+                .map(|w| w.iter().map(|row| eval(row, &x)).collect())
+                .collect(),
+        ))
+    }
+
+    /// Run the loop on `stacks` and return the number of iterations,
+    /// or `None` without changing stacks when it cannot run in closed form.
+    pub fn run_stacks(&self, stacks: &mut [Vec<Num>]) -> Option<Num> {
+        let mut x = Vec::new();
+        for &(s, w) in &self.windows {
+            let st = stacks.get(s)?;
+            if st.len() < w {
+                return None;
+            }
+            x.extend(st[st.len() - w..].iter().cloned());
+        }
+
+        let (k, res) = self.run(&x)?;
+        for (&(s, w), v) in self.windows.iter().zip(res) {
+            let len = stacks[s].len() - w;
+            stacks[s].truncate(len);
+            stacks[s].extend(v);
+        }
+        Some(k)
+    }
+}
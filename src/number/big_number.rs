@@ -1,8 +1,24 @@
-use std::cmp::{max, min, Ordering};
-use std::{error, fmt, ops};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::{max, min, Ordering};
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+use core::{fmt, iter, ops};
+
+/// Minimum length of both operands to use Karatsuba multiplication
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Minimum length of divisor and quotient to use Newton division
+const NEWTON_THRESHOLD: usize = 64;
+
+/// Maximum length to use quadratic radix conversion
+const CONVERT_THRESHOLD: usize = 32;
 
 /// Error class for `BigNum`
-/// Used in `BigNum::from_string()`, `BigNum::from_string_base()`, `BigNum::to_string_base()`.
+/// Used in `BigNum::from_string()`, `BigNum::from_string_base()`, `BigNum::to_string_base()`
+/// and conversions to primitive types.
 ///
 /// # Examples
 ///
@@ -18,6 +34,7 @@ use std::{error, fmt, ops};
 pub enum Error {
     ParseError,
     BaseSizeError(usize),
+    RangeError,
 }
 
 impl fmt::Display for Error {
@@ -30,16 +47,19 @@ impl fmt::Display for Error {
     ///
     /// assert_eq!("Parse Error", format!("{}", Error::ParseError));
     /// assert_eq!("Base Size Error: size = 100", format!("{}", Error::BaseSizeError(100)));
+    /// assert_eq!("Range Error", format!("{}", Error::RangeError));
     /// ```
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::ParseError => write!(f, "Parse Error"),
             Error::BaseSizeError(s) => write!(f, "Base Size Error: size = {}", s),
+            Error::RangeError => write!(f, "Range Error"),
         }
     }
 }
 
-impl error::Error for Error {}
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
 
 /// `BigNum` for big number handling
 /// - Using `Vec<u32>` for data and using `u32::max_value()` as base of the number
@@ -94,17 +114,7 @@ impl BigNum {
     /// assert_eq!("-4321", b.to_string());
     /// ```
     pub fn new(n: isize) -> BigNum {
-        if n >= 0 {
-            BigNum {
-                pos: true,
-                val: vec![n as u32],
-            }
-        } else {
-            BigNum {
-                pos: false,
-                val: vec![(-n) as u32],
-            }
-        }
+        BigNum::from(n)
     }
 
     /// Makes new `BigNum` from vector
@@ -219,13 +229,11 @@ impl BigNum {
     }
 
     /// Make new `BigNum` from string (10 based)
-    /// It won't make error when the format is not right.
-    /// But it would make unexpected number.
     /// Negative numbers are supported.
     ///
     /// # Time Complexity
     ///
-    /// `O(n^2)` where `n := s.len()`
+    /// `O(M(n) * log(n))` where `n := s.len()` and `M(n)` is the time of `mul`
     ///
     /// # Errors
     ///
@@ -247,22 +255,24 @@ impl BigNum {
     }
 
     /// Make new `BigNum` from string
-    /// It won't make error when the format is not right.
-    /// But it would make unexpected number.
+    /// Both upper and lower case letters are accepted as digits.
     /// Negative numbers are supported.
     ///
     /// # Time Complexity
     ///
-    /// `O(n^2)` where `n := s.len()`
+    /// - `O(n)` when `base` is a power of two
+    /// - `O(M(n) * log(n))` otherwise, where `M(n)` is the time of `mul`
+    ///
+    /// where `n := s.len()`
     ///
     /// # Assertions
     ///
-    /// - `0 < _base <= 36`
+    /// - `2 <= base <= 36`
     ///
     /// # Errors
     ///
     /// - `Error::BaseSizeError(base)`: when base size is not in range
-    /// - `Error::ParseError`: when parse failed
+    /// - `Error::ParseError`: when there is no digit or a character that is not a digit of `base`
     ///
     /// # Examples
     ///
@@ -271,37 +281,51 @@ impl BigNum {
     ///
     /// let a = BigNum::from_string_base(String::from("A"), 16).unwrap();
     /// let b = BigNum::from_string_base(String::from("-1010"), 2).unwrap();
+    /// let c = BigNum::from_string_base(String::from("ff"), 16).unwrap();
     ///
     /// assert_eq!("10", a.to_string());
     /// assert_eq!("-10", b.to_string());
+    /// assert_eq!("255", c.to_string());
     /// ```
-    pub fn from_string_base(s: String, _base: usize) -> Result<BigNum, Error> {
-        if !(1..=36).contains(&_base) {
-            return Result::Err(Error::BaseSizeError(_base));
+    pub fn from_string_base(s: String, base: usize) -> Result<BigNum, Error> {
+        if !(2..=36).contains(&base) {
+            return Result::Err(Error::BaseSizeError(base));
         }
 
-        let base = BigNum::new(_base as isize);
-        let mut res = BigNum::new(0);
-        let mut flip = false;
-
-        for (i, c) in s.chars().enumerate() {
-            if i == 0 && c == '-' {
-                flip = true;
-                continue;
+        let (neg, body) = match s.strip_prefix('-') {
+            Some(t) => (true, t),
+            None => (false, &s[..]),
+        };
+        let mut digits = Vec::with_capacity(body.len());
+        for c in body.chars() {
+            match c.to_digit(base as u32) {
+                Some(d) => digits.push(d),
+                None => return Result::Err(Error::ParseError),
             }
-            let k = if ('0'..='9').contains(&c) {
-                c as isize - '0' as isize
-            } else if ('A'..='Z').contains(&c) {
-                c as isize - 'A' as isize + 10
-            } else {
-                return Result::Err(Error::ParseError);
-            };
-            res *= &base;
-            res += &BigNum::new(k);
         }
+        if digits.is_empty() {
+            return Result::Err(Error::ParseError);
+        }
+
+        let mut res = BigNum::from_vec(if base.is_power_of_two() {
+            BigNum::from_digits_pow2_core(&digits, base.trailing_zeros())
+        } else {
+            let (width, big_base) = BigNum::chunk_size(base);
+            let chunks = digits
+                .rchunks(width)
+                .map(|c| c.iter().fold(0, |acc, &d| acc * base as u32 + d))
+                .collect::<Vec<_>>();
+
+            let mut pows = vec![vec![big_base]];
+            while 1 << pows.len() < chunks.len() {
+                let p = pows.last().unwrap();
+                pows.push(BigNum::mult_core(p, p));
+            }
+            BigNum::from_chunks_core(&chunks, &pows)
+        });
 
-        if flip {
-            res.pos = false;
+        if neg {
+            res.minus();
         }
         Result::Ok(res)
     }
@@ -311,11 +335,14 @@ impl BigNum {
     ///
     /// # Time Complexity
     ///
-    /// `O(n^2)` where `n := res.len()`
+    /// - `O(n)` when `base` is a power of two
+    /// - `O(M(n) * log(n))` otherwise, where `M(n)` is the time of `mul`
+    ///
+    /// where `n := self.val.len()`
     ///
     /// # Assertions
     ///
-    /// - `0 < _base <= 36`
+    /// - `2 <= base <= 36`
     ///
     /// # Errors
     ///
@@ -332,39 +359,205 @@ impl BigNum {
     /// assert_eq!("A", a.to_string_base(16).unwrap());
     /// assert_eq!("-1010", b.to_string_base(2).unwrap());
     /// ```
+    pub fn to_string_base(&self, base: usize) -> Result<String, Error> {
+        if !(2..=36).contains(&base) {
+            return Result::Err(Error::BaseSizeError(base));
+        }
+
+        let val = BigNum::trim_core(&self.val);
+        let mut res = Vec::new();
+        if !self.pos {
+            res.push(b'-');
+        }
+
+        if val.is_empty() {
+            res.push(b'0');
+        } else if base.is_power_of_two() {
+            BigNum::to_digits_pow2_core(val, base.trailing_zeros(), &mut res);
+        } else {
+            let (width, big_base) = BigNum::chunk_size(base);
+            // `val < B^val.len() <= p^2` when `2 * p.len() - 1 > val.len()`,
+            // so the square of the last power is not needed
+            let mut pows = vec![vec![big_base]];
+            while 2 * pows.last().unwrap().len() - 1 <= val.len() {
+                let p = pows.last().unwrap();
+                pows.push(BigNum::trim_core(&BigNum::mult_core(p, p)).to_vec());
+            }
+            if BigNum::less_core(val, pows.last().unwrap()) {
+                pows.pop();
+            }
+
+            let level = pows.len();
+            let pows = pows
+                .into_iter()
+                .map(|p| {
+                    let r = if p.len() >= NEWTON_THRESHOLD {
+                        Option::Some(BigNum::recip_core(&p))
+                    } else {
+                        Option::None
+                    };
+                    (p, r)
+                })
+                .collect::<Vec<_>>();
+            BigNum::to_digits_core(val, level, &pows, base, width, 0, &mut res);
+        }
+
+        Result::Ok(res.into_iter().map(|c| c as char).collect())
+    }
+
+    /// Private function for getting the digit count and the value of the biggest power of `base`
+    /// that fits in one `u32`.
+    fn chunk_size(base: usize) -> (usize, u32) {
+        let mut width = 1;
+        let mut big_base = base as u64;
+        while big_base * base as u64 <= u32::MAX as u64 {
+            big_base *= base as u64;
+            width += 1;
+        }
+        (width, big_base as u32)
+    }
+
+    /// Private function for converting digit value to ascii character.
+    fn digit_to_char(d: u32) -> u8 {
+        if d < 10 {
+            b'0' + d as u8
+        } else {
+            b'A' + d as u8 - 10
+        }
+    }
+
+    /// Private function for packing digits of power of two base into vector.
+    /// `digits` is most significant digit first.
     ///
-    /// # TODO
+    /// # Time Complexity
     ///
-    /// - [Better Algorithm](https://en.wikipedia.org/wiki/Double_dabble)
-    pub fn to_string_base(&self, _base: usize) -> Result<String, Error> {
-        if !(1..=36).contains(&_base) {
-            return Result::Err(Error::BaseSizeError(_base));
+    /// `O(n)` where `n := digits.len()`
+    fn from_digits_pow2_core(digits: &[u32], bits: u32) -> Vec<u32> {
+        let mut v = vec![0; (digits.len() * bits as usize) / 32 + 1];
+
+        for (i, &d) in digits.iter().rev().enumerate() {
+            let pos = i * bits as usize;
+            let (idx, off) = (pos / 32, pos % 32);
+            v[idx] |= d << off;
+            if off + bits as usize > 32 {
+                v[idx + 1] |= d >> (32 - off);
+            }
         }
 
-        let base = BigNum::new(_base as isize);
-        let mut res = String::new();
-        let mut num = self.clone();
-        num.pos = true;
+        v
+    }
 
-        while !num.is_zero() {
-            let k = &num % &base;
-            num /= &base;
+    /// Private function for writing digits of power of two base from vector.
+    /// `val` should not have leading zeros and should not be empty.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := val.len()`
+    fn to_digits_pow2_core(val: &[u32], bits: u32, out: &mut Vec<u8>) {
+        let bits = bits as usize;
+        let bit_len = val.len() * 32 - val.last().unwrap().leading_zeros() as usize;
+        let mask = (1u32 << bits) - 1;
+
+        for i in (0..bit_len.div_ceil(bits)).rev() {
+            let pos = i * bits;
+            let (idx, off) = (pos / 32, pos % 32);
+            let mut d = val[idx] >> off;
+            if off + bits > 32 && idx + 1 < val.len() {
+                d |= val[idx + 1] << (32 - off);
+            }
+            out.push(BigNum::digit_to_char(d & mask));
+        }
+    }
 
-            res.push(if k.val[0] < 10 {
-                (b'0' + k.val[0] as u8) as char
-            } else {
-                (b'A' + k.val[0] as u8 - 10) as char
-            });
+    /// Private function for combining chunks into vector (divide and conquer).
+    /// `chunks` is least significant first and each chunk is a digit of base `pows[0][0]`.
+    /// `pows[i]` is `pows[0][0]^(2^i)`.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(M(n) * log(n))` where `n := chunks.len()`
+    fn from_chunks_core(chunks: &[u32], pows: &[Vec<u32>]) -> Vec<u32> {
+        if chunks.len() <= CONVERT_THRESHOLD {
+            let mut v = vec![0];
+            for &c in chunks.iter().rev() {
+                BigNum::mul_add_small_core(&mut v, pows[0][0], c);
+            }
+            return v;
         }
 
-        if res.is_empty() {
-            res = String::from("0");
+        let level = (usize::BITS - (chunks.len() - 1).leading_zeros() - 1) as usize;
+        let (lo, hi) = chunks.split_at(1 << level);
+        let lo = BigNum::from_chunks_core(lo, pows);
+        let hi = BigNum::from_chunks_core(hi, pows);
+
+        let mut v = BigNum::mult_core(&hi, &pows[level]);
+        v.push(0);
+        BigNum::add_into_core(&mut v, BigNum::trim_core(&lo));
+        v
+    }
+
+    /// Private function for writing digits from vector (divide and conquer).
+    /// `val < base^(width * 2^level)` should hold and `pows[i].0` is `base^(width * 2^i)`.
+    /// If `pad` is not zero, the result is left-padded with zeros to `pad` digits.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(M(n) * log(n))` where `n := val.len()`
+    fn to_digits_core(
+        val: &[u32],
+        level: usize,
+        pows: &[(Vec<u32>, Option<Vec<u32>>)],
+        base: usize,
+        width: usize,
+        pad: usize,
+        out: &mut Vec<u8>,
+    ) {
+        let val = BigNum::trim_core(val);
+
+        if level == 0 || val.len() <= CONVERT_THRESHOLD {
+            let big_base = (base as u32).pow(width as u32);
+            let mut v = val.to_vec();
+            let mut res = Vec::new();
+            while !BigNum::trim_core(&v).is_empty() {
+                let (q, mut r) = BigNum::div_small_core(&v, big_base);
+                v = q;
+                let last = BigNum::trim_core(&v).is_empty();
+                for _ in 0..width {
+                    if last && r == 0 {
+                        break;
+                    }
+                    res.push(BigNum::digit_to_char(r % base as u32));
+                    r /= base as u32;
+                }
+            }
+            while res.len() < pad {
+                res.push(b'0');
+            }
+            out.extend(res.iter().rev());
+            return;
         }
 
-        if !self.pos {
-            res.push('-')
+        let (p, r) = &pows[level - 1];
+        let (hi, lo) = match r {
+            Some(r) => BigNum::div_rem_recip_core(val, p, r),
+            None => BigNum::div_rem_core(val, p),
+        };
+        let digits = width << (level - 1);
+
+        if pad == 0 && BigNum::trim_core(&hi).is_empty() {
+            BigNum::to_digits_core(&lo, level - 1, pows, base, width, 0, out);
+        } else {
+            BigNum::to_digits_core(
+                &hi,
+                level - 1,
+                pows,
+                base,
+                width,
+                pad.saturating_sub(digits),
+                out,
+            );
+            BigNum::to_digits_core(&lo, level - 1, pows, base, width, digits, out);
         }
-        Result::Ok(res.chars().rev().collect())
     }
 
     /// Private function for removing leading zero in data.
@@ -453,61 +646,367 @@ impl BigNum {
         (v, swapped)
     }
 
+    /// Private function for slicing off leading zeros.
+    /// Unlike `shrink_to_fit`, zero becomes an empty slice.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := v.len()`
+    fn trim_core(v: &[u32]) -> &[u32] {
+        let mut n = v.len();
+        while n > 0 && v[n - 1] == 0 {
+            n -= 1;
+        }
+        &v[..n]
+    }
+
+    /// Private function for adding `rhs` to `lhs` in place.
+    /// It assumes that the result fits in `lhs`.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := lhs.len()`
+    fn add_into_core(lhs: &mut [u32], rhs: &[u32]) {
+        let mut carry = 0u64;
+        for (i, x) in lhs.iter_mut().enumerate() {
+            if i >= rhs.len() && carry == 0 {
+                break;
+            }
+            let t = *x as u64 + *rhs.get(i).unwrap_or(&0) as u64 + carry;
+            *x = t as u32;
+            carry = t >> 32;
+        }
+    }
+
+    /// Private function for subtracting `rhs` from `lhs` in place.
+    /// It assumes that `lhs >= rhs`.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := lhs.len()`
+    fn sub_into_core(lhs: &mut [u32], rhs: &[u32]) {
+        let mut borrow = 0i64;
+        for (i, x) in lhs.iter_mut().enumerate() {
+            if i >= rhs.len() && borrow == 0 {
+                break;
+            }
+            let mut t = *x as i64 - *rhs.get(i).unwrap_or(&0) as i64 - borrow;
+            borrow = 0;
+            if t < 0 {
+                t += 1i64 << 32;
+                borrow = 1;
+            }
+            *x = t as u32;
+        }
+    }
+
+    /// Private function for `v = v * m + a` in place.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := v.len()`
+    fn mul_add_small_core(v: &mut Vec<u32>, m: u32, a: u32) {
+        let mut carry = a as u64;
+        for x in v.iter_mut() {
+            let t = *x as u64 * m as u64 + carry;
+            *x = t as u32;
+            carry = t >> 32;
+        }
+        if carry > 0 {
+            v.push(carry as u32);
+        }
+    }
+
+    /// Private function for dividing vector with single `u32`.
+    /// Returns quotient and remainder.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := lhs.len()`
+    fn div_small_core(lhs: &[u32], rhs: u32) -> (Vec<u32>, u32) {
+        let mut v = vec![0; lhs.len()];
+        let mut r = 0u64;
+
+        for i in (0..lhs.len()).rev() {
+            let t = (r << 32) | lhs[i] as u64;
+            v[i] = (t / rhs as u64) as u32;
+            r = t % rhs as u64;
+        }
+
+        (v, r as u32)
+    }
+
     /// Private function for multiplying two numbers. (Core function)
     /// Gets two vectors of data and returns new vector of result.
     /// It assumes two value are positive.
+    /// Uses Karatsuba algorithm when both numbers are long enough.
     ///
     /// # Time Complexity
     ///
-    /// `O(n + m * k)` where `n := lhs.len()`, `m := non-zero values in lhs` and `k := rhs.len()`
+    /// `O(n * m^0.59)` where `n := max(lhs.len(), rhs.len())` and `m := min(lhs.len(), rhs.len())`
     ///
     /// # TODO
     ///
     /// - [Better Algorithm](https://en.wikipedia.org/wiki/Sch%C3%B6nhage%E2%80%93Strassen_algorithm)
     fn mult_core(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
-        let mut v = vec![0; lhs.len() + rhs.len() + 1];
+        let (a, b) = if lhs.len() < rhs.len() {
+            (BigNum::trim_core(rhs), BigNum::trim_core(lhs))
+        } else {
+            (BigNum::trim_core(lhs), BigNum::trim_core(rhs))
+        };
 
-        for i in 0..lhs.len() {
-            if lhs[i] == 0 {
-                continue;
-            }
+        if a.is_empty() || b.is_empty() {
+            return vec![0];
+        }
+
+        let mut v = vec![0; a.len() + b.len()];
+
+        if b.len() < KARATSUBA_THRESHOLD {
+            for (i, &x) in b.iter().enumerate() {
+                if x == 0 {
+                    continue;
+                }
 
-            for j in 0..rhs.len() {
-                let t = (lhs[i] as u64) * (rhs[j] as u64);
-                v[i + j] += t % (1u64 << 32);
-                v[i + j + 1] += t / (1u64 << 32);
-                v[i + j + 1] += v[i + j] / (1u64 << 32);
-                v[i + j] %= 1u64 << 32;
+                let mut carry = 0u64;
+                for (j, &y) in a.iter().enumerate() {
+                    let t = (x as u64) * (y as u64) + v[i + j] as u64 + carry;
+                    v[i + j] = t as u32;
+                    carry = t >> 32;
+                }
+                v[i + a.len()] = carry as u32;
+            }
+        } else if b.len() * 2 <= a.len() {
+            for (i, c) in a.chunks(b.len()).enumerate() {
+                let t = BigNum::mult_core(c, b);
+                BigNum::add_into_core(&mut v[i * b.len()..], BigNum::trim_core(&t));
             }
+        } else {
+            let m = a.len() / 2;
+            let (a0, a1) = a.split_at(m);
+            let (b0, b1) = b.split_at(m);
+
+            let z0 = BigNum::mult_core(a0, b0);
+            let z2 = BigNum::mult_core(a1, b1);
+            let mut z1 = BigNum::mult_core(&BigNum::add_core(a0, a1), &BigNum::add_core(b0, b1));
+            BigNum::sub_into_core(&mut z1, &z0);
+            BigNum::sub_into_core(&mut z1, &z2);
+
+            BigNum::add_into_core(&mut v, BigNum::trim_core(&z0));
+            BigNum::add_into_core(&mut v[m..], BigNum::trim_core(&z1));
+            BigNum::add_into_core(&mut v[2 * m..], BigNum::trim_core(&z2));
         }
 
-        v.iter().map(|&x| x as u32).collect()
+        v
     }
 
     /// Private function for dividing two numbers. (Core function)
-    /// Gets two vectors of data and returns new vector of result.
+    /// Gets two vectors of data and returns new vectors of quotient and remainder.
     /// It assumes two value are positive.
+    /// Uses schoolbook division for short numbers and Newton's method for long numbers.
     ///
     /// # Time Complexity
     ///
-    /// `O(n * max(n, m))` where `n := lhs.len()` and `m := rhs.len()`
+    /// - `O(n * m)` when `n` or `m` is small
+    /// - `O(M(n))` otherwise
     ///
-    /// # TODO
+    /// where `n := lhs.len()`, `m := rhs.len()` and `M(n)` is the time of `mult_core`
+    ///
+    /// # Panics
+    ///
+    /// - when `rhs` is zero
+    fn div_rem_core(lhs: &[u32], rhs: &[u32]) -> (Vec<u32>, Vec<u32>) {
+        let (u, v) = (BigNum::trim_core(lhs), BigNum::trim_core(rhs));
+
+        if v.is_empty() {
+            panic!("attempt to divide by zero");
+        }
+        if u.is_empty() || BigNum::less_core(u, v) {
+            return (vec![0], if u.is_empty() { vec![0] } else { u.to_vec() });
+        }
+        if v.len() == 1 {
+            let (q, r) = BigNum::div_small_core(u, v[0]);
+            return (q, vec![r]);
+        }
+        if v.len() < NEWTON_THRESHOLD || u.len() - v.len() < NEWTON_THRESHOLD {
+            return BigNum::div_knuth_core(u, v);
+        }
+
+        BigNum::div_rem_recip_core(u, v, &BigNum::recip_core(v))
+    }
+
+    /// Private function for schoolbook division.
+    /// [Knuth's Algorithm D](https://en.wikipedia.org/wiki/Division_algorithm#Long_division)
+    /// It assumes that `lhs` and `rhs` have no leading zeros, `rhs.len() >= 2` and `lhs >= rhs`.
     ///
-    /// - [Better Algorithm](https://en.wikipedia.org/wiki/Division_algorithm)
-    fn div_core(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
-        let mut v = vec![0; max(lhs.len(), rhs.len())];
+    /// # Time Complexity
+    ///
+    /// `O(m * (n - m + 1))` where `n := lhs.len()` and `m := rhs.len()`
+    fn div_knuth_core(lhs: &[u32], rhs: &[u32]) -> (Vec<u32>, Vec<u32>) {
+        let n = rhs.len();
+        let m = lhs.len() - n;
+        let s = rhs[n - 1].leading_zeros();
+        let shift = |v: &[u32]| {
+            let mut res = vec![0; v.len() + 1];
+            for i in 0..v.len() {
+                res[i] |= v[i] << s;
+                if s > 0 {
+                    res[i + 1] = v[i] >> (32 - s);
+                }
+            }
+            res
+        };
+        let vn = shift(rhs);
+        let mut un = shift(lhs);
+        let mut q = vec![0; m + 1];
+
+        for j in (0..=m).rev() {
+            let num = ((un[j + n] as u64) << 32) | un[j + n - 1] as u64;
+            let mut qhat = num / vn[n - 1] as u64;
+            let mut rhat = num % vn[n - 1] as u64;
+            while qhat >= 1u64 << 32
+                || qhat * vn[n - 2] as u64 > ((rhat << 32) | un[j + n - 2] as u64)
+            {
+                qhat -= 1;
+                rhat += vn[n - 1] as u64;
+                if rhat >= 1u64 << 32 {
+                    break;
+                }
+            }
+
+            let mut k = 0i64;
+            for i in 0..n {
+                let p = qhat * vn[i] as u64;
+                let t = un[i + j] as i64 - k - (p & 0xFFFF_FFFF) as i64;
+                un[i + j] = t as u32;
+                k = (p >> 32) as i64 - (t >> 32);
+            }
+            let t = un[j + n] as i64 - k;
+            un[j + n] = t as u32;
 
-        for i in (0..v.len()).rev() {
-            for j in (0..32).rev() {
-                v[i] += 1u32 << j;
-                if BigNum::less_core(lhs, &BigNum::mult_core(&v, rhs)) {
-                    v[i] -= 1u32 << j;
+            q[j] = qhat as u32;
+            if t < 0 {
+                q[j] -= 1;
+                let mut carry = 0u64;
+                for i in 0..n {
+                    let t = un[i + j] as u64 + vn[i] as u64 + carry;
+                    un[i + j] = t as u32;
+                    carry = t >> 32;
                 }
+                un[j + n] = un[j + n].wrapping_add(carry as u32);
             }
         }
 
-        v
+        let mut r = vec![0; n];
+        for i in 0..n {
+            r[i] = un[i] >> s;
+            if s > 0 {
+                r[i] |= un[i + 1] << (32 - s);
+            }
+        }
+
+        (q, r)
+    }
+
+    /// Private function for getting `floor(B^(2n) / v)` where `B := 2^32` and `n := v.len()`.
+    /// It assumes that `v` has no leading zeros.
+    /// [Newton's method](https://en.wikipedia.org/wiki/Division_algorithm#Newton%E2%80%93Raphson_division)
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(M(n))` where `n := v.len()` and `M(n)` is the time of `mult_core`
+    fn recip_core(v: &[u32]) -> Vec<u32> {
+        let n = v.len();
+        if n < NEWTON_THRESHOLD {
+            let mut pow = vec![0; 2 * n + 1];
+            pow[2 * n] = 1;
+            return BigNum::div_knuth_core(&pow, v).0;
+        }
+
+        // with `x := y * B^k` and `t := B^(2n - k) - d * y`,
+        // x + x * (B^(2n) - d * x) / B^(2n) = y * B^k + y * t / B^(2n - 2k)
+        // and low `n - k - 1` limbs of `t` change it by at most one
+        let k = n - (n / 2 + 2);
+        let y = BigNum::from_vec(BigNum::recip_core(&v[k..]));
+        let d = BigNum::from_vec(v.to_vec());
+        let mut pow = vec![0; 2 * n - k + 1];
+        pow[2 * n - k] = 1;
+
+        let t = &BigNum::from_vec(pow) - &(&d * &y);
+        let e = (&y * &t.shr_limbs(n - k - 1)).shr_limbs(n - k + 1);
+        let mut x = &(&y << (32 * k)) + &e;
+
+        // B^(2n) - d * x
+        let mut r = &(&t << (32 * k)) - &(&d * &e);
+        while !r.pos {
+            x -= &BigNum::one();
+            r += &d;
+        }
+        while r >= d {
+            x += &BigNum::one();
+            r -= &d;
+        }
+
+        x.val
+    }
+
+    /// Private function for dividing with precomputed `recip := recip_core(rhs)`.
+    /// It assumes that `rhs` has no leading zeros.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(M(m) * n / m)` where `n := lhs.len()`, `m := rhs.len()` and `M(n)` is the time of `mult_core`
+    fn div_rem_recip_core(lhs: &[u32], rhs: &[u32], recip: &[u32]) -> (Vec<u32>, Vec<u32>) {
+        let n = rhs.len();
+        let d = BigNum::from_vec(rhs.to_vec());
+
+        if lhs.len() <= 2 * n {
+            let u = BigNum::from_vec(lhs.to_vec());
+            // low `n - 1` limbs of `lhs` change the quotient by at most one
+            let k = min(n - 1, lhs.len());
+            let mut q = BigNum::from_vec(BigNum::mult_core(&lhs[k..], recip)).shr_limbs(2 * n - k);
+            let mut r = &u - &(&q * &d);
+            while !r.pos {
+                q -= &BigNum::one();
+                r += &d;
+            }
+            while r >= d {
+                q += &BigNum::one();
+                r -= &d;
+            }
+            return (q.val, r.val);
+        }
+
+        let mut q = vec![0; lhs.len()];
+        let mut r = vec![0];
+        let blocks = lhs.len().div_ceil(n);
+        for i in (0..blocks).rev() {
+            let (s, e) = (i * n, min((i + 1) * n, lhs.len()));
+            let mut cur = lhs[s..e].to_vec();
+            cur.extend_from_slice(BigNum::trim_core(&r));
+            let (t, rem) = BigNum::div_rem_recip_core(&cur, rhs, recip);
+            let t = BigNum::trim_core(&t);
+            q[s..s + t.len()].copy_from_slice(t);
+            r = rem;
+        }
+
+        (q, r)
+    }
+
+    /// Private function for shifting right by `k` limbs (rounding to zero).
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := self.val.len()`
+    fn shr_limbs(&self, k: usize) -> BigNum {
+        if self.val.len() <= k {
+            BigNum::zero()
+        } else {
+            let mut res = BigNum::from_vec(self.val[k..].to_vec());
+            if !self.pos {
+                res.minus();
+            }
+            res
+        }
     }
 
     /// Private function for comparing two numbers. (Core function)
@@ -664,7 +1163,7 @@ impl BigNum {
     ///
     /// # Time Complexity
     ///
-    /// `O(n + m * k)` where `n := lhs.val.len()`, `m := non-zero values in lhs.val` and `k := rhs.val.len()`
+    /// `O(n * m^0.59)` where `n := max(lhs.val.len(), rhs.val.len())` and `m := min(lhs.val.len(), rhs.val.len())`
     ///
     /// # Examples
     ///
@@ -691,7 +1190,7 @@ impl BigNum {
     ///
     /// # Time Complexity
     ///
-    /// `O(n * max(n, m))` where `n := lhs.val.len()` and `m := rhs.val.len()`
+    /// `O(m * (n - m + 1))` or `O(M(n))` for long numbers, where `n := lhs.val.len()` and `m := rhs.val.len()`
     ///
     /// # Warning
     ///
@@ -711,7 +1210,7 @@ impl BigNum {
     /// assert_eq!("-39", BigNum::div(&a, &b).to_string());
     /// ```
     pub fn div(lhs: &BigNum, rhs: &BigNum) -> BigNum {
-        let mut res = BigNum::from_vec(BigNum::div_core(&lhs.val, &rhs.val));
+        let mut res = BigNum::from_vec(BigNum::div_rem_core(&lhs.val, &rhs.val).0);
 
         if lhs.pos ^ rhs.pos {
             res.minus();
@@ -725,7 +1224,7 @@ impl BigNum {
     ///
     /// # Time Complexity
     ///
-    /// `O(div(lhs, rhs) + mul(q, rhs))` where `q := lhs / rhs`
+    /// same as `div(lhs, rhs)`
     ///
     /// # Warning
     ///
@@ -745,8 +1244,13 @@ impl BigNum {
     /// assert_eq!("-25", BigNum::rem(&a, &b).to_string());
     /// ```
     pub fn rem(lhs: &BigNum, rhs: &BigNum) -> BigNum {
-        let q = BigNum::div(lhs, rhs);
-        BigNum::sub(lhs, &BigNum::mul(&q, rhs))
+        let mut res = BigNum::from_vec(BigNum::div_rem_core(&lhs.val, &rhs.val).1);
+
+        if !lhs.pos {
+            res.minus();
+        }
+
+        res
     }
 
     /// Get greatest common value of two number and make new `BigNum` as result
@@ -783,91 +1287,440 @@ impl BigNum {
         a
     }
 
-    /// Returns new `BigNum` that minus is applied.
+    /// Get least common multiple of two number and make new `BigNum` as result
+    /// The result is always non-negative.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(gcd(lhs, rhs) + mul(lhs, rhs))`
     ///
     /// # Example
     ///
     /// ```
     /// use hyeong::number::big_number::BigNum;
     ///
-    /// let a = BigNum::new(1234);
-    /// let b = BigNum::neg(&a);
+    /// let a = BigNum::new(18);
+    /// let b = BigNum::new(-24);
     ///
-    /// assert_eq!("-1234", b.to_string());
+    /// assert_eq!("72", BigNum::lcm(&a, &b).to_string());
     /// ```
-    pub fn neg(v: &BigNum) -> BigNum {
-        BigNum {
-            pos: if !v.is_zero() { !v.pos } else { v.pos },
-            val: v.val.clone(),
+    pub fn lcm(lhs: &BigNum, rhs: &BigNum) -> BigNum {
+        if lhs.is_zero() || rhs.is_zero() {
+            return BigNum::zero();
         }
+
+        let mut res = &(lhs / &BigNum::gcd(lhs, rhs)) * rhs;
+        res.pos = true;
+        res
     }
 
-    /// Makes `self` same value as `rhs` but copying the value
+    /// Get `self^exp` and make new `BigNum` as result
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(M(n * exp))` where `n := self.val.len()` and `M(n)` is the time of `mul`
     ///
     /// # Example
     ///
     /// ```
     /// use hyeong::number::big_number::BigNum;
     ///
-    /// let a = BigNum::new(1234);
-    /// let mut b = BigNum::zero();
-    ///
-    /// b.set_copy(&a);
+    /// let a = BigNum::new(-3);
     ///
-    /// assert_eq!("1234", b.to_string());
+    /// assert_eq!("-243", a.pow(5).to_string());
+    /// assert_eq!("1", a.pow(0).to_string());
     /// ```
-    pub fn set_copy(&mut self, rhs: &BigNum) {
-        self.val = rhs.val.clone();
-        self.pos = rhs.pos;
+    pub fn pow(&self, mut exp: u32) -> BigNum {
+        let mut res = BigNum::one();
+        let mut base = self.clone();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                res *= &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+
+        res
     }
 
-    /// Makes `self` same value as `rhs` but moving the value
+    /// Get integer square root (`floor(sqrt(self))`) and make new `BigNum` as result
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(div(self, self) * log(n))` where `n := self.val.len()`
+    ///
+    /// # Panics
+    ///
+    /// - when `self` is negative
     ///
     /// # Example
     ///
     /// ```
     /// use hyeong::number::big_number::BigNum;
     ///
-    /// let a = BigNum::new(1234);
-    /// let mut b = BigNum::zero();
-    ///
-    /// b.set_move(a);
-    /// //         ^ `a` moved here
+    /// let a = BigNum::new(99);
     ///
-    /// assert_eq!("1234", b.to_string());
+    /// assert_eq!("9", a.isqrt().to_string());
     /// ```
-    pub fn set_move(&mut self, rhs: BigNum) {
-        self.val = rhs.val;
-        self.pos = rhs.pos;
+    pub fn isqrt(&self) -> BigNum {
+        if !self.pos {
+            panic!("argument of integer square root cannot be negative");
+        }
+        if self.is_zero() {
+            return BigNum::zero();
+        }
+
+        // Newton's method starting from the value bigger than the answer
+        let mut x = &BigNum::one() << self.bit_len().div_ceil(2);
+        loop {
+            let y = &(&x + &(self / &x)) >> 1;
+            if y >= x {
+                break x;
+            }
+            x = y;
+        }
     }
-}
 
-impl PartialEq for BigNum {
-    /// Equal function of two `BigNum`
+    /// Get `self^exp mod modulus` and make new `BigNum` as result
+    /// The result is in `0 <= res < |modulus|`.
     ///
-    /// # Examples
+    /// # Time Complexity
+    ///
+    /// `O(div(m^2, m) * k)` where `m := modulus` and `k := exp.bit_len()`
+    ///
+    /// # Panics
+    ///
+    /// - when `exp` is negative
+    /// - when `modulus` is zero
+    ///
+    /// # Example
     ///
     /// ```
     /// use hyeong::number::big_number::BigNum;
     ///
-    /// let a = BigNum::new(1234);
-    /// let b = BigNum::new(1234);
-    /// let c = BigNum::new(-1234);
+    /// let a = BigNum::new(-4);
+    /// let b = BigNum::new(13);
+    /// let c = BigNum::new(497);
     ///
-    /// assert_eq!(true, a == b);
-    /// assert_eq!(false, a == c);
+    /// assert_eq!("52", a.modpow(&b, &c).to_string());
     /// ```
-    fn eq(&self, other: &Self) -> bool {
-        if self.is_zero() && other.is_zero() {
-            true
-        } else {
-            self.pos == other.pos && self.val == other.val
+    pub fn modpow(&self, exp: &BigNum, modulus: &BigNum) -> BigNum {
+        if !exp.pos {
+            panic!("exponent of modpow cannot be negative");
         }
-    }
-}
+
+        let mut m = modulus.clone();
+        m.pos = true;
+        let mut base = self.modulo(&m);
+        let mut res = &BigNum::one() % &m;
+
+        for i in 0..exp.bit_len() {
+            if exp.bit(i) {
+                res = &(&res * &base) % &m;
+            }
+            if i + 1 < exp.bit_len() {
+                base = &(&base * &base) % &m;
+            }
+        }
+
+        res
+    }
+
+    /// Get modular multiplicative inverse of `self` and make new `BigNum` as result
+    /// The result is in `0 <= res < |modulus|`.
+    /// Returns `None` when the inverse doesn't exist.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(div(m, m) * log(m))` where `m := modulus`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let a = BigNum::new(3);
+    /// let b = BigNum::new(11);
+    ///
+    /// assert_eq!("4", a.modinv(&b).unwrap().to_string());
+    /// assert!(BigNum::new(6).modinv(&BigNum::new(9)).is_none());
+    /// ```
+    pub fn modinv(&self, modulus: &BigNum) -> Option<BigNum> {
+        let mut m = modulus.clone();
+        m.pos = true;
+        if m.is_zero() {
+            return Option::None;
+        }
+
+        // extended euclidean algorithm keeping only the coefficient of `self`
+        let (mut a, mut b) = (self.modulo(&m), m.clone());
+        let (mut x, mut y) = (BigNum::one(), BigNum::zero());
+        while !b.is_zero() {
+            let (q, r) = (&a / &b, &a % &b);
+            let t = &x - &(&q * &y);
+            a = b;
+            b = r;
+            x = y;
+            y = t;
+        }
+
+        if a == BigNum::one() {
+            Option::Some(x.modulo(&m))
+        } else if m == BigNum::one() {
+            Option::Some(BigNum::zero())
+        } else {
+            Option::None
+        }
+    }
+
+    /// Get number of bits of the absolute value
+    /// Zero has zero bits.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := self.val.len()`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// assert_eq!(4, BigNum::new(-10).bit_len());
+    /// assert_eq!(33, BigNum::from_vec(vec![0, 1]).bit_len());
+    /// assert_eq!(0, BigNum::zero().bit_len());
+    /// ```
+    pub fn bit_len(&self) -> usize {
+        let val = BigNum::trim_core(&self.val);
+        match val.last() {
+            Some(x) => val.len() * 32 - x.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Get number of trailing zero bits of the absolute value
+    /// Returns `None` when the value is zero.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := self.val.len()`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// assert_eq!(Some(3), BigNum::new(-40).trailing_zeros());
+    /// assert_eq!(Some(32), BigNum::from_vec(vec![0, 1]).trailing_zeros());
+    /// assert_eq!(None, BigNum::zero().trailing_zeros());
+    /// ```
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        self.val
+            .iter()
+            .position(|&x| x != 0)
+            .map(|i| i * 32 + self.val[i].trailing_zeros() as usize)
+    }
+
+    /// Private function for getting `i`-th bit of the absolute value.
+    fn bit(&self, i: usize) -> bool {
+        match self.val.get(i / 32) {
+            Some(x) => (x >> (i % 32)) & 1 == 1,
+            None => false,
+        }
+    }
+
+    /// Private function for getting non-negative remainder.
+    /// It assumes that `m` is positive.
+    fn modulo(&self, m: &BigNum) -> BigNum {
+        let r = self % m;
+        if r.pos {
+            r
+        } else {
+            &r + m
+        }
+    }
+
+    /// Private function for shifting left by `k` bits.
+    /// Gets vector of data and returns new vector of result.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n + k)` where `n := v.len()`
+    fn shl_core(v: &[u32], k: usize) -> Vec<u32> {
+        let (limbs, bits) = (k / 32, (k % 32) as u32);
+        let mut res = vec![0; v.len() + limbs + 1];
+
+        for (i, &x) in v.iter().enumerate() {
+            res[i + limbs] |= x << bits;
+            if bits > 0 {
+                res[i + limbs + 1] = x >> (32 - bits);
+            }
+        }
+
+        res
+    }
+
+    /// Private function for shifting right by `k` bits (rounding to zero).
+    /// Gets vector of data and returns new vector of result.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := v.len()`
+    fn shr_core(v: &[u32], k: usize) -> Vec<u32> {
+        let (limbs, bits) = (k / 32, (k % 32) as u32);
+        if v.len() <= limbs {
+            return vec![0];
+        }
+
+        let mut res = vec![0; v.len() - limbs];
+        for i in 0..res.len() {
+            res[i] = v[i + limbs] >> bits;
+            if bits > 0 && i + limbs + 1 < v.len() {
+                res[i] |= v[i + limbs + 1] << (32 - bits);
+            }
+        }
+
+        res
+    }
+
+    /// Private function for converting to two's complement representation of `n` limbs.
+    /// It assumes that the value fits in `n` limbs with sign bit.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)`
+    fn to_twos_core(&self, n: usize) -> Vec<u32> {
+        let mut v = self.val.clone();
+        v.resize(n, 0);
+        if !self.pos {
+            BigNum::sub_into_core(&mut v, &[1]);
+            v.iter_mut().for_each(|x| *x = !*x);
+        }
+        v
+    }
+
+    /// Private function for converting from two's complement representation.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := v.len()`
+    fn from_twos_core(mut v: Vec<u32>) -> BigNum {
+        if v.last().is_some_and(|x| x >> 31 == 1) {
+            v.iter_mut().for_each(|x| *x = !*x);
+            BigNum::add_into_core(&mut v, &[1]);
+            let mut res = BigNum::from_vec(v);
+            res.minus();
+            res
+        } else {
+            BigNum::from_vec(v)
+        }
+    }
+
+    /// Private function for bitwise operation on two's complement representation.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(max(n, m))` where `n := lhs.val.len()` and `m := rhs.val.len()`
+    fn bitwise<F>(lhs: &BigNum, rhs: &BigNum, f: F) -> BigNum
+    where
+        F: Fn(u32, u32) -> u32,
+    {
+        let n = max(lhs.val.len(), rhs.val.len()) + 1;
+        let (a, b) = (lhs.to_twos_core(n), rhs.to_twos_core(n));
+        BigNum::from_twos_core(a.iter().zip(b.iter()).map(|(&x, &y)| f(x, y)).collect())
+    }
+
+    /// Returns new `BigNum` that minus is applied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let a = BigNum::new(1234);
+    /// let b = BigNum::neg(&a);
+    ///
+    /// assert_eq!("-1234", b.to_string());
+    /// ```
+    pub fn neg(v: &BigNum) -> BigNum {
+        BigNum {
+            pos: if !v.is_zero() { !v.pos } else { v.pos },
+            val: v.val.clone(),
+        }
+    }
+
+    /// Makes `self` same value as `rhs` but copying the value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let a = BigNum::new(1234);
+    /// let mut b = BigNum::zero();
+    ///
+    /// b.set_copy(&a);
+    ///
+    /// assert_eq!("1234", b.to_string());
+    /// ```
+    pub fn set_copy(&mut self, rhs: &BigNum) {
+        self.val = rhs.val.clone();
+        self.pos = rhs.pos;
+    }
+
+    /// Makes `self` same value as `rhs` but moving the value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let a = BigNum::new(1234);
+    /// let mut b = BigNum::zero();
+    ///
+    /// b.set_move(a);
+    /// //         ^ `a` moved here
+    ///
+    /// assert_eq!("1234", b.to_string());
+    /// ```
+    pub fn set_move(&mut self, rhs: BigNum) {
+        self.val = rhs.val;
+        self.pos = rhs.pos;
+    }
+}
+
+impl PartialEq for BigNum {
+    /// Equal function of two `BigNum`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let a = BigNum::new(1234);
+    /// let b = BigNum::new(1234);
+    /// let c = BigNum::new(-1234);
+    ///
+    /// assert_eq!(true, a == b);
+    /// assert_eq!(false, a == c);
+    /// ```
+    fn eq(&self, other: &Self) -> bool {
+        if self.is_zero() && other.is_zero() {
+            true
+        } else {
+            self.pos == other.pos && self.val == other.val
+        }
+    }
+}
+
+impl Eq for BigNum {}
 
 impl PartialOrd for BigNum {
     /// Compare function of two `BigNum`
+    /// Always returns `Some` since `BigNum` is totally ordered.
     ///
     /// # Examples
     ///
@@ -885,8 +1738,30 @@ impl PartialOrd for BigNum {
     /// });
     /// ```
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Option::Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigNum {
+    /// Compare function of two `BigNum`
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(max(n, m))` where `n := self.val.len()` and `m := other.val.len()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let mut v = vec![BigNum::new(3), BigNum::new(-10), BigNum::zero()];
+    /// v.sort();
+    ///
+    /// assert_eq!("[-10, 0, 3]", format!("{:?}", v));
+    /// ```
+    fn cmp(&self, other: &Self) -> Ordering {
         if self == other {
-            Option::Some(Ordering::Equal)
+            Ordering::Equal
         } else if if self.pos {
             if other.pos {
                 BigNum::less_core(&self.val, &other.val)
@@ -898,13 +1773,178 @@ impl PartialOrd for BigNum {
         } else {
             BigNum::less_core(&other.val, &self.val)
         } {
-            Option::Some(Ordering::Less)
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    }
+}
+
+impl Hash for BigNum {
+    /// Hash function of `BigNum`
+    /// Equal values have same hash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut s = HashSet::new();
+    /// s.insert(BigNum::new(10));
+    ///
+    /// assert!(s.contains(&BigNum::from_string(String::from("10")).unwrap()));
+    /// ```
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.pos || self.is_zero()).hash(state);
+        BigNum::trim_core(&self.val).hash(state);
+    }
+}
+
+impl FromStr for BigNum {
+    type Err = Error;
+
+    /// Make new `BigNum` from string (10 based)
+    /// Same as `BigNum::from_string()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let a: BigNum = "-1234".parse().unwrap();
+    ///
+    /// assert_eq!(BigNum::new(-1234), a);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BigNum::from_string(s.to_string())
+    }
+}
+
+impl BigNum {
+    /// Private function for making `BigNum` from sign and absolute value.
+    fn from_u128(pos: bool, n: u128) -> BigNum {
+        let mut res = BigNum::from_vec(vec![
+            n as u32,
+            (n >> 32) as u32,
+            (n >> 64) as u32,
+            (n >> 96) as u32,
+        ]);
+        if !pos {
+            res.minus();
+        }
+        res
+    }
+
+    /// Private function for getting absolute value as `u128`.
+    fn to_u128(&self) -> Option<u128> {
+        let val = BigNum::trim_core(&self.val);
+        if val.len() > 4 {
+            Option::None
         } else {
-            Option::Some(Ordering::Greater)
+            Option::Some(val.iter().rev().fold(0, |acc, &x| (acc << 32) | x as u128))
         }
     }
 }
 
+/// Implements conversions between `BigNum` and primitive integers.
+macro_rules! impl_primitive {
+    (signed $($t:ty),*) => {$(
+        impl From<$t> for BigNum {
+            fn from(n: $t) -> BigNum {
+                BigNum::from_u128(n >= 0, (n as i128).unsigned_abs())
+            }
+        }
+
+        impl_primitive!(try_from $t);
+    )*};
+    (unsigned $($t:ty),*) => {$(
+        impl From<$t> for BigNum {
+            fn from(n: $t) -> BigNum {
+                BigNum::from_u128(true, n as u128)
+            }
+        }
+
+        impl_primitive!(try_from $t);
+    )*};
+    (try_from $t:ty) => {
+        impl TryFrom<&BigNum> for $t {
+            type Error = Error;
+
+            fn try_from(n: &BigNum) -> Result<$t, Error> {
+                let m = n.to_u128().ok_or(Error::RangeError)?;
+                let v = if n.pos {
+                    <$t>::try_from(m).ok()
+                } else if m <= 1 << 127 {
+                    <$t>::try_from((m as i128).wrapping_neg()).ok()
+                } else {
+                    Option::None
+                };
+                v.ok_or(Error::RangeError)
+            }
+        }
+
+        impl TryFrom<BigNum> for $t {
+            type Error = Error;
+
+            fn try_from(n: BigNum) -> Result<$t, Error> {
+                <$t>::try_from(&n)
+            }
+        }
+    };
+}
+
+impl_primitive!(signed i8, i16, i32, i64, i128, isize);
+impl_primitive!(unsigned u8, u16, u32, u64, u128, usize);
+
+impl From<char> for BigNum {
+    /// Makes new `BigNum` from unicode code point
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// assert_eq!(BigNum::new(54805), BigNum::from('형'));
+    /// ```
+    fn from(c: char) -> BigNum {
+        BigNum::from(c as u32)
+    }
+}
+
+impl TryFrom<&BigNum> for char {
+    type Error = Error;
+
+    /// Makes unicode character from `BigNum`
+    ///
+    /// # Errors
+    ///
+    /// - `Error::RangeError`: when the value is not a unicode scalar value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    /// use std::convert::TryFrom;
+    ///
+    /// assert_eq!('형', char::try_from(&BigNum::new(54805)).unwrap());
+    /// assert!(char::try_from(&BigNum::new(-1)).is_err());
+    /// ```
+    fn try_from(n: &BigNum) -> Result<char, Error> {
+        char::from_u32(u32::try_from(n)?).ok_or(Error::RangeError)
+    }
+}
+
+impl TryFrom<BigNum> for char {
+    type Error = Error;
+
+    fn try_from(n: BigNum) -> Result<char, Error> {
+        char::try_from(&n)
+    }
+}
+
+impl_sum_product!(BigNum);
+
 impl fmt::Debug for BigNum {
     /// Printing feature of `BigNum`
     ///
@@ -927,7 +1967,7 @@ impl fmt::Display for BigNum {
     ///
     /// # Time Complexity
     ///
-    /// `O(n^2)` where `n := res.len()`
+    /// `O(M(n) * log(n))` where `n := self.val.len()` and `M(n)` is the time of `mul`
     ///
     /// # Examples
     ///
@@ -1049,7 +2089,7 @@ impl ops::Mul<&BigNum> for &BigNum {
     ///
     /// # Time Complexity
     ///
-    /// `O(n + m * k)` where `n := lhs.val.len()`, `m := non-zero values in lhs.val` and `k := rhs.val.len()`
+    /// `O(n * m^0.59)` where `n := max(lhs.val.len(), rhs.val.len())` and `m := min(lhs.val.len(), rhs.val.len())`
     ///
     /// # Examples
     ///
@@ -1072,7 +2112,7 @@ impl ops::MulAssign<&BigNum> for BigNum {
     ///
     /// # Time Complexity
     ///
-    /// `O(n + m * k)` where `n := lhs.val.len()`, `m := non-zero values in lhs.val` and `k := rhs.val.len()`
+    /// `O(n * m^0.59)` where `n := max(lhs.val.len(), rhs.val.len())` and `m := min(lhs.val.len(), rhs.val.len())`
     ///
     /// # Examples
     ///
@@ -1098,7 +2138,7 @@ impl ops::Div<&BigNum> for &BigNum {
     ///
     /// # Time Complexity
     ///
-    /// `O(n * max(n, m))` where `n := lhs.val.len()` and `m := rhs.val.len()`
+    /// `O(m * (n - m + 1))` or `O(M(n))` for long numbers, where `n := lhs.val.len()` and `m := rhs.val.len()`
     ///
     /// # Warning
     ///
@@ -1128,7 +2168,7 @@ impl ops::DivAssign<&BigNum> for BigNum {
     ///
     /// # Time Complexity
     ///
-    /// `O(n * max(n, m))` where `n := lhs.val.len()` and `m := rhs.val.len()`
+    /// `O(m * (n - m + 1))` or `O(M(n))` for long numbers, where `n := lhs.val.len()` and `m := rhs.val.len()`
     ///
     /// # Warning
     ///
@@ -1161,7 +2201,7 @@ impl ops::Rem<&BigNum> for &BigNum {
     ///
     /// # Time Complexity
     ///
-    /// `O(div(lhs, rhs) + mul(q, rhs))` where `q := lhs / rhs`
+    /// same as `div(lhs, rhs)`
     ///
     /// # Warning
     ///
@@ -1191,7 +2231,7 @@ impl ops::RemAssign<&BigNum> for BigNum {
     ///
     /// # Time Complexity
     ///
-    /// `O(div(lhs, rhs) + mul(q, rhs))` where `q := lhs / rhs`
+    /// same as `div(lhs, rhs)`
     ///
     /// # Warning
     ///
@@ -1234,3 +2274,280 @@ impl ops::Neg for &BigNum {
         BigNum::neg(self)
     }
 }
+
+impl ops::Shl<usize> for &BigNum {
+    type Output = BigNum;
+
+    /// Shifts left by `rhs` bits and make new `BigNum` as result
+    /// Same as multiplying `2^rhs`.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n + rhs)` where `n := self.val.len()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let a = BigNum::new(-3);
+    ///
+    /// assert_eq!("-12884901888", (&a << 32).to_string());
+    /// ```
+    fn shl(self, rhs: usize) -> Self::Output {
+        let mut res = BigNum::from_vec(BigNum::shl_core(&self.val, rhs));
+        if !self.pos {
+            res.minus();
+        }
+        res
+    }
+}
+
+impl ops::ShlAssign<usize> for BigNum {
+    /// Shifts left by `rhs` bits and move the value to `self`
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n + rhs)` where `n := self.val.len()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let mut a = BigNum::new(5);
+    /// a <<= 3;
+    ///
+    /// assert_eq!("40", a.to_string());
+    /// ```
+    fn shl_assign(&mut self, rhs: usize) {
+        self.set_move(&*self << rhs);
+    }
+}
+
+impl ops::Shr<usize> for &BigNum {
+    type Output = BigNum;
+
+    /// Shifts right by `rhs` bits and make new `BigNum` as result
+    /// Same as dividing `2^rhs` but rounding to negative infinity
+    /// like two's complement arithmetic shift.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := self.val.len()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// assert_eq!("2", (&BigNum::new(5) >> 1).to_string());
+    /// assert_eq!("-3", (&BigNum::new(-5) >> 1).to_string());
+    /// ```
+    fn shr(self, rhs: usize) -> Self::Output {
+        if self.pos {
+            BigNum::from_vec(BigNum::shr_core(&self.val, rhs))
+        } else {
+            // -((|x| - 1) >> rhs) - 1
+            let t = &(-self) - &BigNum::one();
+            let mut res = BigNum::from_vec(BigNum::shr_core(&t.val, rhs));
+            res += &BigNum::one();
+            res.minus();
+            res
+        }
+    }
+}
+
+impl ops::ShrAssign<usize> for BigNum {
+    /// Shifts right by `rhs` bits and move the value to `self`
+    /// Rounds to negative infinity.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(n)` where `n := self.val.len()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let mut a = BigNum::new(-40);
+    /// a >>= 3;
+    ///
+    /// assert_eq!("-5", a.to_string());
+    /// ```
+    fn shr_assign(&mut self, rhs: usize) {
+        self.set_move(&*self >> rhs);
+    }
+}
+
+impl ops::Shl<usize> for BigNum {
+    type Output = BigNum;
+
+    fn shl(self, rhs: usize) -> Self::Output {
+        &self << rhs
+    }
+}
+
+impl ops::Shr<usize> for BigNum {
+    type Output = BigNum;
+
+    fn shr(self, rhs: usize) -> Self::Output {
+        &self >> rhs
+    }
+}
+
+impl ops::BitAnd<&BigNum> for &BigNum {
+    type Output = BigNum;
+
+    /// Bitwise and of two number as two's complement and make new `BigNum` as result
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(max(n, m))` where `n := lhs.val.len()` and `m := rhs.val.len()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let a = BigNum::new(-12);
+    /// let b = BigNum::new(10);
+    ///
+    /// assert_eq!("0", (&a & &b).to_string());
+    /// ```
+    fn bitand(self, rhs: &BigNum) -> Self::Output {
+        BigNum::bitwise(self, rhs, |x, y| x & y)
+    }
+}
+
+impl ops::BitAndAssign<&BigNum> for BigNum {
+    /// Bitwise and of two number as two's complement and move the value to `self`
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(max(n, m))` where `n := lhs.val.len()` and `m := rhs.val.len()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let mut a = BigNum::new(-1);
+    /// a &= &BigNum::new(10);
+    ///
+    /// assert_eq!("10", a.to_string());
+    /// ```
+    fn bitand_assign(&mut self, rhs: &BigNum) {
+        self.set_move(&*self & rhs);
+    }
+}
+
+impl ops::BitOr<&BigNum> for &BigNum {
+    type Output = BigNum;
+
+    /// Bitwise or of two number as two's complement and make new `BigNum` as result
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(max(n, m))` where `n := lhs.val.len()` and `m := rhs.val.len()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let a = BigNum::new(-12);
+    /// let b = BigNum::new(10);
+    ///
+    /// assert_eq!("-2", (&a | &b).to_string());
+    /// ```
+    fn bitor(self, rhs: &BigNum) -> Self::Output {
+        BigNum::bitwise(self, rhs, |x, y| x | y)
+    }
+}
+
+impl ops::BitOrAssign<&BigNum> for BigNum {
+    /// Bitwise or of two number as two's complement and move the value to `self`
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(max(n, m))` where `n := lhs.val.len()` and `m := rhs.val.len()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let mut a = BigNum::new(5);
+    /// a |= &BigNum::new(10);
+    ///
+    /// assert_eq!("15", a.to_string());
+    /// ```
+    fn bitor_assign(&mut self, rhs: &BigNum) {
+        self.set_move(&*self | rhs);
+    }
+}
+
+impl ops::BitXor<&BigNum> for &BigNum {
+    type Output = BigNum;
+
+    /// Bitwise xor of two number as two's complement and make new `BigNum` as result
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(max(n, m))` where `n := lhs.val.len()` and `m := rhs.val.len()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let a = BigNum::new(-12);
+    /// let b = BigNum::new(10);
+    ///
+    /// assert_eq!("-2", (&a ^ &b).to_string());
+    /// ```
+    fn bitxor(self, rhs: &BigNum) -> Self::Output {
+        BigNum::bitwise(self, rhs, |x, y| x ^ y)
+    }
+}
+
+impl ops::BitXorAssign<&BigNum> for BigNum {
+    /// Bitwise xor of two number as two's complement and move the value to `self`
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(max(n, m))` where `n := lhs.val.len()` and `m := rhs.val.len()`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// let mut a = BigNum::new(5);
+    /// a ^= &BigNum::new(6);
+    ///
+    /// assert_eq!("3", a.to_string());
+    /// ```
+    fn bitxor_assign(&mut self, rhs: &BigNum) {
+        self.set_move(&*self ^ rhs);
+    }
+}
+
+impl ops::Neg for BigNum {
+    type Output = BigNum;
+
+    fn neg(self) -> Self::Output {
+        BigNum::neg(&self)
+    }
+}
+
+forward_binop!(BigNum, Add, add, AddAssign, add_assign);
+forward_binop!(BigNum, Sub, sub, SubAssign, sub_assign);
+forward_binop!(BigNum, Mul, mul, MulAssign, mul_assign);
+forward_binop!(BigNum, Div, div, DivAssign, div_assign);
+forward_binop!(BigNum, Rem, rem, RemAssign, rem_assign);
+forward_binop!(BigNum, BitAnd, bitand, BitAndAssign, bitand_assign);
+forward_binop!(BigNum, BitOr, bitor, BitOrAssign, bitor_assign);
+forward_binop!(BigNum, BitXor, bitxor, BitXorAssign, bitxor_assign);
@@ -1,2 +1,68 @@
+/// Implements owned variants of binary operators from the `&T op &T` implementation.
+/// (`T op T`, `T op &T`, `&T op T` and `T op= T`)
+macro_rules! forward_binop {
+    ($t:ty, $imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident) => {
+        impl ops::$imp<$t> for $t {
+            type Output = $t;
+
+            fn $method(self, rhs: $t) -> Self::Output {
+                <&$t as ops::$imp<&$t>>::$method(&self, &rhs)
+            }
+        }
+
+        impl ops::$imp<&$t> for $t {
+            type Output = $t;
+
+            fn $method(self, rhs: &$t) -> Self::Output {
+                <&$t as ops::$imp<&$t>>::$method(&self, rhs)
+            }
+        }
+
+        impl ops::$imp<$t> for &$t {
+            type Output = $t;
+
+            fn $method(self, rhs: $t) -> Self::Output {
+                <&$t as ops::$imp<&$t>>::$method(self, &rhs)
+            }
+        }
+
+        impl ops::$imp_assign<$t> for $t {
+            fn $method_assign(&mut self, rhs: $t) {
+                <$t as ops::$imp_assign<&$t>>::$method_assign(self, &rhs)
+            }
+        }
+    };
+}
+
+/// Implements `Sum` and `Product` from `Add` and `Mul` with `zero()` and `one()`.
+macro_rules! impl_sum_product {
+    ($t:ty) => {
+        impl iter::Sum for $t {
+            fn sum<I: Iterator<Item = $t>>(iter: I) -> $t {
+                iter.fold(<$t>::zero(), |acc, x| &acc + &x)
+            }
+        }
+
+        impl<'a> iter::Sum<&'a $t> for $t {
+            fn sum<I: Iterator<Item = &'a $t>>(iter: I) -> $t {
+                iter.fold(<$t>::zero(), |acc, x| &acc + x)
+            }
+        }
+
+        impl iter::Product for $t {
+            fn product<I: Iterator<Item = $t>>(iter: I) -> $t {
+                iter.fold(<$t>::one(), |acc, x| &acc * &x)
+            }
+        }
+
+        impl<'a> iter::Product<&'a $t> for $t {
+            fn product<I: Iterator<Item = &'a $t>>(iter: I) -> $t {
+                iter.fold(<$t>::one(), |acc, x| &acc * x)
+            }
+        }
+    };
+}
+
+pub mod affine;
 pub mod big_number;
 pub mod num;
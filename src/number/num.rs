@@ -1,12 +1,18 @@
-use crate::number::big_number::BigNum;
-use std::cmp::Ordering;
-use std::mem::swap;
-use std::{fmt, ops};
+use crate::number::big_number::{BigNum, Error};
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::mem::swap;
+use core::str::FromStr;
+use core::{fmt, iter, ops};
 
 /// `Num` for rational number handling
 /// - Using two `BigNum` for denominator and numerator.
 /// - Can handle negative numbers
 /// - Can handle NaN
+/// - NaN is equal to itself and greater than any other number
 ///
 /// # Examples
 ///
@@ -21,12 +27,42 @@ use std::{fmt, ops};
 ///
 /// assert_eq!("10/3", c.to_string());
 /// ```
-#[derive(PartialEq, Clone)]
+#[derive(Clone)]
 pub struct Num {
     up: BigNum,
     down: BigNum,
 }
 
+/// Output format of `Num` used by [`Num::to_string_format`](struct.Num.html#method.to_string_format)
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::number::num::{Format, Num};
+///
+/// let a = Num::new(10, 3);
+///
+/// assert_eq!("10/3", a.to_string_format(Format::Fraction));
+/// assert_eq!("3 1/3", a.to_string_format(Format::Mixed));
+/// assert_eq!("3.333", a.to_string_format(Format::Decimal(3)));
+/// assert_eq!("3.(3)", a.to_string_format(Format::Repeating(100)));
+/// assert_eq!("3.33e0", a.to_string_format(Format::Scientific(2)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// `up/down`, same as `Display`
+    Fraction,
+    /// integer part followed by a proper fraction, such as `3 1/3`
+    Mixed,
+    /// exact decimal expansion truncated to the given number of digits
+    Decimal(usize),
+    /// decimal expansion with the repeating part in parentheses, such as `0.1(6)`;
+    /// it gives up with `...` after the given number of digits
+    Repeating(usize),
+    /// scientific notation with the given number of digits after the point
+    Scientific(usize),
+}
+
 impl Num {
     /// Makes new `Num` from the denominator and numerator.
     /// Supports negative numbers (only denominator)
@@ -199,47 +235,239 @@ impl Num {
         self.down.is_zero()
     }
 
-    /// Make `Num` from `String
-    /// Supports NaN, Negative
+    /// Make `Num` from `String`
+    /// Supports NaN, Negative, fractions, mixed fractions and decimals
+    ///
+    /// # Errors
+    ///
+    /// - `Error::ParseError`: when parse failed, same as `str::parse`
     ///
     /// # Examples
     /// ```
     /// use hyeong::number::num::Num;
     ///
-    /// assert_eq!("10/3", Num::from_string(String::from("10/3")).to_string());
-    /// assert_eq!("너무 커엇...", Num::from_string(String::from("너무 커엇...")).to_string());
-    /// assert_eq!("-12", Num::from_string(String::from("-12")).to_string());
+    /// assert_eq!("10/3", Num::from_string(String::from("10/3")).unwrap().to_string());
+    /// assert_eq!("너무 커엇...", Num::from_string(String::from("너무 커엇...")).unwrap().to_string());
+    /// assert_eq!("-12", Num::from_string(String::from("-12")).unwrap().to_string());
+    /// assert_eq!("-1/7", Num::from_string(String::from("-0.(142857)")).unwrap().to_string());
+    /// assert_eq!("5/4", Num::from_string(String::from("1.25")).unwrap().to_string());
+    /// assert!(Num::from_string(String::from("1/")).is_err());
     /// ```
-    pub fn from_string(mut s: String) -> Num {
-        if s == *"너무 커엇..." {
-            Num::nan()
-        } else {
-            let neg = s.starts_with('-');
-            if neg {
-                s = s[1..].to_string();
+    pub fn from_string(s: String) -> Result<Num, Error> {
+        s.parse()
+    }
+
+    /// Make string with given `Format`
+    /// Every digit is truncated toward zero, and trailing zeros after the point are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::{Format, Num};
+    ///
+    /// let a = Num::new(-1, 7);
+    /// let b = Num::new(1, 800);
+    ///
+    /// assert_eq!("-0.(142857)", a.to_string_format(Format::Repeating(100)));
+    /// assert_eq!("-0.1428...", a.to_string_format(Format::Repeating(4)));
+    /// assert_eq!("0.00125", b.to_string_format(Format::Decimal(10)));
+    /// assert_eq!("1.25e-3", b.to_string_format(Format::Scientific(10)));
+    /// assert_eq!("-1/7", a.to_string_format(Format::Mixed));
+    /// assert_eq!("너무 커엇...", Num::nan().to_string_format(Format::Decimal(3)));
+    /// ```
+    pub fn to_string_format(&self, format: Format) -> String {
+        if self.is_nan() {
+            return self.to_string();
+        }
+
+        let sign = if self.up.is_pos() { "" } else { "-" };
+        let mut abs = self.up.clone();
+        if !abs.is_pos() {
+            abs.minus();
+        }
+
+        match format {
+            Format::Fraction => self.to_string(),
+            Format::Mixed => {
+                let int = &abs / &self.down;
+                let rem = &abs % &self.down;
+                if rem.is_zero() {
+                    format!("{}{}", sign, int)
+                } else if int.is_zero() {
+                    format!("{}{}/{}", sign, rem, self.down)
+                } else {
+                    format!("{}{} {}/{}", sign, int, rem, self.down)
+                }
+            }
+            Format::Decimal(digits) => {
+                let scaled = &(&abs * &Num::pow10(digits)) / &self.down;
+                let s = scaled.to_string();
+                let s = format!("{}{}", "0".repeat((digits + 1).saturating_sub(s.len())), s);
+                let (int, frac) = s.split_at(s.len() - digits);
+                let frac = frac.trim_end_matches('0');
+                let sign = if scaled.is_zero() { "" } else { sign };
+                if frac.is_empty() {
+                    format!("{}{}", sign, int)
+                } else {
+                    format!("{}{}.{}", sign, int, frac)
+                }
+            }
+            Format::Repeating(limit) => {
+                let ten = BigNum::from(10);
+                let mut rest = self.down.clone();
+                let two = rest.trailing_zeros().unwrap();
+                rest >>= two;
+                let mut five = 0;
+                while (&rest % &BigNum::from(5)).is_zero() {
+                    rest /= &BigNum::from(5);
+                    five += 1;
+                }
+                let pre = two.max(five);
+
+                let int = &abs / &self.down;
+                let mut rem = &abs % &self.down;
+                let mut frac = String::new();
+                let mut start = None;
+                while !rem.is_zero() {
+                    if frac.len() == pre {
+                        start = Some(rem.clone());
+                    } else if frac.len() > pre && start.as_ref() == Some(&rem) {
+                        frac.insert(pre, '(');
+                        frac.push(')');
+                        break;
+                    }
+                    if frac.len() == limit {
+                        frac.push_str("...");
+                        break;
+                    }
+                    rem *= &ten;
+                    frac.push_str(&(&rem / &self.down).to_string());
+                    rem %= &self.down;
+                }
+
+                if frac.is_empty() {
+                    format!("{}{}", sign, int)
+                } else {
+                    format!("{}{}.{}", sign, int, frac)
+                }
+            }
+            Format::Scientific(digits) => {
+                if abs.is_zero() {
+                    return String::from("0");
+                }
+
+                // `exp` is the floor of log10, which is off by at most one from the length difference
+                let mut exp = abs.to_string().len() as isize - self.down.to_string().len() as isize;
+                let (l, r) = Num::scale10(&abs, &self.down, -exp);
+                if l < r {
+                    exp -= 1;
+                }
+
+                let (l, r) = Num::scale10(&abs, &self.down, digits as isize - exp);
+                let s = (&l / &r).to_string();
+                let (int, frac) = s.split_at(1);
+                let frac = frac.trim_end_matches('0');
+                if frac.is_empty() {
+                    format!("{}{}e{}", sign, int, exp)
+                } else {
+                    format!("{}{}.{}e{}", sign, int, frac, exp)
+                }
             }
-            let v = s.split('/').map(|x| x.to_string()).collect::<Vec<_>>();
-            let mut res = if v.len() == 1 {
-                Num::from_big_num(BigNum::from_string(v[0].clone()).unwrap(), BigNum::one())
+        }
+    }
+
+    /// Private function for `10^exp`
+    fn pow10(exp: usize) -> BigNum {
+        BigNum::from(10).pow(exp as u32)
+    }
+
+    /// Private function for multiplying `10^exp` to `up / down`.
+    /// Returns new numerator and denominator.
+    fn scale10(up: &BigNum, down: &BigNum, exp: isize) -> (BigNum, BigNum) {
+        if exp >= 0 {
+            (up * &Num::pow10(exp as usize), down.clone())
+        } else {
+            (up.clone(), down * &Num::pow10(exp.unsigned_abs()))
+        }
+    }
+
+    /// Largest absolute exponent accepted by `from_decimal_str`,
+    /// so that a short input like `1e2000000000` does not expand to a huge number
+    const MAX_EXP: i32 = 100_000;
+
+    /// Private function for parsing decimal notation
+    /// such as `1.25`, `-0.(142857)`, `.5` and `1e10`.
+    fn from_decimal_str(s: &str) -> Result<Num, Error> {
+        let (neg, s) = match s.strip_prefix('-') {
+            Some(t) => (true, t),
+            None => (false, s),
+        };
+        let (s, exp) = match s.find(['e', 'E']) {
+            Some(i) => (
+                &s[..i],
+                s[i + 1..].parse::<i32>().map_err(|_| Error::ParseError)?,
+            ),
+            None => (s, 0),
+        };
+        if !(-Num::MAX_EXP..=Num::MAX_EXP).contains(&exp) {
+            return Result::Err(Error::ParseError);
+        }
+        let (int, frac) = match s.find('.') {
+            Some(i) => (&s[..i], &s[i + 1..]),
+            None => (s, ""),
+        };
+        let (fin, rep) = match frac.find('(') {
+            Some(i) => match frac[i + 1..].strip_suffix(')') {
+                Some(t) if !t.is_empty() => (&frac[..i], t),
+                _ => return Result::Err(Error::ParseError),
+            },
+            None => (frac, ""),
+        };
+
+        let is_digits = |x: &str| x.bytes().all(|c| c.is_ascii_digit());
+        if (int.is_empty() && fin.is_empty() && rep.is_empty())
+            || !is_digits(int)
+            || !is_digits(fin)
+            || !is_digits(rep)
+        {
+            return Result::Err(Error::ParseError);
+        }
+        let parse = |x: &str| {
+            if x.is_empty() {
+                Result::Ok(BigNum::zero())
             } else {
-                Num::from_big_num(
-                    BigNum::from_string(v[0].clone()).unwrap(),
-                    BigNum::from_string(v[1].clone()).unwrap(),
-                )
-            };
-            if neg {
-                res.minus();
+                x.parse::<BigNum>()
             }
-            res
+        };
+
+        // x = int.fin(rep) = (int fin rep - int fin) / (10^|fin| * (10^|rep| - 1))
+        let mut up = parse(&format!("{}{}", int, fin))?;
+        let mut down = Num::pow10(fin.len());
+        if !rep.is_empty() {
+            let r = &Num::pow10(rep.len()) - &BigNum::one();
+            up = &(&up * &r) + &parse(rep)?;
+            down *= &r;
+        }
+        let (mut up, down) = Num::scale10(&up, &down, exp as isize);
+        if neg {
+            up.minus();
         }
+        Result::Ok(Num::from_big_num(up, down))
     }
 
     /// Optimize (abbreviation) the number
     /// Also, it makes that only denominator is positive.
     fn optimize(&mut self) {
         let g = BigNum::gcd(&self.up, &self.down);
+        if g.is_zero() {
+            return;
+        }
         self.up /= &g;
         self.down /= &g;
+        if !self.down.is_pos() {
+            self.up.minus();
+            self.down.minus();
+        }
     }
 
     /// Make itself change the sign
@@ -345,6 +573,52 @@ impl Num {
         res
     }
 
+    /// Subtract two `Num` and returns new `Num` as result
+    /// If any of the value is Nan, the result is NaN.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(a * d + b * c + c * d)` where `a / b := lhs` and `c / d := rhs`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::Num;
+    ///
+    /// let a = Num::new(10, 3);
+    /// let b = Num::new(7, 5);
+    /// let c = Num::sub(&a, &b);
+    ///
+    /// assert_eq!("29/15", c.to_string());
+    /// ```
+    pub fn sub(lhs: &Num, rhs: &Num) -> Num {
+        Num::add(lhs, &Num::neg(rhs))
+    }
+
+    /// Divide two `Num` and returns new `Num` as result
+    /// If any of the value is Nan or `rhs` is zero, the result is NaN.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(a * d + b * c)` where `a / b := lhs` and `c / d := rhs`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::Num;
+    ///
+    /// let a = Num::new(10, 3);
+    /// let b = Num::new(7, 5);
+    ///
+    /// assert_eq!("50/21", Num::div(&a, &b).to_string());
+    /// assert_eq!("너무 커엇...", Num::div(&a, &Num::zero()).to_string());
+    /// ```
+    pub fn div(lhs: &Num, rhs: &Num) -> Num {
+        let mut r = rhs.clone();
+        r.flip();
+        Num::mul(lhs, &r)
+    }
+
     /// Returns new `Num` that minus is applied.
     /// If the value is Nan, the result is NaN.
     ///
@@ -361,7 +635,7 @@ impl Num {
     pub fn neg(v: &Num) -> Num {
         Num {
             up: (-&v.up),
-            down: (&v.down).clone(),
+            down: v.down.clone(),
         }
     }
 
@@ -405,9 +679,32 @@ impl Num {
     }
 }
 
+impl PartialEq for Num {
+    /// Equal function of two `Num`
+    /// NaN is equal to NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::Num;
+    ///
+    /// assert!(Num::new(10, 4) == Num::new(5, 2));
+    /// assert!(Num::nan() == -&Num::nan());
+    /// ```
+    fn eq(&self, other: &Self) -> bool {
+        if self.is_nan() || other.is_nan() {
+            self.is_nan() && other.is_nan()
+        } else {
+            self.up == other.up && self.down == other.down
+        }
+    }
+}
+
+impl Eq for Num {}
+
 impl PartialOrd for Num {
     /// Compare function of two `Num`
-    /// If any of the value is Nan, the result is None.
+    /// Always returns `Some`. See `Ord` for the order of NaN.
     ///
     /// # Examples
     ///
@@ -425,18 +722,184 @@ impl PartialOrd for Num {
     /// })
     /// ```
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.is_nan() || other.is_nan() {
-            Option::None
-        } else if self == other {
-            Option::Some(Ordering::Equal)
-        } else if &self.up * &other.down < &self.down * &other.down {
-            Option::Some(Ordering::Less)
+        Option::Some(self.cmp(other))
+    }
+}
+
+impl Ord for Num {
+    /// Compare function of two `Num`
+    /// NaN is equal to NaN and greater than any other number.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(a * d + b * c)` where `a / b := self` and `c / d := other`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::Num;
+    ///
+    /// let mut v = vec![Num::nan(), Num::new(5, 2), Num::from_num(-3), Num::from_num(3)];
+    /// v.sort();
+    ///
+    /// assert_eq!("[-3, 5/2, 3, 너무 커엇...]", format!("{:?}", v));
+    /// ```
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_nan(), other.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => (&self.up * &other.down).cmp(&(&other.up * &self.down)),
+        }
+    }
+}
+
+impl Hash for Num {
+    /// Hash function of `Num`
+    /// Equal values have same hash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::Num;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut s = HashSet::new();
+    /// s.insert(Num::new(10, 4));
+    /// s.insert(Num::nan());
+    ///
+    /// assert!(s.contains(&Num::new(5, 2)));
+    /// assert!(s.contains(&-&Num::nan()));
+    /// ```
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if self.is_nan() {
+            self.down.hash(state);
         } else {
-            Option::Some(Ordering::Greater)
+            self.up.hash(state);
+            self.down.hash(state);
         }
     }
 }
 
+impl FromStr for Num {
+    type Err = Error;
+
+    /// Make `Num` from string
+    /// Supports NaN, negative numbers, fractions (`10/3`), mixed fractions (`3 1/3`),
+    /// decimals with optional repeating part (`1.25`, `-0.(142857)`) and exponents (`1e10`).
+    ///
+    /// # Errors
+    ///
+    /// - `Error::ParseError`: when parse failed, such as an empty numerator or denominator,
+    ///   a signed fraction in a mixed fraction or an exponent larger than `100000` in absolute value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::Num;
+    ///
+    /// let a: Num = "-10/4".parse().unwrap();
+    ///
+    /// assert_eq!(Num::new(-5, 2), a);
+    /// assert_eq!(Num::new(5, 4), "1.25".parse().unwrap());
+    /// assert_eq!(Num::new(-1, 7), "-0.(142857)".parse().unwrap());
+    /// assert_eq!(Num::new(-10, 3), "-3 1/3".parse().unwrap());
+    /// assert_eq!(Num::from_num(10_000_000_000), "1e10".parse().unwrap());
+    /// assert!("1/2/3".parse::<Num>().is_err());
+    /// assert!("0.(1".parse::<Num>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "너무 커엇..." {
+            return Result::Ok(Num::nan());
+        }
+
+        if let Some((int, frac)) = s.split_once(' ') {
+            let (neg, int) = match int.strip_prefix('-') {
+                Some(t) => (true, t),
+                None => (false, int),
+            };
+            if int.starts_with('-') || frac.contains('-') || !frac.contains('/') {
+                return Result::Err(Error::ParseError);
+            }
+            let mut res = &Num::from(int.parse::<BigNum>()?) + &frac.parse::<Num>()?;
+            if neg {
+                res.minus();
+            }
+            return Result::Ok(res);
+        }
+
+        if !s.contains('/') && s.contains(['.', '(', 'e', 'E']) {
+            return Num::from_decimal_str(s);
+        }
+
+        let mut v = s.split('/');
+        let up = v.next().unwrap().parse::<BigNum>()?;
+        let down = match v.next() {
+            Some(t) => t.parse::<BigNum>()?,
+            None => BigNum::one(),
+        };
+        if v.next().is_some() {
+            return Result::Err(Error::ParseError);
+        }
+        Result::Ok(Num::from_big_num(up, down))
+    }
+}
+
+impl From<BigNum> for Num {
+    /// Makes new `Num` from integer `BigNum`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::Num;
+    /// use hyeong::number::big_number::BigNum;
+    ///
+    /// assert_eq!(Num::from_num(-12), Num::from(BigNum::new(-12)));
+    /// ```
+    fn from(n: BigNum) -> Num {
+        Num {
+            up: n,
+            down: BigNum::one(),
+        }
+    }
+}
+
+/// Implements conversions between `Num` and primitive integers and `char`.
+/// Conversion from `Num` fails when the value is not an integer or NaN.
+macro_rules! impl_primitive {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Num {
+            fn from(n: $t) -> Num {
+                Num::from(BigNum::from(n))
+            }
+        }
+
+        impl TryFrom<&Num> for $t {
+            type Error = Error;
+
+            fn try_from(n: &Num) -> Result<$t, Error> {
+                if n.down != BigNum::one() {
+                    Result::Err(Error::RangeError)
+                } else {
+                    <$t>::try_from(&n.up)
+                }
+            }
+        }
+
+        impl TryFrom<Num> for $t {
+            type Error = Error;
+
+            fn try_from(n: Num) -> Result<$t, Error> {
+                <$t>::try_from(&n)
+            }
+        }
+    )*};
+}
+
+impl_primitive!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, char);
+
+impl_sum_product!(Num);
+
 impl fmt::Debug for Num {
     /// Printing function of `Num`
     ///
@@ -581,6 +1044,106 @@ impl ops::MulAssign<&Num> for Num {
     }
 }
 
+impl ops::Sub<&Num> for &Num {
+    type Output = Num;
+
+    /// Subtract two `Num` and returns new `Num` as result
+    /// If any of the value is Nan, the result is NaN.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(a * d + b * c + c * d)` where `a / b := lhs` and `c / d := rhs`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::Num;
+    ///
+    /// let a = Num::new(10, 3);
+    /// let b = Num::new(7, 5);
+    /// let c = &a - &b;
+    ///
+    /// assert_eq!("29/15", c.to_string());
+    /// ```
+    fn sub(self, rhs: &Num) -> Self::Output {
+        Num::sub(self, rhs)
+    }
+}
+
+impl ops::SubAssign<&Num> for Num {
+    /// Subtract two `Num` and moves the value to `self`
+    /// If any of the value is Nan, the result is NaN.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(a * d + b * c + c * d)` where `a / b := lhs` and `c / d := rhs`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::Num;
+    ///
+    /// let mut a = Num::new(10, 3);
+    /// let b = Num::new(7, 5);
+    /// a -= &b;
+    ///
+    /// assert_eq!("29/15", a.to_string());
+    /// ```
+    fn sub_assign(&mut self, rhs: &Num) {
+        self.set_move(&*self - rhs);
+    }
+}
+
+impl ops::Div<&Num> for &Num {
+    type Output = Num;
+
+    /// Divide two `Num` and returns new `Num` as result
+    /// If any of the value is Nan or `rhs` is zero, the result is NaN.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(a * d + b * c)` where `a / b := lhs` and `c / d := rhs`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::Num;
+    ///
+    /// let a = Num::new(10, 3);
+    /// let b = Num::new(7, 5);
+    /// let c = &a / &b;
+    ///
+    /// assert_eq!("50/21", c.to_string());
+    /// ```
+    fn div(self, rhs: &Num) -> Self::Output {
+        Num::div(self, rhs)
+    }
+}
+
+impl ops::DivAssign<&Num> for Num {
+    /// Divide two `Num` and moves the value to `self`
+    /// If any of the value is Nan or `rhs` is zero, the result is NaN.
+    ///
+    /// # Time Complexity
+    ///
+    /// `O(a * d + b * c)` where `a / b := lhs` and `c / d := rhs`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::number::num::Num;
+    ///
+    /// let mut a = Num::new(10, 3);
+    /// let b = Num::new(7, 5);
+    /// a /= &b;
+    ///
+    /// assert_eq!("50/21", a.to_string());
+    /// ```
+    fn div_assign(&mut self, rhs: &Num) {
+        self.set_move(&*self / rhs);
+    }
+}
+
 impl ops::Neg for &Num {
     type Output = Num;
 
@@ -601,3 +1164,16 @@ impl ops::Neg for &Num {
         Num::neg(self)
     }
 }
+
+impl ops::Neg for Num {
+    type Output = Num;
+
+    fn neg(self) -> Self::Output {
+        Num::neg(&self)
+    }
+}
+
+forward_binop!(Num, Add, add, AddAssign, add_assign);
+forward_binop!(Num, Sub, sub, SubAssign, sub_assign);
+forward_binop!(Num, Mul, mul, MulAssign, mul_assign);
+forward_binop!(Num, Div, div, DivAssign, div_assign);
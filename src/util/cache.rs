@@ -0,0 +1,85 @@
+use crate::util::error::Error;
+use std::env::consts::EXE_SUFFIX;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Offset basis of 128-bit FNV-1a
+const FNV_OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+
+/// Prime of 128-bit FNV-1a
+const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+/// 128-bit FNV-1a hash of the parts
+///
+/// Unlike the hasher of std, it is the same on every platform and version of rust,
+/// so the cache stays valid when the compiler is rebuilt.
+/// Each part is prefixed with its length, so moving bytes between parts changes the hash.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::util::cache;
+///
+/// assert_eq!(cache::hash(&[b"ab", b"c"]), cache::hash(&[b"ab", b"c"]));
+/// assert_ne!(cache::hash(&[b"ab", b"c"]), cache::hash(&[b"a", b"bc"]));
+/// ```
+pub fn hash(parts: &[&[u8]]) -> u128 {
+    let mut res = FNV_OFFSET;
+    for part in parts {
+        for b in (part.len() as u64).to_le_bytes().iter().chain(part.iter()) {
+            res ^= *b as u128;
+            res = res.wrapping_mul(FNV_PRIME);
+        }
+    }
+    res
+}
+
+/// Key of the build in the cache
+///
+/// It is the hash of the parts and the version of this compiler in hex.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::util::cache;
+///
+/// let a = cache::key(&["fn main() {}", "release"]);
+///
+/// assert_eq!(32, a.len());
+/// assert_ne!(a, cache::key(&["fn main() {}", "debug"]));
+/// ```
+pub fn key(parts: &[&str]) -> String {
+    let mut bytes = vec![env!("CARGO_PKG_VERSION").as_bytes()];
+    bytes.extend(parts.iter().map(|s| s.as_bytes()));
+    format!("{:032x}", hash(&bytes))
+}
+
+/// Path of the binary cached with `key`
+pub fn binary(build_path: &Path, key: &str) -> PathBuf {
+    build_path
+        .join("cache")
+        .join(key)
+        .join(format!("main{}", EXE_SUFFIX))
+}
+
+/// Directory to build `key` in, which is different for each process
+pub fn workspace(build_path: &Path, key: &str) -> PathBuf {
+    build_path
+        .join("work")
+        .join(format!("{}-{}", key, process::id()))
+}
+
+/// Copy the built binary to the cache
+///
+/// It is copied next to the destination first and renamed,
+/// so that other builds never see a binary that is partly written.
+pub fn store(file: &Path, build_path: &Path, key: &str) -> Result<PathBuf, Error> {
+    let res = binary(build_path, key);
+    let dir = res.parent().unwrap();
+    fs::create_dir_all(dir)?;
+    let temp = dir.join(format!("main-{}.tmp", process::id()));
+    fs::copy(file, &temp)?;
+    fs::rename(&temp, &res)?;
+    Ok(res)
+}
@@ -1,5 +1,7 @@
-use crate::core::code::UnOptCode;
+use crate::core::code::{Code, OptCode, UnOptCode};
+use crate::core::optimize::{Config, PassManager, Program};
 use crate::core::parse;
+use crate::core::state::OptState;
 use crate::number::num::Num;
 use crate::util::error::Error;
 use crate::util::io;
@@ -58,6 +60,71 @@ pub fn parse_file(
     Ok(un_opt_code)
 }
 
+/// Parsed commands, one in each line with the index and the location in the source
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::core::parse;
+/// use hyeong::util::ext;
+///
+/// let c = parse::parse(String::from("형... 흑.\n하앗...♥"));
+///
+/// assert_eq!("0 | 1:0 형_1_3 _\n1 | 1:5 흑_1_1 _\n2 | 2:0 핫_2_3 ♥\n", ext::ast_to_string(&c));
+/// ```
+pub fn ast_to_string(code: &[UnOptCode]) -> String {
+    code.iter()
+        .enumerate()
+        .map(|(i, c)| {
+            format!(
+                "{} | {}:{} {}_{}_{} {}\n",
+                i,
+                c.get_location().0,
+                c.get_location().1,
+                parse::COMMANDS[c.get_type() as usize],
+                c.get_hangul_count(),
+                c.get_dot_count(),
+                c.get_area()
+            )
+        })
+        .collect()
+}
+
+/// Optimize code with the pipeline of optimization level
+///
+/// In verbose mode, it prints the statistics of each pass.
+/// If `dump_after` is set, it prints the program after that pass.
+/// If `explain_opt` is set, it prints the [report](../../core/optimize/struct.Program.html#method.explain) of the result.
+pub fn optimize_code(
+    stdout: &mut StandardStream,
+    code: Vec<UnOptCode>,
+    option: &HyeongOption,
+) -> Result<(OptState, Vec<OptCode>), Error> {
+    io::print_log(stdout, format!("optimizing to level {}", option.optimize))?;
+    let mut pm = PassManager::from_config(
+        &Config::new(option.optimize).pre_exec_steps(option.pre_exec_steps),
+    );
+    if let Some(pass) = &option.dump_after {
+        pm = pm.dump_after(pass.clone());
+    }
+    let (program, report) = pm.run(Program::new(code))?;
+
+    if option.verbose {
+        for stats in &report.stats {
+            io::print_log(stdout, format!("⮑  {}", stats))?;
+        }
+    }
+    for (pass, dump) in report.dumps {
+        io::print_log(stdout, format!("program after {}", pass))?;
+        write!(stdout, "{}", dump)?;
+    }
+    if option.explain_opt {
+        io::print_log(stdout, "optimization report")?;
+        write!(stdout, "{}", program.explain())?;
+    }
+    Ok((program.state, program.code))
+}
+
 /// change `Num` to unicode char
 ///
 /// # Examples
@@ -127,16 +194,28 @@ pub fn execute_command_stdout(w: &mut StandardStream, command: &str) -> Result<(
 /// Execute command and stream stdout to `StandardStream`
 #[cfg(not(tarpaulin_include))]
 pub fn execute_command_stderr(w: &mut StandardStream, command: &str) -> Result<(), Error> {
+    execute_command_stderr_env(w, command, &[])
+}
+
+/// Execute command with environment variables set and stream stderr to `StandardStream`
+#[cfg(not(tarpaulin_include))]
+pub fn execute_command_stderr_env(
+    w: &mut StandardStream,
+    command: &str,
+    env: &[(&str, String)],
+) -> Result<(), Error> {
     let mut cmd = if cfg!(target_os = "windows") {
         Command::new("cmd")
             .arg("/C")
             .arg(command)
+            .envs(env.iter().map(|(k, v)| (k, v)))
             .stderr(Stdio::piped())
             .spawn()?
     } else {
         Command::new("bash")
             .arg("-c")
             .arg(command)
+            .envs(env.iter().map(|(k, v)| (k, v)))
             .stderr(Stdio::piped())
             .spawn()?
     };
@@ -1,3 +1,4 @@
+use crate::number::num::Format;
 use crate::util::error::Error;
 use clap::{Arg, ArgMatches};
 use std::env;
@@ -48,7 +49,7 @@ pub fn color<'a>() -> Arg<'a> {
         .help("whether prints color")
         .default_value("auto")
         .global(true)
-        .possible_values(&["never", "auto", "always"])
+        .possible_values(["never", "auto", "always"])
         .multiple_occurrences(false)
 }
 
@@ -63,6 +64,210 @@ pub fn parse_color(matches: &ArgMatches) -> Result<ColorChoice, Error> {
     }
 }
 
+/// Dump after pass option
+#[cfg(not(tarpaulin_include))]
+pub fn dump_after<'a>() -> Arg<'a> {
+    Arg::new("dump-after")
+        .value_name("pass")
+        .takes_value(true)
+        .long("dump-after")
+        .help("print the program after optimization pass")
+        .multiple_occurrences(false)
+}
+
+/// Parse dump after pass option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_dump_after(matches: &ArgMatches) -> Option<String> {
+    matches.value_of("dump-after").map(String::from)
+}
+
+/// Language that hyeong code is compiled to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Backend {
+    /// rust, built with cargo
+    Rust,
+    /// C99, built with `cc`
+    C,
+    /// JavaScript module, which is not built
+    Js,
+    /// WebAssembly text module, which is not built
+    Wat,
+}
+
+impl Backend {
+    /// Extension of the source file
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Backend::Rust => "rs",
+            Backend::C => "c",
+            Backend::Js => "js",
+            Backend::Wat => "wat",
+        }
+    }
+
+    /// Whether the source is built to binary
+    pub fn has_binary(&self) -> bool {
+        !matches!(self, Backend::Js | Backend::Wat)
+    }
+}
+
+/// Backend option
+#[cfg(not(tarpaulin_include))]
+pub fn backend<'a>() -> Arg<'a> {
+    Arg::new("backend")
+        .value_name("backend")
+        .takes_value(true)
+        .long("backend")
+        .help("language to compile to")
+        .default_value("rust")
+        .possible_values(["rust", "c", "js", "wat"])
+        .multiple_occurrences(false)
+}
+
+/// Parse backend option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_backend(matches: &ArgMatches) -> Backend {
+    match matches.value_of("backend").unwrap() {
+        "rust" => Backend::Rust,
+        "c" => Backend::C,
+        "js" => Backend::Js,
+        "wat" => Backend::Wat,
+        _ => unreachable!(),
+    }
+}
+
+/// Output kind of build, in the order of stages
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Emit {
+    /// parsed commands
+    Ast,
+    /// optimized commands with the initial state
+    Opt,
+    /// generated source, in the language of backend
+    Rust,
+    /// map from lines of generated rust code to hyeong commands
+    Map,
+    /// single rust file with the runtime inlined, built with `rustc -O`
+    RustStandalone,
+    /// binary built with cargo
+    Bin,
+}
+
+impl Emit {
+    /// Extension of the file written
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyeong::util::option::{Backend, Emit};
+    ///
+    /// assert_eq!("opt", Emit::Opt.extension(Backend::Rust));
+    /// assert_eq!("c", Emit::Rust.extension(Backend::C));
+    /// assert_eq!("js", Emit::Bin.extension(Backend::Js));
+    /// ```
+    pub fn extension(&self, backend: Backend) -> &'static str {
+        match self {
+            Emit::Ast => "ast",
+            Emit::Opt => "opt",
+            Emit::Rust => backend.extension(),
+            Emit::Map => "map",
+            Emit::RustStandalone => "rs",
+            Emit::Bin if backend.has_binary() => "",
+            Emit::Bin => backend.extension(),
+        }
+    }
+}
+
+/// Kinds of output that build writes, sorted in the order of stages
+///
+/// With `emit_only` or backend without binary, the binary is replaced with the source.
+/// The last one is written to the output, and the others next to it with their extension.
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::util::option::{self, Backend, Emit};
+///
+/// assert_eq!(
+///     vec![Emit::Ast, Emit::Rust],
+///     option::emit_kinds(&[Emit::Bin, Emit::Ast, Emit::Rust], true, Backend::Rust)
+/// );
+/// assert_eq!(vec![Emit::Rust], option::emit_kinds(&[Emit::Bin], false, Backend::Js));
+/// ```
+pub fn emit_kinds(emit: &[Emit], emit_only: bool, backend: Backend) -> Vec<Emit> {
+    let mut res = emit
+        .iter()
+        .map(|e| match e {
+            Emit::Bin if emit_only || !backend.has_binary() => Emit::Rust,
+            _ => *e,
+        })
+        .collect::<Vec<_>>();
+    res.sort_unstable();
+    res.dedup();
+    res
+}
+
+/// Emit option
+#[cfg(not(tarpaulin_include))]
+pub fn emit<'a>() -> Arg<'a> {
+    Arg::new("emit")
+        .value_name("emit")
+        .takes_value(true)
+        .long("emit")
+        .help("output kinds, separated by comma")
+        .default_value("bin")
+        .possible_values(["ast", "opt", "rust", "map", "rust-standalone", "bin"])
+        .use_value_delimiter(true)
+        .require_value_delimiter(true)
+        .multiple_occurrences(false)
+}
+
+/// Parse emit option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_emit(matches: &ArgMatches) -> Vec<Emit> {
+    matches
+        .values_of("emit")
+        .unwrap()
+        .map(|x| match x {
+            "ast" => Emit::Ast,
+            "opt" => Emit::Opt,
+            "rust" => Emit::Rust,
+            "map" => Emit::Map,
+            "rust-standalone" => Emit::RustStandalone,
+            "bin" => Emit::Bin,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Emit only option
+#[cfg(not(tarpaulin_include))]
+pub fn emit_only<'a>() -> Arg<'a> {
+    Arg::new("emit-only")
+        .long("emit-only")
+        .help("write the generated source to output without compiling it")
+}
+
+/// Parse emit only option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_emit_only(matches: &ArgMatches) -> bool {
+    matches.is_present("emit-only")
+}
+
+/// Explain optimization option
+#[cfg(not(tarpaulin_include))]
+pub fn explain_opt<'a>() -> Arg<'a> {
+    Arg::new("explain-opt")
+        .long("explain-opt")
+        .help("print what the optimizer did: stack map, pre-executed state and point table")
+}
+
+/// Parse explain optimization option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_explain_opt(matches: &ArgMatches) -> bool {
+    matches.is_present("explain-opt")
+}
+
 /// Path to input of program
 #[cfg(not(tarpaulin_include))]
 pub fn input<'a>() -> Arg<'a> {
@@ -87,6 +292,111 @@ pub fn parse_input(matches: &ArgMatches) -> std::io::Result<PathBuf> {
     }
 }
 
+/// Number format option
+#[cfg(not(tarpaulin_include))]
+pub fn num_format<'a>() -> Arg<'a> {
+    Arg::new("num-format")
+        .value_name("num-format")
+        .takes_value(true)
+        .long("num-format")
+        .help("format of printed numbers")
+        .possible_values(NUM_FORMATS)
+        .multiple_occurrences(false)
+}
+
+/// Names of number formats
+pub const NUM_FORMATS: [&str; 5] = ["fraction", "mixed", "decimal", "repeating", "scientific"];
+
+/// Make number format from its name
+///
+/// # Examples
+///
+/// ```
+/// use hyeong::number::num::Format;
+/// use hyeong::util::option;
+///
+/// assert_eq!(Some(Format::Decimal(5)), option::num_format_from_name("decimal", 5));
+/// assert_eq!(None, option::num_format_from_name("hex", 5));
+/// ```
+pub fn num_format_from_name(name: &str, precision: usize) -> Option<Format> {
+    match name {
+        "fraction" => Some(Format::Fraction),
+        "mixed" => Some(Format::Mixed),
+        "decimal" => Some(Format::Decimal(precision)),
+        "repeating" => Some(Format::Repeating(precision)),
+        "scientific" => Some(Format::Scientific(precision)),
+        _ => None,
+    }
+}
+
+/// Parse number format option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_num_format(matches: &ArgMatches) -> Result<Option<Format>, Error> {
+    let precision = parse_precision(matches)?;
+    Ok(matches
+        .value_of("num-format")
+        .and_then(|x| num_format_from_name(x, precision)))
+}
+
+/// Step budget option for pre-execution
+#[cfg(not(tarpaulin_include))]
+pub fn pre_exec_steps<'a>() -> Arg<'a> {
+    Arg::new("pre-exec-steps")
+        .value_name("steps")
+        .takes_value(true)
+        .long("pre-exec-steps")
+        .help("maximum number of commands executed while optimizing in level 2")
+        .default_value("10000")
+        .multiple_occurrences(false)
+}
+
+/// Parse step budget option for pre-execution
+#[cfg(not(tarpaulin_include))]
+pub fn parse_pre_exec_steps(matches: &ArgMatches) -> Result<usize, Error> {
+    match matches.value_of("pre-exec-steps").unwrap().parse::<usize>() {
+        Ok(t) => Ok(t),
+        Err(e) => Err(Error::from(e, "steps should be a non-negative integer")),
+    }
+}
+
+/// Standard input file option
+#[cfg(not(tarpaulin_include))]
+pub fn stdin<'a>() -> Arg<'a> {
+    Arg::new("stdin")
+        .value_name("FILE")
+        .takes_value(true)
+        .long("input")
+        .help("file to use as standard input of the program")
+        .multiple_occurrences(false)
+}
+
+/// Parse standard input file option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_stdin(matches: &ArgMatches) -> Option<PathBuf> {
+    matches.value_of("stdin").map(PathBuf::from)
+}
+
+/// Precision option for number format
+#[cfg(not(tarpaulin_include))]
+pub fn precision<'a>() -> Arg<'a> {
+    Arg::new("precision")
+        .value_name("precision")
+        .takes_value(true)
+        .long("precision")
+        .help("digits printed in decimal, repeating and scientific number format")
+        .default_value("10")
+        .multiple_occurrences(false)
+}
+
+/// Parse precision option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_precision(matches: &ArgMatches) -> Result<usize, Error> {
+    match matches.value_of("precision").unwrap().parse::<usize>() {
+        Ok(t) => Ok(t),
+        Err(e) => Err(Error::from(e, "precision should be a non-negative integer")),
+    }
+}
+
 /// Optimization option
 #[cfg(not(tarpaulin_include))]
 pub fn optimize<'a>() -> Arg<'a> {
@@ -97,7 +407,7 @@ pub fn optimize<'a>() -> Arg<'a> {
         .long("optimize")
         .help("optimize level")
         .default_value("2")
-        .possible_values(&["0", "1", "2"])
+        .possible_values(["0", "1", "2", "3"])
         .multiple_occurrences(false)
 }
 
@@ -108,10 +418,157 @@ pub fn parse_optimize(matches: &ArgMatches) -> Result<u8, Error> {
         "0" => Ok(0),
         "1" => Ok(1),
         "2" => Ok(2),
+        "3" => Ok(3),
         _ => unreachable!(),
     }
 }
 
+/// Profile of the built binary
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Profile {
+    /// unoptimized with debug info
+    Debug,
+    /// optimized for speed
+    Release,
+    /// optimized for size with link time optimization
+    Size,
+}
+
+impl Profile {
+    /// Name of cargo profile, which is also the name of the section in `Cargo.toml`
+    pub fn cargo_name(&self) -> &'static str {
+        match self {
+            Profile::Debug => "dev",
+            Profile::Release => "release",
+            Profile::Size => "size",
+        }
+    }
+
+    /// Directory of the binary in the target directory
+    pub fn target_dir(&self) -> &'static str {
+        match self {
+            Profile::Debug => "debug",
+            Profile::Release => "release",
+            Profile::Size => "size",
+        }
+    }
+
+    /// `opt-level` of the profile when it is not set
+    pub fn opt_level(&self) -> &'static str {
+        match self {
+            Profile::Debug => "0",
+            Profile::Release => "3",
+            Profile::Size => "z",
+        }
+    }
+}
+
+/// Profile option
+#[cfg(not(tarpaulin_include))]
+pub fn profile<'a>() -> Arg<'a> {
+    Arg::new("profile")
+        .value_name("profile")
+        .takes_value(true)
+        .long("profile")
+        .help("profile of the built binary")
+        .default_value("release")
+        .possible_values(["debug", "release", "size"])
+        .multiple_occurrences(false)
+}
+
+/// Parse profile option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_profile(matches: &ArgMatches) -> Profile {
+    match matches.value_of("profile").unwrap() {
+        "debug" => Profile::Debug,
+        "release" => Profile::Release,
+        "size" => Profile::Size,
+        _ => unreachable!(),
+    }
+}
+
+/// Opt-level option of rustc
+#[cfg(not(tarpaulin_include))]
+pub fn opt_level<'a>() -> Arg<'a> {
+    Arg::new("opt-level")
+        .value_name("level")
+        .takes_value(true)
+        .long("opt-level")
+        .help("opt-level of the built binary, instead of the one of profile")
+        .possible_values(["0", "1", "2", "3", "s", "z"])
+        .multiple_occurrences(false)
+}
+
+/// Parse opt-level option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_opt_level(matches: &ArgMatches) -> Option<String> {
+    matches.value_of("opt-level").map(String::from)
+}
+
+/// Link time optimization option
+#[cfg(not(tarpaulin_include))]
+pub fn lto<'a>() -> Arg<'a> {
+    Arg::new("lto")
+        .value_name("lto")
+        .takes_value(true)
+        .long("lto")
+        .help("link time optimization of the built binary, instead of the one of profile")
+        .possible_values(["off", "thin", "fat"])
+        .multiple_occurrences(false)
+}
+
+/// Parse link time optimization option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_lto(matches: &ArgMatches) -> Option<String> {
+    matches.value_of("lto").map(String::from)
+}
+
+/// Panic abort option
+#[cfg(not(tarpaulin_include))]
+pub fn panic_abort<'a>() -> Arg<'a> {
+    Arg::new("panic-abort")
+        .long("panic-abort")
+        .help("abort on panic instead of unwinding, which exits by signal instead of code 101")
+}
+
+/// Parse panic abort option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_panic_abort(matches: &ArgMatches) -> bool {
+    matches.is_present("panic-abort")
+}
+
+/// Runtime flags option
+#[cfg(not(tarpaulin_include))]
+pub fn runtime_flags<'a>() -> Arg<'a> {
+    Arg::new("runtime-flags")
+        .long("runtime-flags")
+        .help("let the binary take `--trace`, `--max-steps N` and `--stats` (rust backend only)")
+}
+
+/// Parse runtime flags option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_runtime_flags(matches: &ArgMatches) -> bool {
+    matches.is_present("runtime-flags")
+}
+
+/// Extra rustc flags option
+#[cfg(not(tarpaulin_include))]
+pub fn rustflags<'a>() -> Arg<'a> {
+    Arg::new("rustflags")
+        .value_name("flags")
+        .takes_value(true)
+        .long("rustflags")
+        .allow_hyphen_values(true)
+        .help("flags passed to rustc, after the ones in `RUSTFLAGS`")
+        .multiple_occurrences(false)
+}
+
+/// Parse extra rustc flags option
+#[cfg(not(tarpaulin_include))]
+pub fn parse_rustflags(matches: &ArgMatches) -> Option<String> {
+    matches.value_of("rustflags").map(String::from)
+}
+
 /// Path to output of program
 #[cfg(not(tarpaulin_include))]
 pub fn output<'a>() -> Arg<'a> {
@@ -120,7 +577,7 @@ pub fn output<'a>() -> Arg<'a> {
         .takes_value(true)
         .short('o')
         .long("output")
-        .help("binary output file (filename by default)")
+        .help("output file of the last stage emitted, next to which the other stages are written")
         .multiple_occurrences(false)
 }
 
@@ -137,7 +594,12 @@ pub fn parse_output(matches: &ArgMatches, input: &Path) -> std::io::Result<PathB
             Ok(p)
         }
     } else {
-        Ok(input.with_extension(""))
+        let kinds = emit_kinds(
+            &parse_emit(matches),
+            parse_emit_only(matches),
+            parse_backend(matches),
+        );
+        Ok(input.with_extension(kinds.last().unwrap().extension(parse_backend(matches))))
     }
 }
 
@@ -163,11 +625,26 @@ pub fn parse_verbose(matches: &ArgMatches) -> bool {
 /// All of the options
 #[derive(Clone)]
 pub struct HyeongOption {
+    pub backend: Backend,
     pub build_path: Option<PathBuf>,
     pub color: ColorChoice,
+    pub dump_after: Option<String>,
+    pub emit: Vec<Emit>,
+    pub emit_only: bool,
+    pub explain_opt: bool,
     pub input: Option<PathBuf>,
+    pub lto: Option<String>,
+    pub num_format: Option<Format>,
+    pub opt_level: Option<String>,
     pub optimize: u8,
     pub output: Option<PathBuf>,
+    pub panic_abort: bool,
+    pub pre_exec_steps: usize,
+    pub precision: usize,
+    pub profile: Profile,
+    pub runtime_flags: bool,
+    pub rustflags: Option<String>,
+    pub stdin: Option<PathBuf>,
     pub verbose: bool,
 }
 
@@ -182,15 +659,38 @@ impl HyeongOption {
     #[cfg(not(tarpaulin_include))]
     pub fn new() -> HyeongOption {
         HyeongOption {
+            backend: Backend::Rust,
             build_path: None,
             color: ColorChoice::Auto,
+            dump_after: None,
+            emit: vec![Emit::Bin],
+            emit_only: false,
+            explain_opt: false,
             input: None,
+            lto: None,
+            num_format: None,
+            opt_level: None,
             optimize: 0,
             output: None,
+            panic_abort: false,
+            pre_exec_steps: 10000,
+            precision: 10,
+            profile: Profile::Release,
+            runtime_flags: false,
+            rustflags: None,
+            stdin: None,
             verbose: false,
         }
     }
 
+    /// Add `backend` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn backend(mut self, backend: Backend) -> HyeongOption {
+        self.backend = backend;
+        self
+    }
+
     /// Add `build_path` option
     #[cfg(not(tarpaulin_include))]
     #[must_use]
@@ -207,6 +707,38 @@ impl HyeongOption {
         self
     }
 
+    /// Add `dump_after` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn dump_after(mut self, pass: Option<String>) -> HyeongOption {
+        self.dump_after = pass;
+        self
+    }
+
+    /// Add `emit` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn emit(mut self, emit: Vec<Emit>) -> HyeongOption {
+        self.emit = emit;
+        self
+    }
+
+    /// Add `emit_only` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn emit_only(mut self, emit_only: bool) -> HyeongOption {
+        self.emit_only = emit_only;
+        self
+    }
+
+    /// Add `explain_opt` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn explain_opt(mut self, explain: bool) -> HyeongOption {
+        self.explain_opt = explain;
+        self
+    }
+
     /// Add `input` option
     #[cfg(not(tarpaulin_include))]
     #[must_use]
@@ -215,6 +747,30 @@ impl HyeongOption {
         self
     }
 
+    /// Add `lto` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn lto(mut self, lto: Option<String>) -> HyeongOption {
+        self.lto = lto;
+        self
+    }
+
+    /// Add `num_format` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn num_format(mut self, format: Option<Format>) -> HyeongOption {
+        self.num_format = format;
+        self
+    }
+
+    /// Add `opt_level` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn opt_level(mut self, level: Option<String>) -> HyeongOption {
+        self.opt_level = level;
+        self
+    }
+
     /// Add `optimize` option
     #[cfg(not(tarpaulin_include))]
     #[must_use]
@@ -231,6 +787,62 @@ impl HyeongOption {
         self
     }
 
+    /// Add `panic_abort` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn panic_abort(mut self, abort: bool) -> HyeongOption {
+        self.panic_abort = abort;
+        self
+    }
+
+    /// Add `pre_exec_steps` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn pre_exec_steps(mut self, steps: usize) -> HyeongOption {
+        self.pre_exec_steps = steps;
+        self
+    }
+
+    /// Add `precision` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn precision(mut self, precision: usize) -> HyeongOption {
+        self.precision = precision;
+        self
+    }
+
+    /// Add `profile` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn profile(mut self, profile: Profile) -> HyeongOption {
+        self.profile = profile;
+        self
+    }
+
+    /// Add `runtime_flags` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn runtime_flags(mut self, flags: bool) -> HyeongOption {
+        self.runtime_flags = flags;
+        self
+    }
+
+    /// Add `rustflags` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn rustflags(mut self, flags: Option<String>) -> HyeongOption {
+        self.rustflags = flags;
+        self
+    }
+
+    /// Add `stdin` option
+    #[cfg(not(tarpaulin_include))]
+    #[must_use]
+    pub fn stdin(mut self, path: Option<PathBuf>) -> HyeongOption {
+        self.stdin = path;
+        self
+    }
+
     /// Add `verbose` option
     #[cfg(not(tarpaulin_include))]
     #[must_use]
@@ -3,6 +3,8 @@ mod big_number_test {
     use hyeong::number::big_number::BigNum;
     use hyeong::number::big_number::Error;
     use std::cmp::Ordering;
+    use std::collections::HashSet;
+    use std::convert::TryFrom;
 
     #[test]
     fn add_test01() {
@@ -162,6 +164,17 @@ mod big_number_test {
         );
     }
 
+    #[test]
+    fn mul_test04() {
+        let a = BigNum::from_string("9".repeat(2000)).unwrap();
+        let c = &a * &a;
+
+        assert_eq!(
+            format!("{}8{}1", "9".repeat(1999), "0".repeat(1999)),
+            c.to_string()
+        );
+    }
+
     #[test]
     fn div_test01() {
         let a = BigNum::new(4321);
@@ -180,6 +193,65 @@ mod big_number_test {
         assert_eq!(BigNum::new(-1234 / 31), c);
     }
 
+    #[test]
+    fn div_test03() {
+        let a = BigNum::from_string("1234567891".repeat(300)).unwrap();
+        let b = BigNum::from_string("9876543".repeat(200)).unwrap();
+        let q = &a / &b;
+        let r = &a % &b;
+
+        assert!(r < b);
+        assert_eq!(a, &(&q * &b) + &r);
+    }
+
+    #[test]
+    fn div_test04() {
+        let a = BigNum::from_string("-1000000000000000000000000000000".to_string()).unwrap();
+        let b = BigNum::from_string("7777777777777".to_string()).unwrap();
+
+        assert_eq!("-128571428571441428", (&a / &b).to_string());
+        assert_eq!("-4444444454444", (&a % &b).to_string());
+    }
+
+    #[test]
+    fn div_test05() {
+        // long divisors that are divided with the reciprocal by Newton's method
+        let mut x = 20201019u64;
+        let mut random = |n: usize| {
+            BigNum::from_vec(
+                (0..n)
+                    .map(|_| {
+                        x = x
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        (x >> 32) as u32
+                    })
+                    .collect(),
+            )
+        };
+        for n in [64, 65, 100, 127, 200, 333, 513] {
+            let mut top = vec![0; n];
+            top[n - 1] = 0x80000000;
+            let mut one = vec![0; n];
+            one[n - 1] = 1;
+            let divisors = [
+                BigNum::from_vec(vec![u32::MAX; n]),
+                BigNum::from_vec(top),
+                BigNum::from_vec(one),
+                random(n),
+            ];
+            for b in divisors {
+                for a in [&(&b * &b) - &BigNum::one(), &b * &b, random(2 * n - 1)] {
+                    let q = &a / &b;
+                    let r = &a % &b;
+
+                    assert!(r >= BigNum::zero() && r < b);
+                    assert_eq!(a, &(&q * &b) + &r);
+                }
+            }
+        }
+    }
+
     #[test]
     fn from_string_test01() {
         let a = BigNum::from_string("1234".to_string()).unwrap();
@@ -215,6 +287,37 @@ mod big_number_test {
         assert!(matches!(a, Result::Err(Error::BaseSizeError(100))));
     }
 
+    #[test]
+    fn from_string_test06() {
+        let a = BigNum::from_string_base("ff".to_string(), 16).unwrap();
+        let b = BigNum::from_string_base("-zZ".to_string(), 36).unwrap();
+
+        assert_eq!(BigNum::new(255), a);
+        assert_eq!(BigNum::new(-1295), b);
+    }
+
+    #[test]
+    fn from_string_test07() {
+        let a = BigNum::from_string_base("102".to_string(), 2);
+
+        assert!(matches!(a, Result::Err(Error::ParseError)));
+    }
+
+    #[test]
+    fn from_string_test08() {
+        let a = BigNum::from_string_base("0".to_string(), 1);
+
+        assert!(matches!(a, Result::Err(Error::BaseSizeError(1))));
+    }
+
+    #[test]
+    fn from_string_test09() {
+        let a = BigNum::from_string_base("-0".to_string(), 10).unwrap();
+
+        assert!(a.is_pos());
+        assert_eq!("0", a.to_string());
+    }
+
     #[test]
     fn to_string_test01() {
         let a = BigNum::new(1234);
@@ -237,6 +340,57 @@ mod big_number_test {
         assert!(matches!(b, Result::Err(Error::BaseSizeError(100))));
     }
 
+    #[test]
+    fn to_string_test04() {
+        let a = BigNum::from_vec(vec![0x89abcdef, 0x01234567, 0xf]);
+
+        assert_eq!("F0123456789ABCDEF", a.to_string_base(16).unwrap());
+        assert_eq!("36004432126361152746757", a.to_string_base(8).unwrap());
+        assert_eq!("1MEV9OVWQ3Q033", a.to_string_base(36).unwrap());
+    }
+
+    #[test]
+    fn to_string_test05() {
+        let s = "1234567890".repeat(1000);
+        let a = BigNum::from_string(s.clone()).unwrap();
+
+        assert_eq!(s, a.to_string());
+    }
+
+    #[test]
+    fn to_string_test06() {
+        let a = BigNum::from_string(format!("1{}", "0".repeat(3000))).unwrap();
+        let b = BigNum::from_string_base(a.to_string_base(7).unwrap(), 7).unwrap();
+        let c = BigNum::from_string_base(a.to_string_base(32).unwrap(), 32).unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+    }
+
+    #[test]
+    fn to_string_test07() {
+        let a = BigNum::new(1234);
+
+        assert!(matches!(
+            a.to_string_base(1),
+            Result::Err(Error::BaseSizeError(1))
+        ));
+    }
+
+    #[test]
+    fn to_string_test08() {
+        // numbers next to the powers of 10^9 that split the digits in halves
+        for k in [288, 576, 1152, 2304, 4608, 9216] {
+            for s in [
+                "9".repeat(k),
+                format!("1{}", "0".repeat(k)),
+                format!("1{}1", "0".repeat(k - 1)),
+            ] {
+                assert_eq!(s, BigNum::from_string(s.clone()).unwrap().to_string());
+            }
+        }
+    }
+
     #[test]
     fn equal_test01() {
         let a = BigNum::zero();
@@ -282,4 +436,253 @@ mod big_number_test {
 
         assert_eq!(0, a.to_int());
     }
+
+    #[test]
+    fn owned_ops_test01() {
+        let a = BigNum::new(-1234);
+        let b = BigNum::new(31);
+
+        assert_eq!(BigNum::new(-1203), a.clone() + b.clone());
+        assert_eq!(BigNum::new(-1265), a.clone() - &b);
+        assert_eq!(BigNum::new(-38254), &a * b.clone());
+        assert_eq!(BigNum::new(-39), a.clone() / b.clone());
+        assert_eq!(BigNum::new(-25), a.clone() % b.clone());
+        assert_eq!(BigNum::new(1234), -a);
+    }
+
+    #[test]
+    fn owned_ops_test02() {
+        let mut a = BigNum::new(100);
+        a %= BigNum::new(30);
+        a *= BigNum::new(7);
+        a -= BigNum::new(1);
+
+        assert_eq!(BigNum::new(69), a);
+    }
+
+    #[test]
+    fn compare_test04() {
+        let mut v = vec![
+            BigNum::new(3),
+            BigNum::new(-10),
+            BigNum::zero(),
+            BigNum::new(-2),
+        ];
+        v.sort();
+
+        assert_eq!(
+            vec![
+                BigNum::new(-10),
+                BigNum::new(-2),
+                BigNum::zero(),
+                BigNum::new(3)
+            ],
+            v
+        );
+        assert_eq!(Ordering::Equal, BigNum::zero().cmp(&-&BigNum::zero()));
+    }
+
+    #[test]
+    fn hash_test01() {
+        let mut s = HashSet::new();
+        s.insert(BigNum::from_vec(vec![0, 1]));
+        s.insert(BigNum::zero());
+
+        assert!(s.contains(&"4294967296".parse().unwrap()));
+        assert!(s.contains(&"-0".parse().unwrap()));
+        assert!(!s.contains(&BigNum::new(1)));
+    }
+
+    #[test]
+    fn from_str_test01() {
+        assert_eq!(BigNum::new(-1234), "-1234".parse().unwrap());
+        assert!(matches!(
+            "12a".parse::<BigNum>(),
+            Result::Err(Error::ParseError)
+        ));
+    }
+
+    #[test]
+    fn from_str_test02() {
+        for s in ["", "-", "--1", "-0x"] {
+            assert!(matches!(
+                s.parse::<BigNum>(),
+                Result::Err(Error::ParseError)
+            ));
+        }
+        assert!(matches!(
+            BigNum::from_string_base(String::from("-"), 16),
+            Result::Err(Error::ParseError)
+        ));
+    }
+
+    #[test]
+    fn sum_test01() {
+        let v = vec![BigNum::new(2), BigNum::new(-3), BigNum::new(7)];
+
+        assert_eq!(BigNum::new(6), v.iter().sum());
+        assert_eq!(BigNum::new(-42), v.into_iter().product());
+    }
+
+    #[test]
+    fn convert_test01() {
+        assert_eq!("-128", BigNum::from(i8::MIN).to_string());
+        assert_eq!(
+            "170141183460469231731687303715884105727",
+            BigNum::from(i128::MAX).to_string()
+        );
+        assert_eq!(
+            "-170141183460469231731687303715884105728",
+            BigNum::from(i128::MIN).to_string()
+        );
+        assert_eq!(
+            "340282366920938463463374607431768211455",
+            BigNum::from(u128::MAX).to_string()
+        );
+        assert_eq!("18446744073709551615", BigNum::from(u64::MAX).to_string());
+        assert_eq!(BigNum::new(65), BigNum::from('A'));
+    }
+
+    #[test]
+    fn convert_test02() {
+        assert_eq!(i128::MIN, i128::try_from(BigNum::from(i128::MIN)).unwrap());
+        assert_eq!(u128::MAX, u128::try_from(&BigNum::from(u128::MAX)).unwrap());
+        assert_eq!(-5, i64::try_from(BigNum::new(-5)).unwrap());
+        assert_eq!('A', char::try_from(BigNum::new(65)).unwrap());
+    }
+
+    #[test]
+    fn convert_test03() {
+        assert!(matches!(
+            u32::try_from(BigNum::new(-1)),
+            Result::Err(Error::RangeError)
+        ));
+        assert!(matches!(
+            i8::try_from(BigNum::new(128)),
+            Result::Err(Error::RangeError)
+        ));
+        assert!(matches!(
+            i128::try_from(&BigNum::from(u128::MAX)),
+            Result::Err(Error::RangeError)
+        ));
+        assert!(matches!(
+            u128::try_from(BigNum::from_vec(vec![0, 0, 0, 0, 1])),
+            Result::Err(Error::RangeError)
+        ));
+        assert!(matches!(
+            char::try_from(BigNum::new(0xD800)),
+            Result::Err(Error::RangeError)
+        ));
+    }
+
+    #[test]
+    fn pow_test01() {
+        let a = BigNum::from_string("-123456789123".to_string()).unwrap();
+        let mut b = BigNum::one();
+        for _ in 0..13 {
+            b *= &a;
+        }
+
+        assert_eq!(b, a.pow(13));
+        assert_eq!(BigNum::one(), BigNum::zero().pow(0));
+    }
+
+    #[test]
+    fn isqrt_test01() {
+        let a = BigNum::from_string("98765432123456789".repeat(30)).unwrap();
+        let r = a.isqrt();
+        let s = &r + &BigNum::one();
+
+        assert!(&r * &r <= a);
+        assert!(&s * &s > a);
+        assert_eq!(BigNum::new(31622), BigNum::new(1_000_000_000).isqrt());
+    }
+
+    #[test]
+    fn modpow_test01() {
+        let a = BigNum::new(-7);
+        let m = BigNum::from_string("1000000007".to_string()).unwrap();
+        let p = a.pow(50);
+        let r = &(&(&p % &m) + &m) % &m;
+
+        assert_eq!(r, a.modpow(&BigNum::new(50), &m));
+        assert_eq!(BigNum::zero(), a.modpow(&BigNum::new(3), &BigNum::one()));
+    }
+
+    #[test]
+    fn modinv_test01() {
+        let m = BigNum::from_string("1000000007".to_string()).unwrap();
+        let a = BigNum::from_string("-123456789123456789".to_string()).unwrap();
+        let b = a.modinv(&m).unwrap();
+
+        assert_eq!(BigNum::one(), (&(&a * &b) % &m + &m) % &m);
+        assert!(BigNum::new(10).modinv(&BigNum::new(15)).is_none());
+    }
+
+    #[test]
+    fn lcm_test01() {
+        let a = BigNum::new(-12);
+        let b = BigNum::new(18);
+        let l = BigNum::lcm(&a, &b);
+
+        assert_eq!(BigNum::new(36), l);
+        assert_eq!(BigNum::zero(), &l % &a);
+        assert_eq!(BigNum::zero(), &l % &b);
+        assert_eq!(BigNum::zero(), BigNum::lcm(&a, &BigNum::zero()));
+    }
+
+    #[test]
+    fn shift_test01() {
+        let a = BigNum::from_string("-123456789123456789123456789".to_string()).unwrap();
+        let p = BigNum::new(2).pow(45);
+
+        assert_eq!(&a * &p, &a << 45);
+        assert_eq!(&a / &p - BigNum::one(), &a >> 45);
+        assert_eq!(&(-&a) / &p, &(-&a) >> 45);
+        assert_eq!(BigNum::new(-1), &BigNum::new(-1) >> 100);
+        assert_eq!(BigNum::zero(), &BigNum::new(1) >> 100);
+    }
+
+    #[test]
+    fn bitwise_test01() {
+        let v = [
+            -70000000000i64,
+            -12,
+            -1,
+            0,
+            5,
+            10,
+            4294967296,
+            81985529216486895,
+        ];
+        for &x in v.iter() {
+            for &y in v.iter() {
+                let (a, b) = (BigNum::from(x), BigNum::from(y));
+
+                assert_eq!(BigNum::from(x & y), &a & &b);
+                assert_eq!(BigNum::from(x | y), &a | &b);
+                assert_eq!(BigNum::from(x ^ y), &a ^ &b);
+            }
+        }
+    }
+
+    #[test]
+    fn bitwise_test02() {
+        let a = BigNum::from_string("-98765432123456789987654321".to_string()).unwrap();
+        let b = BigNum::from_string("123456789987654321123".to_string()).unwrap();
+
+        assert_eq!(&a + &b, &(&a & &b) + &(&a | &b));
+        assert_eq!(&a ^ &b, &(&a | &b) - &(&a & &b));
+    }
+
+    #[test]
+    fn bit_len_test01() {
+        let a = BigNum::new(2).pow(100);
+
+        assert_eq!(101, a.bit_len());
+        assert_eq!(101, (-&a).bit_len());
+        assert_eq!(Some(100), a.trailing_zeros());
+        assert_eq!(Some(0), BigNum::new(-7).trailing_zeros());
+        assert_eq!(None, BigNum::zero().trailing_zeros());
+    }
 }
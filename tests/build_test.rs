@@ -1,13 +1,17 @@
-﻿#[cfg(test)]
+#[cfg(test)]
 mod build_test {
-    use hyeong::app::init;
+    use hyeong::app::{build, init};
+    use hyeong::core::compile::map::{self, SourceMap};
     use hyeong::core::state::UnOptState;
     use hyeong::core::{compile, optimize, parse};
-    use hyeong::util::option::HyeongOption;
+    use hyeong::util::option::{Backend, Emit, HyeongOption, Profile};
     use hyeong::util::{ext, io};
     use std::env;
+    use std::fs;
     use std::path::PathBuf;
-    use std::process::Command;
+    use std::process::{Command, Stdio};
+    use std::thread;
+    use std::time::{Duration, Instant};
     use termcolor::{ColorChoice, StandardStream};
 
     fn helper_function(name: &str, code: &str, level: u8) -> String {
@@ -29,6 +33,8 @@ mod build_test {
         p.push(format!(".hyeong/test/{}", name));
         if !p.join("hyeong-build/Cargo.toml").exists() {
             init::install_run(&mut s, &HyeongOption::new().build_path(p.clone())).unwrap();
+        } else {
+            init::update_runtime(&p).unwrap();
         }
         p.push("hyeong-build");
 
@@ -36,7 +42,7 @@ mod build_test {
 
         ext::execute_command_stdout(
             &mut s,
-            &*format!(
+            &format!(
                 "cargo build --manifest-path={} --release",
                 ext::path_to_string(&p.join("Cargo.toml")).unwrap()
             ),
@@ -105,4 +111,349 @@ mod build_test {
             )
         );
     }
+
+    #[test]
+    fn build_test05() {
+        let p = env::temp_dir().join(format!("hyeong-runtime-test-{}", std::process::id()));
+        init::update_runtime(&p).unwrap();
+        for file in ["mod.rs", "affine.rs", "big_number.rs", "num.rs"] {
+            assert_eq!(
+                fs::read_to_string(PathBuf::from("src/number").join(file)).unwrap(),
+                fs::read_to_string(p.join("hyeong-runtime/src/number").join(file)).unwrap()
+            );
+        }
+        assert!(fs::read_to_string(p.join("hyeong-build/Cargo.toml"))
+            .unwrap()
+            .contains("path = \"../hyeong-runtime\""));
+        fs::remove_dir_all(p).unwrap();
+    }
+
+    #[test]
+    fn build_test06() {
+        let p = env::temp_dir().join(format!("hyeong-standalone-test-{}", std::process::id()));
+        fs::create_dir_all(&p).unwrap();
+        let code = fs::read_to_string("examples/1_to_8/1_to_8.hyeong").unwrap();
+        for level in 0..=3 {
+            let un_opt_code = parse::parse(code.clone());
+            let source = if level >= 1 {
+                let (opt_state, opt_code) = optimize::optimize(un_opt_code, level).unwrap();
+                compile::build_source(opt_state, &opt_code, level)
+            } else {
+                compile::build_source(UnOptState::new(), &un_opt_code, level)
+            };
+            let source = compile::build_standalone_source(source);
+
+            let main = p.join(format!("main{}.rs", level));
+            let bin = p.join(format!("main{}", level));
+            io::save_to_file(&main, source).unwrap();
+            let status = Command::new("rustc")
+                .arg("-O")
+                .arg(&main)
+                .arg("-o")
+                .arg(&bin)
+                .status()
+                .unwrap();
+            assert!(status.success());
+            assert_eq!(
+                "12345678",
+                String::from_utf8(Command::new(&bin).output().unwrap().stdout).unwrap()
+            );
+        }
+        fs::remove_dir_all(p).unwrap();
+    }
+
+    #[test]
+    fn build_test07() {
+        // loop over many states, which are dispatched by flat match to functions
+        let mut code = String::from("형.......... 형.......... 하앗... 항...♥");
+        for i in 0..300 {
+            code.push_str(&format!(" 형{}?", ".".repeat(i % 8 + 1)));
+        }
+        code.push_str(" 형. 흣.... 하앙... 흑...?♥ 흣.");
+        let (opt_state, opt_code) = optimize::optimize(parse::parse(code), 1).unwrap();
+        let source = compile::build_source(opt_state, &opt_code, 1);
+        assert!(source.contains("let next = match state {"));
+        assert!(source.contains("fn state_301("));
+        assert!(source.contains("let mut point: Point = [usize::MAX; 1];"));
+
+        let p = env::temp_dir().join(format!("hyeong-states-test-{}", std::process::id()));
+        fs::create_dir_all(&p).unwrap();
+        io::save_to_file(&p.join("main.rs"), compile::build_standalone_source(source)).unwrap();
+        let status = Command::new("rustc")
+            .arg(p.join("main.rs"))
+            .arg("-o")
+            .arg(p.join("main"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(
+            "2",
+            String::from_utf8(Command::new(p.join("main")).output().unwrap().stdout).unwrap()
+        );
+        fs::remove_dir_all(p).unwrap();
+    }
+
+    #[test]
+    fn build_test08() {
+        // intermediate stages are written next to the output, without building binary
+        let p = env::temp_dir().join(format!("hyeong-emit-test-{}", std::process::id()));
+        fs::create_dir_all(&p).unwrap();
+        let mut s = StandardStream::stdout(ColorChoice::Never);
+        let input = PathBuf::from("examples/hello_world/hello_world.hyeong");
+        build::run(
+            &mut s,
+            &HyeongOption::new()
+                .emit(vec![Emit::Rust, Emit::Ast, Emit::Opt])
+                .input(input.clone())
+                .optimize(1)
+                .output(p.join("hello.rs")),
+        )
+        .unwrap();
+
+        let un_opt_code = parse::parse(fs::read_to_string(&input).unwrap());
+        assert_eq!(
+            ext::ast_to_string(&un_opt_code),
+            fs::read_to_string(p.join("hello.ast")).unwrap()
+        );
+        let (opt_state, opt_code) = optimize::optimize(un_opt_code.clone(), 1).unwrap();
+        assert!(fs::read_to_string(p.join("hello.opt"))
+            .unwrap()
+            .starts_with("stacks: "));
+        let source = compile::build_source(opt_state, &opt_code, 1);
+        let map = SourceMap::new(&source, &un_opt_code);
+        assert_eq!(
+            map::add_panic_hook(source, &map, "hello_world.hyeong"),
+            fs::read_to_string(p.join("hello.rs")).unwrap()
+        );
+        assert!(!p.join("hello").exists());
+        fs::remove_dir_all(p).unwrap();
+    }
+
+    #[test]
+    fn build_test09() {
+        // second build of the same source is taken from the cache, and workspaces are removed
+        let p = env::temp_dir().join(format!("hyeong-cache-test-{}", std::process::id()));
+        let mut s = StandardStream::stdout(ColorChoice::Never);
+        let hy_opt = HyeongOption::new()
+            .backend(Backend::C)
+            .build_path(p.clone())
+            .input(PathBuf::from("examples/1_to_8/1_to_8.hyeong"))
+            .optimize(1);
+        for name in ["a", "b"] {
+            build::run(&mut s, &hy_opt.clone().output(p.join(name))).unwrap();
+            assert_eq!(
+                "12345678",
+                String::from_utf8(Command::new(p.join(name)).output().unwrap().stdout).unwrap()
+            );
+        }
+        assert_eq!(1, fs::read_dir(p.join("cache")).unwrap().count());
+        assert_eq!(0, fs::read_dir(p.join("work")).unwrap().count());
+
+        build::run(&mut s, &hy_opt.optimize(2).output(p.join("c"))).unwrap();
+        assert_eq!(2, fs::read_dir(p.join("cache")).unwrap().count());
+        fs::remove_dir_all(p).unwrap();
+    }
+
+    #[test]
+    fn build_test10() {
+        // each profile is built and cached separately
+        let p = env::temp_dir().join(format!("hyeong-profile-test-{}", std::process::id()));
+        let mut s = StandardStream::stdout(ColorChoice::Never);
+        let hy_opt = HyeongOption::new()
+            .backend(Backend::C)
+            .build_path(p.clone())
+            .input(PathBuf::from("examples/hello_world/hello_world.hyeong"))
+            .optimize(1);
+        for (name, profile) in [
+            ("debug", Profile::Debug),
+            ("release", Profile::Release),
+            ("size", Profile::Size),
+        ] {
+            build::run(
+                &mut s,
+                &hy_opt.clone().profile(profile).output(p.join(name)),
+            )
+            .unwrap();
+            assert_eq!(
+                "Hello, world!\n",
+                String::from_utf8(Command::new(p.join(name)).output().unwrap().stdout).unwrap()
+            );
+        }
+        assert_eq!(3, fs::read_dir(p.join("cache")).unwrap().count());
+        fs::remove_dir_all(p).unwrap();
+    }
+
+    #[test]
+    fn build_test11() {
+        // every command of generated code is mapped back to the parsed command
+        let code = fs::read_to_string("examples/hello_world/hello_world.hyeong").unwrap();
+        for level in 0..=3 {
+            let un_opt_code = parse::parse(code.clone());
+            let source = if level >= 1 {
+                let (opt_state, opt_code) = optimize::optimize(un_opt_code.clone(), level).unwrap();
+                compile::build_source(opt_state, &opt_code, level)
+            } else {
+                compile::build_source(UnOptState::new(), &un_opt_code, level)
+            };
+            let map = SourceMap::new(&source, &un_opt_code);
+            assert_eq!(
+                source.matches("// hyeong command ").count(),
+                map.entries.len()
+            );
+            for e in &map.entries {
+                assert_eq!(un_opt_code[e.index].get_location(), e.location);
+                assert!(source.lines().nth(e.first - 1).unwrap().starts_with("    "));
+            }
+        }
+    }
+
+    #[test]
+    fn build_test12() {
+        // panic from printing invalid character reports the hyeong location
+        let p = env::temp_dir().join(format!("hyeong-map-test-{}", std::process::id()));
+        fs::create_dir_all(&p).unwrap();
+        let un_opt_code = parse::parse(String::from(
+            "혀엉................ 혀엉................ 혀엉................\n  \
+             혀엉................ 형.. 하아아아앗.",
+        ));
+        let (opt_state, opt_code) = optimize::optimize(un_opt_code.clone(), 1).unwrap();
+        let source = compile::build_source(opt_state, &opt_code, 1);
+        let map = SourceMap::new(&source, &un_opt_code);
+        let source = map::add_panic_hook(source, &map, "bad.hyeong");
+
+        io::save_to_file(&p.join("main.rs"), compile::build_standalone_source(source)).unwrap();
+        let status = Command::new("rustc")
+            .arg(p.join("main.rs"))
+            .arg("-o")
+            .arg(p.join("main"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let output = Command::new(p.join("main")).output().unwrap();
+        assert_eq!(Some(101), output.status.code());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("note: at command 5 of hyeong code, bad.hyeong:2:25\n"));
+        fs::remove_dir_all(p).unwrap();
+    }
+
+    #[test]
+    fn build_test13() {
+        // binary with runtime flags traces, limits and counts the commands
+        let p = env::temp_dir().join(format!("hyeong-flags-test-{}", std::process::id()));
+        fs::create_dir_all(&p).unwrap();
+        let un_opt_code = parse::parse(String::from("형... 흑...... 항. 하앗... 흑. 항."));
+        let (opt_state, opt_code) = optimize::optimize(un_opt_code.clone(), 1).unwrap();
+        let source = compile::build_source_with_flags(opt_state, &opt_code, 1, true);
+        let map = SourceMap::new(&source, &un_opt_code);
+        let source = map::add_panic_hook(source, &map, "a.hyeong");
+
+        io::save_to_file(&p.join("main.rs"), compile::build_standalone_source(source)).unwrap();
+        let status = Command::new("rustc")
+            .arg(p.join("main.rs"))
+            .arg("-o")
+            .arg(p.join("main"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let run = |args: &[&str]| {
+            let output = Command::new(p.join("main")).args(args).output().unwrap();
+            (
+                output.status.code(),
+                String::from_utf8(output.stderr).unwrap(),
+            )
+        };
+        assert_eq!((Some(0), String::new()), run(&[]));
+        assert_eq!(
+            (Some(0), String::from("steps: 6\nstack 3: 2\nstack 4: 1\n")),
+            run(&["--stats"])
+        );
+        assert_eq!(
+            (
+                Some(124),
+                String::from(
+                    "trace: step 1, command 0 at 1:0, stack 3\n\
+                     trace: step 2, command 1 at 1:5, stack 3\n\
+                     stopped after 2 steps\n"
+                )
+            ),
+            run(&["--trace", "--max-steps", "2"])
+        );
+        assert_eq!(Some(2), run(&["--max-steps"]).0);
+        fs::remove_dir_all(p).unwrap();
+    }
+
+    #[test]
+    fn build_test14() {
+        // accelerated loop is run in closed form, also when pre-execution stops inside it
+        let p = env::temp_dir().join(format!("hyeong-accel-test-{}", std::process::id()));
+        fs::create_dir_all(&p).unwrap();
+        let code = format!(
+            "{}하{}앗... 흣... 형...💕 하앙... 흑...💕? 흣..",
+            "형.......... ".repeat(30),
+            "아".repeat(28)
+        );
+        for steps in [0, 1000] {
+            let config = optimize::Config::new(3).pre_exec_steps(steps);
+            let (program, _) = optimize::PassManager::from_config(&config)
+                .run(optimize::Program::new(parse::parse(code.clone())))
+                .unwrap();
+            let source = compile::build_source(program.state, &program.code, 3);
+            assert!(source.contains("AffineLoop::new("));
+
+            io::save_to_file(&p.join("main.rs"), compile::build_standalone_source(source)).unwrap();
+            let status = Command::new("rustc")
+                .arg(p.join("main.rs"))
+                .arg("-o")
+                .arg(p.join("main"))
+                .status()
+                .unwrap();
+            assert!(status.success());
+            // the loop takes 10^30 / 3 iterations without acceleration
+            let mut child = Command::new(p.join("main"))
+                .stderr(Stdio::piped())
+                .spawn()
+                .unwrap();
+            let start = Instant::now();
+            while child.try_wait().unwrap().is_none() {
+                if start.elapsed() > Duration::from_secs(60) {
+                    child.kill().unwrap();
+                    panic!("loop is not accelerated with {} steps", steps);
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            let output = child.wait_with_output().unwrap();
+            assert_eq!("5", String::from_utf8(output.stderr).unwrap());
+        }
+        fs::remove_dir_all(p).unwrap();
+    }
+
+    #[test]
+    fn build_test15() {
+        // compiler and flags from the environment are part of the cache key
+        let p = env::temp_dir().join(format!("hyeong-env-test-{}", std::process::id()));
+        let build = |backend: &str, var: &str, value: &str, name: &str| {
+            let status = Command::new(env!("CARGO_BIN_EXE_hyeong"))
+                .args(["build", "--color", "never", "--profile", "debug"])
+                .args(["--backend", backend, "--build-path"])
+                .arg(&p)
+                .arg("-o")
+                .arg(p.join(name))
+                .arg("examples/hello_world/hello_world.hyeong")
+                .env(var, value)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success());
+            fs::read_dir(p.join("cache")).unwrap().count()
+        };
+        assert_eq!(1, build("c", "CC", "cc", "a"));
+        assert_eq!(2, build("c", "CC", "cc -DHYEONG_TEST", "b"));
+        assert_eq!(2, build("c", "CC", "cc", "c"));
+        assert_eq!(3, build("rust", "RUSTFLAGS", "", "d"));
+        assert_eq!(4, build("rust", "RUSTFLAGS", "-Cdebug-assertions=off", "e"));
+        assert_eq!(4, build("rust", "RUSTFLAGS", "", "f"));
+        fs::remove_dir_all(p).unwrap();
+    }
 }
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod c_test {
+    use hyeong::core::compile::c;
+    use hyeong::core::state::UnOptState;
+    use hyeong::core::{optimize, parse};
+    use std::env;
+    use std::fs;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    /// Builds `code` to C with system `cc` and runs it, returning stdout and exit code
+    fn helper_function(name: &str, code: &str, level: u8, stdin: &str) -> (String, i32) {
+        let un_opt_code = parse::parse(code.to_string());
+        let source = if level >= 1 {
+            let (opt_state, opt_code) = optimize::optimize(un_opt_code, level).unwrap();
+            c::build_source(opt_state, &opt_code, level)
+        } else {
+            c::build_source(UnOptState::new(), &un_opt_code, level)
+        };
+
+        let p = env::temp_dir().join(format!("hyeong-c-test-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&p).unwrap();
+        fs::write(p.join("main.c"), source).unwrap();
+        let status = Command::new("cc")
+            .args(["-std=c99", "-O2", "-o"])
+            .arg(p.join("main"))
+            .arg(p.join("main.c"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let mut child = Command::new(p.join("main"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        fs::remove_dir_all(p).unwrap();
+        (
+            String::from_utf8(output.stdout).unwrap(),
+            output.status.code().unwrap(),
+        )
+    }
+
+    #[test]
+    fn c_test01() {
+        let cases = [
+            ("1_to_8", "", "12345678"),
+            ("a_mult_b", "3 4\n", "12"),
+            ("a_plus_b", "12 30\n", "42"),
+            ("hello_world", "", "Hello, world!\n"),
+        ];
+        for (name, stdin, out) in cases {
+            let code = fs::read_to_string(format!("examples/{}/{}.hyeong", name, name)).unwrap();
+            for level in 0..=3 {
+                assert_eq!(
+                    (String::from(out), 0),
+                    helper_function(&format!("{}{}", name, level), &code, level, stdin),
+                    "{} at level {}",
+                    name,
+                    level
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn c_test02() {
+        // exit by popping stack 2 in area, before printing
+        for level in 0..=3 {
+            assert_eq!(
+                (String::new(), 1),
+                helper_function(
+                    &format!("exit{}", level),
+                    "형.. 흑.. 형... 하앙..? 형",
+                    level,
+                    ""
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn c_test03() {
+        // big number, fraction and NaN
+        let cases = [
+            (
+                "혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ \
+                 혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ \
+                 하아아아아아아아아아아앗... 흣... 항.",
+                "281474976710656",
+            ),
+            ("형... 흡... 흣... 항.", "1/3"),
+            ("형 흡... 항. 항.", "너무 커엇...너무 커엇..."),
+        ];
+        for (i, (code, out)) in cases.iter().enumerate() {
+            for level in [0, 3] {
+                assert_eq!(
+                    (String::from(*out), 0),
+                    helper_function(&format!("num{}{}", i, level), code, level, "")
+                );
+            }
+        }
+    }
+}
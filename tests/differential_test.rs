@@ -0,0 +1,339 @@
+#[cfg(test)]
+mod differential_test {
+    use hyeong::core::code::{self, Code};
+    use hyeong::core::{optimize, parse};
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Stdio};
+    use std::time::{Duration, Instant};
+
+    /// Output of running a program: stdout, stderr and exit code
+    type RunResult = (String, String, Option<i32>);
+
+    fn temp_path(name: &str) -> PathBuf {
+        let mut p = std::env::temp_dir();
+        p.push(format!(
+            "hyeong-differential-{}-{}",
+            std::process::id(),
+            name
+        ));
+        p
+    }
+
+    /// Run `cmd` with `input` and return output, or `None` when it takes too long
+    fn spawn(cmd: &mut Command, input: &str, name: &str) -> Option<RunResult> {
+        let in_path = temp_path(&format!("{}.in", name));
+        let out_path = temp_path(&format!("{}.out", name));
+        let err_path = temp_path(&format!("{}.err", name));
+        fs::write(&in_path, input).unwrap();
+
+        let mut child = cmd
+            .stdin(File::open(&in_path).unwrap())
+            .stdout(File::create(&out_path).unwrap())
+            .stderr(File::create(&err_path).unwrap())
+            .spawn()
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(3);
+        let status = loop {
+            if let Some(status) = child.try_wait().unwrap() {
+                break Some(status);
+            }
+            if Instant::now() > deadline {
+                child.kill().unwrap();
+                child.wait().unwrap();
+                break None;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        };
+
+        let out = String::from_utf8_lossy(&fs::read(&out_path).unwrap()).to_string();
+        let err = String::from_utf8_lossy(&fs::read(&err_path).unwrap()).to_string();
+        for p in [in_path, out_path, err_path] {
+            fs::remove_file(p).unwrap();
+        }
+
+        let status = status?;
+        let out = match out.find("==> running code\n") {
+            Some(i) => out[i + "==> running code\n".len()..].to_string(),
+            None => out,
+        };
+        Some((out, err, status.code()))
+    }
+
+    /// Run `hyeong run` with `args` and return output, or `None` when it takes too long
+    fn run(path: &Path, input: &str, args: &[&str], name: &str) -> Option<RunResult> {
+        spawn(
+            Command::new(env!("CARGO_BIN_EXE_hyeong"))
+                .args(["run", "--color", "never"])
+                .args(args)
+                .arg(path),
+            input,
+            name,
+        )
+    }
+
+    /// Build the binary of level 3 without pre-execution and return output of running it
+    ///
+    /// `build` is the build path, shared by the programs of a test.
+    fn run_binary(path: &Path, input: &str, build: &Path, name: &str) -> Option<RunResult> {
+        let bin = temp_path(&format!("{}.bin", name));
+        let status = Command::new(env!("CARGO_BIN_EXE_hyeong"))
+            .args(["build", "--color", "never", "--profile", "debug"])
+            .args(["-O", "3", "--pre-exec-steps", "0", "--build-path"])
+            .arg(build)
+            .arg("-o")
+            .arg(&bin)
+            .arg(path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success(), "failed to build {}", path.display());
+        let res = spawn(&mut Command::new(&bin), input, name);
+        fs::remove_file(bin).unwrap();
+        res
+    }
+
+    /// Compare output of every optimization level with level 0
+    ///
+    /// Level 3 also runs without pre-execution, so that later passes see the whole program.
+    /// With `build`, the binary built from it is also compared.
+    fn check(path: &Path, input: &str, name: &str, build: Option<&Path>) {
+        let expected = match run(path, input, &["-O", "0"], name) {
+            Some(t) => t,
+            None => return,
+        };
+        let options: &[&[&str]] = &[
+            &["-O", "1"],
+            &["-O", "2"],
+            &["-O", "3"],
+            &["-O", "3", "--pre-exec-steps", "0"],
+        ];
+        for args in options {
+            if let Some(res) = run(path, input, args, name) {
+                assert_eq!(
+                    expected,
+                    res,
+                    "{:?} differs on {}\n{}",
+                    args,
+                    path.display(),
+                    fs::read_to_string(path).unwrap()
+                );
+            }
+        }
+        if let Some(res) = build.and_then(|b| run_binary(path, input, b, name)) {
+            assert_eq!(
+                expected,
+                res,
+                "binary differs on {}\n{}",
+                path.display(),
+                fs::read_to_string(path).unwrap()
+            );
+        }
+    }
+
+    /// Simple linear congruential generator for reproducible programs
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self, n: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 33) % n as u64) as usize
+        }
+    }
+
+    fn random_program(rng: &mut Rng) -> String {
+        let first = ["혀", "하", "하", "흐", "흐", "흐"];
+        let mid = ["어", "아", "아", "으", "으", "으"];
+        let last = ["엉", "앙", "앗", "읏", "읍", "윽"];
+        let single = ["형", "항", "핫", "흣", "흡", "흑"];
+        let area = ["?", "!", "♥", "❤", "♡", "💕"];
+
+        let mut s = String::new();
+        for _ in 0..rng.next(20) + 1 {
+            let t = rng.next(6);
+            let cnt = rng.next(3) + 1;
+            if cnt == 1 {
+                s.push_str(single[t]);
+            } else {
+                s.push_str(first[t]);
+                s.push_str(&mid[t].repeat(cnt - 2));
+                s.push_str(last[t]);
+            }
+            s.push_str(&".".repeat(rng.next(6)));
+            if rng.next(3) == 0 {
+                for _ in 0..rng.next(3) + 1 {
+                    s.push_str(area[rng.next(area.len())]);
+                }
+            }
+            s.push(' ');
+        }
+        s
+    }
+
+    /// Program with a loop that only makes simple updates on stacks 3 to 5
+    ///
+    /// The loop counts on stack `d`, from the heart of the head pushing on stack `t`
+    /// to the back edge `흑{t}` or `흐윽{t}` jumping back,
+    /// which have the same id since both have `h` hangul and `t` dots.
+    /// The back edge moves to stack `t` with `h` copies of the counter,
+    /// so the body first comes to stack `d` and drops what is left on stack `t` at the end.
+    /// Each update in the body leaves the size of stacks and the current stack as they were,
+    /// so that most loops can run in closed form.
+    fn random_loop(rng: &mut Rng) -> String {
+        let command = |rng: &mut Rng| {
+            let t = ["혀엉", "하앙", "하앗", "흐읏", "흐윽", "형", "항", "흑"][rng.next(8)];
+            format!("{}{} ", t, ".".repeat(rng.next(3) + 3))
+        };
+        let area = ["?💕", "💕?", "!💕", "💕!", "?💕💕", "!💕💕"];
+        let d = rng.next(3) + 3;
+        let t = rng.next(3) + 3;
+        let h = rng.next(2) + 1;
+        let dots = |n: usize| ".".repeat(n);
+        let drop = |n: usize| format!("형 하앗{0} 하앙{0} ", dots(n));
+
+        let mut s = String::new();
+        for _ in 0..rng.next(4) + 1 {
+            s.push_str(&command(rng));
+        }
+        // make the stacks look like the ones after the back edge
+        s.push_str(&format!("흑{} ", dots(d)));
+        for _ in 1..h + (t != d) as usize {
+            s.push_str(&format!("흑{} ", dots(t)));
+        }
+        s.push_str(&format!("{}{}💕 ", ["형", "혀엉"][h - 1], dots(t)));
+        if t != d {
+            // counter += h * t
+            s.push_str(&format!("흑{0} 하앙{0} ", dots(d)));
+        } else if h == 1 {
+            s.push_str(&format!("하앙{} ", dots(d)));
+        } else {
+            s.push_str(&drop(d).repeat(2));
+        }
+        for _ in 0..rng.next(4) {
+            let o = (rng.next(2) + 3 + d) % 3 + 3;
+            if rng.next(2) == 0 {
+                // counter += k
+                s.push_str(&format!("형{} 하앙{} ", dots(rng.next(4)), dots(d)));
+            } else {
+                // stack o += k * counter, then come back to stack d and drop the copy of stack o
+                s.push_str(&format!(
+                    "흑{0} 형{1} 하앗{0} 하앙{0} 흑{2} {3}",
+                    dots(o),
+                    dots(rng.next(3) + 1),
+                    dots(d),
+                    drop(d)
+                ));
+            }
+        }
+        if t != d {
+            s.push_str(&format!("흑{} {}", dots(t), drop(t).repeat(h + 1)));
+            s.push_str(&format!("흑{} {}", dots(d), drop(d)));
+        }
+        s.push_str(&format!(
+            "{}{}{} ",
+            ["흑", "흐윽"][h - 1],
+            dots(t),
+            area[rng.next(area.len())]
+        ));
+        s.push_str("항. 항. 흑... 항. 흑.... 항. 흑..... 항.");
+        s
+    }
+
+    #[test]
+    fn examples_test01() {
+        let cases = [
+            ("1_to_8", ""),
+            ("a_mult_b", "2 10\n"),
+            ("a_plus_b", "2 10\n"),
+            ("hello_world", ""),
+        ];
+        for (name, input) in cases {
+            let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+            p.push(format!("examples/{0}/{0}.hyeong", name));
+            check(&p, input, name, None);
+        }
+    }
+
+    #[test]
+    fn random_test01() {
+        let mut rng = Rng(20201019);
+        for i in 0..60 {
+            let path = temp_path(&format!("random{}.hyeong", i));
+            File::create(&path)
+                .unwrap()
+                .write_all(random_program(&mut rng).as_bytes())
+                .unwrap();
+            check(&path, "ab\ncd\n", &format!("random{}", i), None);
+            fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn random_test02() {
+        let mut rng = Rng(20201020);
+        let build = temp_path("loop-build");
+        // accelerated loops, ones with the back edge moving to other stack,
+        // and ones with the back edge leaving a copy on the stack
+        let (mut accelerated, mut moved, mut copied) = (0, 0, 0);
+        for i in 0..40 {
+            let code = random_loop(&mut rng);
+            let config = optimize::Config::new(3).pre_exec_steps(0);
+            let (program, _) = optimize::PassManager::from_config(&config)
+                .run(optimize::Program::new(parse::parse(code.clone())))
+                .unwrap();
+            if let Some(i) = program
+                .code
+                .iter()
+                .position(|c| c.get_type() == code::ACCELERATE)
+            {
+                let back = &program.code[i + 1];
+                accelerated += 1;
+                if back.get_dot_count() != program.code[i].get_dot_count() {
+                    moved += 1;
+                }
+                if back.get_hangul_count() > 1 {
+                    copied += 1;
+                }
+            }
+
+            let path = temp_path(&format!("loop{}.hyeong", i));
+            File::create(&path)
+                .unwrap()
+                .write_all(code.as_bytes())
+                .unwrap();
+            check(&path, "", &format!("loop{}", i), Some(&build));
+            fs::remove_file(&path).unwrap();
+        }
+        fs::remove_dir_all(build).unwrap();
+        assert!(
+            accelerated >= 15 && moved >= 5 && copied >= 3,
+            "only {} loops are accelerated, {} moving and {} copying",
+            accelerated,
+            moved,
+            copied
+        );
+    }
+
+    #[test]
+    fn loop_test01() {
+        // back edge moving to other stack, whose closed form should leave it there
+        let build = temp_path("fixed-loop-build");
+        for (i, end) in ["하앙.", "하아아아앙."].iter().enumerate() {
+            let code = format!(
+                "형 흐으윽....\n하아앙....💕\n흑... 형 하앗... 형. 하아앙...\n흐으윽....💕?\n{}",
+                end
+            );
+            let path = temp_path(&format!("fixed-loop{}.hyeong", i));
+            fs::write(&path, code).unwrap();
+            check(&path, "", &format!("fixed-loop{}", i), Some(&build));
+            fs::remove_file(&path).unwrap();
+        }
+        fs::remove_dir_all(build).unwrap();
+    }
+}
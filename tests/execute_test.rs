@@ -63,4 +63,11 @@ mod execute_test {
             "",
         );
     }
+
+    #[test]
+    fn execute_test08() {
+        // `?` compares with the area count, so 1 is less than 2 and it does not jump back,
+        // while comparing with 1 as `partial_cmp` of 0.2.2 did jumps back once more
+        helper_function("형 형 형. 형. 혀엉.💕 항. 항..?💕", "", "\u{2}", "\u{1}");
+    }
 }
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod js_test {
+    use hyeong::core::compile::js;
+    use hyeong::core::state::UnOptState;
+    use hyeong::core::{optimize, parse};
+    use std::env;
+    use std::fs;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    /// Script that calls `run` of the module and passes the result to the process
+    const RUNNER: &str = "\
+import { run } from \"./main.mjs\";
+import { readFileSync } from \"fs\";
+const r = run(readFileSync(0, \"utf8\"));
+process.stdout.write(r.stdout);
+process.stderr.write(r.stderr);
+process.exit(r.exitCode);
+";
+
+    fn has_node() -> bool {
+        Command::new("node").arg("--version").output().is_ok()
+    }
+
+    /// Builds `code` to JavaScript and runs it with `node`, returning stdout and exit code
+    fn helper_function(name: &str, code: &str, level: u8, stdin: &str) -> (String, i32) {
+        let un_opt_code = parse::parse(code.to_string());
+        let source = if level >= 1 {
+            let (opt_state, opt_code) = optimize::optimize(un_opt_code, level).unwrap();
+            js::build_source(opt_state, &opt_code, level)
+        } else {
+            js::build_source(UnOptState::new(), &un_opt_code, level)
+        };
+
+        let p = env::temp_dir().join(format!("hyeong-js-test-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&p).unwrap();
+        fs::write(p.join("main.mjs"), source).unwrap();
+        fs::write(p.join("run.mjs"), RUNNER).unwrap();
+
+        let mut child = Command::new("node")
+            .arg(p.join("run.mjs"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        fs::remove_dir_all(p).unwrap();
+        (
+            String::from_utf8(output.stdout).unwrap(),
+            output.status.code().unwrap(),
+        )
+    }
+
+    #[test]
+    fn js_test01() {
+        if !has_node() {
+            return;
+        }
+        let cases = [
+            ("1_to_8", "", "12345678"),
+            ("a_mult_b", "3 4\n", "12"),
+            ("a_plus_b", "12 30\n", "42"),
+            ("hello_world", "", "Hello, world!\n"),
+        ];
+        for (name, stdin, out) in cases {
+            let code = fs::read_to_string(format!("examples/{}/{}.hyeong", name, name)).unwrap();
+            for level in 0..=3 {
+                assert_eq!(
+                    (String::from(out), 0),
+                    helper_function(&format!("{}{}", name, level), &code, level, stdin),
+                    "{} at level {}",
+                    name,
+                    level
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn js_test02() {
+        if !has_node() {
+            return;
+        }
+        // exit by popping stack 2 in area, before printing
+        for level in 0..=3 {
+            assert_eq!(
+                (String::new(), 1),
+                helper_function(
+                    &format!("exit{}", level),
+                    "형.. 흑.. 형... 하앙..? 형",
+                    level,
+                    ""
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn js_test03() {
+        if !has_node() {
+            return;
+        }
+        // big number, fraction and NaN
+        let cases = [
+            (
+                "혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ \
+                 혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ \
+                 하아아아아아아아아아아앗... 흣... 항.",
+                "281474976710656",
+            ),
+            ("형... 흡... 흣... 항.", "1/3"),
+            ("형 흡... 항. 항.", "너무 커엇...너무 커엇..."),
+        ];
+        for (i, (code, out)) in cases.iter().enumerate() {
+            for level in [0, 3] {
+                assert_eq!(
+                    (String::from(*out), 0),
+                    helper_function(&format!("num{}{}", i, level), code, level, "")
+                );
+            }
+        }
+    }
+}
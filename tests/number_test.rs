@@ -1,14 +1,17 @@
 #[cfg(test)]
 mod number_test {
-    use hyeong::number::num::Num;
+    use hyeong::number::big_number::{BigNum, Error};
+    use hyeong::number::num::{Format, Num};
     use std::cmp::Ordering;
+    use std::collections::HashSet;
+    use std::convert::TryFrom;
 
     #[test]
     fn compare_test01() {
         let a = Num::nan();
         let b = Num::one();
 
-        assert!(matches!(a.partial_cmp(&b), Option::None));
+        assert!(matches!(a.partial_cmp(&b), Option::Some(Ordering::Greater)));
     }
 
     #[test]
@@ -27,6 +30,39 @@ mod number_test {
         assert!(matches!(a.partial_cmp(&b), Option::Some(Ordering::Less)));
     }
 
+    #[test]
+    fn compare_test04() {
+        let a = Num::new(5, 2);
+        let b = Num::from_num(3);
+
+        assert!(matches!(a.cmp(&b), Ordering::Less));
+        assert!(matches!(
+            Num::new(-1, 2).cmp(&Num::new(-1, 3)),
+            Ordering::Less
+        ));
+    }
+
+    #[test]
+    fn compare_test05() {
+        let a = Num::nan();
+        let b = -&Num::nan();
+
+        assert_eq!(a, b);
+        assert!(matches!(a.cmp(&b), Ordering::Equal));
+        assert_eq!(Some(&a), [Num::one(), a.clone(), Num::zero()].iter().max());
+    }
+
+    #[test]
+    fn hash_test01() {
+        let mut s = HashSet::new();
+        s.insert(Num::new(2, 4));
+        s.insert(Num::nan());
+
+        assert!(s.contains(&Num::new(1, 2)));
+        assert!(s.contains(&-&Num::nan()));
+        assert!(!s.contains(&Num::new(-1, 2)));
+    }
+
     #[test]
     fn add_test01() {
         let a = Num::nan();
@@ -50,4 +86,301 @@ mod number_test {
 
         assert_eq!(Num::new(-3, 10), a);
     }
+
+    #[test]
+    fn sub_test01() {
+        let a = Num::new(1, 2);
+        let b = Num::new(1, 3);
+
+        assert_eq!(Num::new(1, 6), &a - &b);
+        assert_eq!(Num::new(-1, 6), b - a);
+    }
+
+    #[test]
+    fn sub_test02() {
+        let mut a = Num::one();
+        a -= Num::nan();
+
+        assert_eq!(Num::nan(), a);
+    }
+
+    #[test]
+    fn div_test01() {
+        let a = Num::new(1, 2);
+        let b = Num::new(-1, 3);
+
+        assert_eq!(Num::new(-3, 2), &a / &b);
+        assert_eq!(Num::nan(), a / Num::zero());
+    }
+
+    #[test]
+    fn div_test02() {
+        let mut a = Num::from_num(7);
+        a /= &Num::from_num(14);
+
+        assert_eq!(Num::new(1, 2), a);
+    }
+
+    #[test]
+    fn from_str_test01() {
+        assert_eq!(Num::new(-5, 2), "-10/4".parse::<Num>().unwrap());
+        assert_eq!(Num::new(-5, 2), "10/-4".parse::<Num>().unwrap());
+        assert_eq!(Num::nan(), "너무 커엇...".parse::<Num>().unwrap());
+    }
+
+    #[test]
+    fn from_str_test02() {
+        assert!(matches!(
+            "1/2/3".parse::<Num>(),
+            Result::Err(Error::ParseError)
+        ));
+        assert!(matches!("x".parse::<Num>(), Result::Err(Error::ParseError)));
+    }
+
+    #[test]
+    fn sum_test01() {
+        let v = vec![Num::new(1, 2), Num::new(1, 3), Num::new(1, 6)];
+
+        assert_eq!(Num::one(), v.iter().sum());
+        assert_eq!(Num::new(1, 36), v.into_iter().product());
+    }
+
+    #[test]
+    fn convert_test01() {
+        assert_eq!(Num::from_num(-3), Num::from(-3i8));
+        assert_eq!(Num::from(BigNum::from(u128::MAX)), Num::from(u128::MAX));
+        assert_eq!(Num::from_num(54805), Num::from('형'));
+    }
+
+    #[test]
+    fn convert_test02() {
+        assert_eq!(-3, i64::try_from(Num::from_num(-3)).unwrap());
+        assert_eq!('형', char::try_from(&Num::from_num(54805)).unwrap());
+        assert!(matches!(
+            i64::try_from(Num::new(1, 2)),
+            Result::Err(Error::RangeError)
+        ));
+        assert!(matches!(
+            u8::try_from(Num::nan()),
+            Result::Err(Error::RangeError)
+        ));
+        assert!(matches!(
+            u8::try_from(Num::from_num(256)),
+            Result::Err(Error::RangeError)
+        ));
+    }
+
+    #[test]
+    fn format_test01() {
+        let a = Num::new(-10, 3);
+
+        assert_eq!("-10/3", a.to_string_format(Format::Fraction));
+        assert_eq!("-3 1/3", a.to_string_format(Format::Mixed));
+        assert_eq!("-3.33", a.to_string_format(Format::Decimal(2)));
+        assert_eq!("-3", a.to_string_format(Format::Decimal(0)));
+        assert_eq!("-3.(3)", a.to_string_format(Format::Repeating(10)));
+        assert_eq!("-3.333e0", a.to_string_format(Format::Scientific(3)));
+        assert_eq!("12", Num::from_num(12).to_string_format(Format::Mixed));
+        assert_eq!("0", Num::new(-1, 1000).to_string_format(Format::Decimal(2)));
+    }
+
+    #[test]
+    fn format_test02() {
+        assert_eq!(
+            "0.1(6)",
+            Num::new(1, 6).to_string_format(Format::Repeating(10))
+        );
+        assert_eq!(
+            "0.08(3)",
+            Num::new(1, 12).to_string_format(Format::Repeating(10))
+        );
+        assert_eq!(
+            "0.(09)",
+            Num::new(1, 11).to_string_format(Format::Repeating(10))
+        );
+        assert_eq!(
+            "0.125",
+            Num::new(1, 8).to_string_format(Format::Repeating(10))
+        );
+        assert_eq!(
+            "0.05...",
+            Num::new(1, 17).to_string_format(Format::Repeating(2))
+        );
+        assert_eq!(
+            "0.(0588235294117647)",
+            Num::new(1, 17).to_string_format(Format::Repeating(16))
+        );
+        assert_eq!("5", Num::from_num(5).to_string_format(Format::Repeating(0)));
+    }
+
+    #[test]
+    fn format_test03() {
+        assert_eq!(
+            "1e10",
+            Num::from_num(10_000_000_000).to_string_format(Format::Scientific(5))
+        );
+        assert_eq!(
+            "9.99e2",
+            Num::from_num(999).to_string_format(Format::Scientific(5))
+        );
+        assert_eq!(
+            "1e3",
+            Num::from_num(1000).to_string_format(Format::Scientific(5))
+        );
+        assert_eq!(
+            "1e-3",
+            Num::new(1, 1000).to_string_format(Format::Scientific(5))
+        );
+        assert_eq!(
+            "9.9e-4",
+            Num::new(99, 100000).to_string_format(Format::Scientific(5))
+        );
+        assert_eq!(
+            "-1.4285e-1",
+            Num::new(-1, 7).to_string_format(Format::Scientific(4))
+        );
+        assert_eq!("0", Num::zero().to_string_format(Format::Scientific(5)));
+        assert_eq!("너무 커엇...", Num::nan().to_string_format(Format::Mixed));
+    }
+
+    #[test]
+    fn from_str_test03() {
+        assert_eq!(Num::new(5, 4), "1.25".parse().unwrap());
+        assert_eq!(Num::new(1, 2), ".5".parse().unwrap());
+        assert_eq!(Num::from_num(3), "3.".parse().unwrap());
+        assert_eq!(Num::new(-1, 7), "-0.(142857)".parse().unwrap());
+        assert_eq!(Num::new(1, 6), "0.1(6)".parse().unwrap());
+        assert_eq!(Num::from_num(1), "0.(9)".parse().unwrap());
+        assert_eq!(Num::from_num(10_000_000_000), "1e10".parse().unwrap());
+        assert_eq!(Num::new(1, 800), "1.25E-3".parse().unwrap());
+        assert_eq!(Num::new(7, 3), "2 1/3".parse().unwrap());
+        assert_eq!(Num::new(-7, 3), "-2 1/3".parse().unwrap());
+    }
+
+    #[test]
+    fn from_str_test04() {
+        for s in &[
+            "1.2.3", "0.(1", "0.()", "0.(1)2", "1e", "e5", ".", "1.x", "2 -1/3", "2 1", "1/2e3",
+        ] {
+            assert!(
+                matches!(s.parse::<Num>(), Result::Err(Error::ParseError)),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn from_str_test05() {
+        for s in &[
+            "",
+            "/",
+            "1/",
+            "/2",
+            "-/-",
+            "3 1/-3",
+            "3 -1/3",
+            "1e100001",
+            "1e-100001",
+            "1e10000000",
+            "1e2000000000",
+        ] {
+            assert!(
+                matches!(s.parse::<Num>(), Result::Err(Error::ParseError)),
+                "{}",
+                s
+            );
+        }
+        assert_eq!(Num::new(-1, 3), "1/-3".parse().unwrap());
+        assert_eq!(
+            Num::from_big_num(BigNum::one(), BigNum::from(10).pow(100_000)),
+            "1e-100000".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn format_parse_test01() {
+        let v = vec![
+            Num::new(-10, 3),
+            Num::new(1, 6),
+            Num::new(22, 7),
+            Num::new(123456, 1000),
+        ];
+        for a in v {
+            for f in &[Format::Fraction, Format::Mixed, Format::Repeating(100)] {
+                assert_eq!(a, a.to_string_format(*f).parse().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn affine_test01() {
+        use hyeong::number::affine::Affine;
+
+        // (x, y) -> (2x - y + 1, x / 3)
+        let a = Affine::new(vec![
+            vec![Num::from_num(2), Num::from_num(-1), Num::one()],
+            vec![Num::new(1, 3), Num::zero(), Num::zero()],
+        ]);
+        let mut x = vec![Num::from_num(5), Num::from_num(-7)];
+        for n in 0..10 {
+            assert_eq!(x, a.pow(n).apply(&[Num::from_num(5), Num::from_num(-7)]));
+            x = a.apply(&x);
+        }
+    }
+
+    #[test]
+    fn affine_test02() {
+        use hyeong::number::affine::{Affine, AffineLoop};
+
+        for &equal in &[false, true] {
+            for &repeat_if in &[false, true] {
+                for step in -3..=3 {
+                    for start in -10..=10 {
+                        // (x, y) -> (x + step, y + x)
+                        let l = match AffineLoop::new(
+                            vec![(3, 2)],
+                            Affine::new(vec![
+                                vec![Num::one(), Num::zero(), Num::from_num(step)],
+                                vec![Num::one(), Num::one(), Num::zero()],
+                            ]),
+                            vec![Num::one(), Num::zero(), Num::zero()],
+                            Num::from_num(2),
+                            equal,
+                            repeat_if,
+                            vec![vec![
+                                vec![Num::zero(), Num::one(), Num::zero()],
+                                vec![Num::one(), Num::zero(), Num::from_num(-1)],
+                            ]],
+                        ) {
+                            Some(l) => l,
+                            None => {
+                                assert_eq!(0, step);
+                                continue;
+                            }
+                        };
+
+                        let (mut x, mut y, mut k) = (start, 0, 0);
+                        while k < 100 && ((equal && x == 2) || (!equal && x < 2)) == repeat_if {
+                            y += x;
+                            x += step;
+                            k += 1;
+                        }
+                        let res = l.run(&[Num::from_num(start), Num::zero()]);
+                        if k == 100 {
+                            assert!(res.is_none());
+                        } else {
+                            assert_eq!(
+                                Some((
+                                    Num::from_num(k),
+                                    vec![vec![Num::from_num(y), Num::from_num(x - 1)]]
+                                )),
+                                res
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
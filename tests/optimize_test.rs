@@ -1,32 +1,37 @@
-﻿#[cfg(test)]
+#[cfg(test)]
 mod optimize_test {
-    use hyeong::core::state::State;
-    use hyeong::core::{execute, optimize, parse};
+    use hyeong::core::state::{Output, State};
+    use hyeong::core::{compile, execute, optimize, parse};
     use hyeong::util::io;
-    use std::io::Write;
 
     fn helper_function(code: &str, stdin: &str, stdout: &str, stderr: &str, level: u8) {
+        helper_function_config(code, stdin, stdout, stderr, optimize::Config::new(level));
+    }
+
+    fn helper_function_config(
+        code: &str,
+        stdin: &str,
+        stdout: &str,
+        stderr: &str,
+        config: optimize::Config,
+    ) {
         let un_opt_code = parse::parse(code.to_string());
         let mut ipt = io::CustomReader::new(stdin.to_string());
         let mut out = io::CustomWriter::new(|_| Result::Ok(()));
         let mut err = io::CustomWriter::new(|_| Result::Ok(()));
         let mut out_str = String::from("");
         let mut err_str = String::from("");
-        let (mut opt_state, opt_code) = optimize::optimize(un_opt_code, level).unwrap();
-        if !opt_state.get_stack(1).is_empty() {
-            for num in opt_state.get_stack(1).iter() {
-                out_str.push_str(&*format!("{}", num.floor().to_int() as u8 as char));
-            }
-            out.flush().unwrap();
-            opt_state.get_stack(1).clear();
-        }
-        if !opt_state.get_stack(2).is_empty() {
-            for num in opt_state.get_stack(2).iter() {
-                err_str.push_str(&*format!("{}", num.floor().to_int() as u8 as char));
+        let (p, _) = optimize::PassManager::from_config(&config)
+            .run(optimize::Program::new(un_opt_code))
+            .unwrap();
+        let (mut opt_state, opt_code) = (p.state, p.code);
+        for output in opt_state.get_output() {
+            match output {
+                Output::Stdout(s) => out_str.push_str(s),
+                Output::Stderr(s) => err_str.push_str(s),
             }
-            err.flush().unwrap();
-            opt_state.get_stack(2).clear();
         }
+        opt_state.clear_output();
         for c in opt_code {
             opt_state = execute::execute(&mut ipt, &mut out, &mut err, opt_state, &c).unwrap();
         }
@@ -119,4 +124,375 @@ mod optimize_test {
     fn optimize_test13() {
         helper_function("형. 흣... 흑 흑.", "", "1", "", 2);
     }
+
+    #[test]
+    fn pass_manager_test01() {
+        assert!(optimize::PassManager::from_level(0).pass_names().is_empty());
+        assert_eq!(
+            vec!["renumber-stacks"],
+            optimize::PassManager::from_level(1).pass_names()
+        );
+        assert_eq!(
+            vec!["renumber-stacks", "pre-execute"],
+            optimize::PassManager::from_level(2).pass_names()
+        );
+    }
+
+    #[test]
+    fn pass_manager_test02() {
+        let code = parse::parse(String::from("형.. 흑....... 형 흣.. 항. 흑 항"));
+        let pm = optimize::PassManager::from_level(2).dump_after(String::from("pre-execute"));
+        let (p, report) = pm.run(optimize::Program::new(code)).unwrap();
+
+        assert_eq!(1, p.code.len());
+        assert_eq!(2, report.stats.len());
+        assert_eq!(0, report.stats[0].commands_removed);
+        assert_eq!(2, report.stats[0].stacks_removed);
+        assert_eq!(6, report.stats[1].commands_removed);
+        assert_eq!(1, report.dumps.len());
+        assert!(report.dumps[0].1.contains("executed: 6 commands"));
+        assert!(report.dumps[0].1.ends_with("0 | 항_1_0 _\n"));
+    }
+
+    #[test]
+    fn pass_manager_test03() {
+        let code = parse::parse(String::from("형."));
+        let pm = optimize::PassManager::from_level(1).dump_after(String::from("pre-execute"));
+
+        assert!(pm.run(optimize::Program::new(code.clone())).is_err());
+
+        let pm = optimize::PassManager::new()
+            .add_pass(optimize::pre_execute::PreExecute::default())
+            .add_pass(optimize::renumber::RenumberStacks);
+
+        assert!(pm.run(optimize::Program::new(code)).is_err());
+    }
+
+    #[test]
+    fn pre_execute_test01() {
+        let code = "형 흣........💕 흣.... 형. 하앙... 흣. 흑... 흐읏....!💕";
+        for steps in [0, 1, 5, 20, 50, 100] {
+            helper_function_config(
+                code,
+                "",
+                "12345678",
+                "",
+                optimize::Config::new(2).pre_exec_steps(steps),
+            );
+        }
+    }
+
+    #[test]
+    fn pre_execute_test02() {
+        let code = parse::parse(String::from(
+            "형 흣........💕 흣.... 형. 하앙... 흣. 흑... 흐읏....!💕",
+        ));
+        let mut p = optimize::Program::new(code.clone());
+        let stop = optimize::pre_execute::pre_execute(&mut p, 20)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(optimize::pre_execute::StopReason::StepBudget, stop.reason);
+        assert_eq!(7, stop.resume);
+
+        let mut p = optimize::Program::new(code);
+        assert!(optimize::pre_execute::pre_execute(&mut p, 1000)
+            .unwrap()
+            .is_none());
+        assert!(p.code.is_empty());
+    }
+
+    #[test]
+    fn pre_execute_test03() {
+        use optimize::pre_execute::{pre_execute, StopReason};
+
+        let cases = [
+            ("형. 흑 형", None),
+            ("형. 흑 항", Some((StopReason::Input, 2))),
+            ("형. 흑. 흑", Some((StopReason::Exit, 2))),
+            ("형. 흑 형♥?", Some((StopReason::UnknownJump, 2))),
+            ("형. 흑.. 형 흑...", Some((StopReason::Exit, 3))),
+        ];
+        for (code, expected) in cases {
+            let mut p = optimize::Program::new(parse::parse(String::from(code)));
+            let stop = pre_execute(&mut p, 100).unwrap();
+            assert_eq!(expected, stop.map(|x| (x.reason, x.at)), "{}", code);
+        }
+    }
+
+    #[test]
+    fn output_order_test01() {
+        let code = parse::parse(String::from("형... 항. 형.. 흣.. 형... 항. 형... 항."));
+        let (state, code) = optimize::optimize(code, 2).unwrap();
+
+        assert_eq!(
+            &[
+                Output::Stdout(String::from("\u{3}")),
+                Output::Stderr(String::from("2")),
+                Output::Stdout(String::from("\u{3}\u{3}")),
+            ],
+            state.get_output()
+        );
+
+        let source = compile::build_source(state, &code, 2);
+        let a = source.find("print!(\"\\u{3}\");").unwrap();
+        let b = source.find("eprint!(\"2\");").unwrap();
+        let c = source.find("print!(\"\\u{3}\\u{3}\");").unwrap();
+        assert!(a < b && b < c);
+    }
+
+    #[test]
+    fn dead_code_test01() {
+        use hyeong::core::optimize::{dead_code::DeadCode, Pass, PassStats, Program};
+
+        let cases = [
+            // exit by popping stack 1
+            ("흑. 흑 형 형", 2),
+            // exit happens after jumping back
+            ("형 흑.... 형♥ 흑. 형♥ 흑 형", 5),
+            // always jumps back to the first heart
+            ("형♥ 형. 흑 형♥ 형 형", 4),
+            // the first heart may not set the point
+            ("형 흑 항?♥ 형♥ 형 형", 6),
+            ("형 항 형", 3),
+        ];
+        for (code, len) in cases {
+            let mut p = Program::new(parse::parse(String::from(code)));
+            DeadCode.run(&mut p, &mut PassStats::default()).unwrap();
+            assert_eq!(len, p.code.len(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn dead_code_test02() {
+        use hyeong::core::area::Area;
+        use hyeong::core::code::Code;
+        use hyeong::core::optimize::{dead_code::DeadCode, Pass, PassStats, Program};
+
+        let mut p = Program::new(parse::parse(String::from("형♥ 형.❤ 형.♥?♡ 형♥ 형")));
+        let mut stats = PassStats::default();
+        DeadCode.run(&mut p, &mut stats).unwrap();
+
+        assert_eq!(4, p.code.len());
+        assert!(matches!(p.code[0].get_area(), Area::Val { .. }));
+        assert!(matches!(p.code[1].get_area(), Area::Nil));
+        assert!(matches!(p.code[2].get_area(), Area::Val { .. }));
+        assert!(matches!(p.code[3].get_area(), Area::Val { .. }));
+        assert_eq!(vec!["2 areas simplified"], stats.notes);
+    }
+
+    #[test]
+    fn fold_test01() {
+        use hyeong::core::optimize::{fold::ConstantFold, Pass, PassStats, Program};
+
+        let cases = [
+            ("형.. 형... 하앗. 형", 2),
+            // current stack is known after the first command
+            ("흑.... 형 형 항", 3),
+            // moves through 흑 collapse
+            ("형.. 흑.... 항.... 항..", 3),
+            // popped value is not known
+            ("항 형", 2),
+            // NaN is not folded
+            ("형 흡", 2),
+            // commands with area are kept
+            ("형. 형.♥ 항", 3),
+        ];
+        for (code, len) in cases {
+            let mut p = Program::new(parse::parse(String::from(code)));
+            ConstantFold.run(&mut p, &mut PassStats::default()).unwrap();
+            assert_eq!(len, p.code.len(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn fold_test02() {
+        use hyeong::core::code::{self, Code};
+        use hyeong::core::optimize::{fold::ConstantFold, Pass, PassStats, Program};
+
+        let mut p = Program::new(parse::parse(String::from("형.. 흑.... 항.... 항..")));
+        let mut stats = PassStats::default();
+        ConstantFold.run(&mut p, &mut stats).unwrap();
+
+        assert_eq!(code::PUSH_CONST, p.code[0].get_type());
+        assert_eq!(3, p.code[0].get_dot_count());
+        assert_eq!(code::PUSH_CONST, p.code[1].get_type());
+        assert_eq!(2, p.code[1].get_dot_count());
+        assert_eq!(code::MOVE_TO, p.code[2].get_type());
+        assert_eq!(4, p.code[2].get_dot_count());
+        assert_eq!(vec!["1 blocks folded"], stats.notes);
+    }
+
+    #[test]
+    fn fold_test03() {
+        let cases = [
+            ("혀어어어어어어엉......핫.. 혀어어어어어어어엉........ 핫. 혀어어어어어어어엉......... 핫..", "", "H", "0Q"),
+            ("형 흣........💕 흣.... 형. 하앙... 흣. 흑... 흐읏....!💕", "", "12345678", ""),
+            ("형. 형.. 형. 흑...💘 항.... 하앙... 항...♡ 흑...💘 ! 흣...흑.", "", "4", ""),
+            ("형. 흣... 흑 항.", "", "1", ""),
+            ("형. 흣... 흑 흡.", "", "1", ""),
+            ("형.. 형... 하앗. 형 흑... 흣 흑... 하아앗...", "", "\u{6}", ""),
+        ];
+        for (code, stdin, stdout, stderr) in cases {
+            let config = optimize::Config::new(3).pre_exec_steps(0);
+            helper_function_config(code, stdin, stdout, stderr, config);
+        }
+    }
+
+    #[test]
+    fn accelerate_test01() {
+        use hyeong::core::code::{self, Code};
+        use hyeong::core::optimize::{loops::AccelerateLoops, Pass, PassStats, Program};
+
+        let cases = [
+            ("형 흑.... 형....💕 하앙.... 흑....!💕", true),
+            // jumps back on the left
+            ("형 흑.... 형....💕 하앙.... 흑....💕?", true),
+            // product of two values is not affine
+            ("형 흑.... 형....💕 흑..... 하앗.... 흑....!💕", false),
+            // reads input
+            ("형 흑.... 형....💕 항 하앙.... 흑....!💕", false),
+            // another area in the loop
+            ("형 흑.... 형....💕 하앙....♥ 흑....!💕", false),
+            // counter does not change
+            ("형 흑.... 형.💕 흑....!💕", false),
+        ];
+        for (c, accelerated) in cases {
+            let mut p = Program::new(parse::parse(String::from(c)));
+            let mut stats = PassStats::default();
+            AccelerateLoops.run(&mut p, &mut stats).unwrap();
+            assert_eq!(
+                accelerated,
+                p.code.iter().any(|c| c.get_type() == code::ACCELERATE),
+                "{}",
+                c
+            );
+            assert_eq!(accelerated, !stats.notes.is_empty(), "{}", c);
+        }
+    }
+
+    #[test]
+    fn accelerate_test02() {
+        let cases = [
+            ("혀어어어엉.................... 흣.... 흑.... 형....💕 하앙.... 흑....!💕 흣.", "", "4", ""),
+            ("혀어어어엉.................... 흣.... 흑.... 형....💕 하앙.... 흑....💕? 흣.", "", "4", ""),
+            // counter on stack 4, and two values on stack 5 updated from it
+            ("형..... 흑..... 형..... 흑... 혀어어어엉........ 흣.... 흑.... 형....💕 하앙.... 흑..... 하아앙..... 흑.... 항..... 흑....!💕 흑..... 항. 항..", "", "\u{4}", "55308"),
+            // the loop is never entered
+            ("형 흑.... 형....💕 하앙.... 흑....!💕 흣.", "", "4", ""),
+        ];
+        for (code, stdin, stdout, stderr) in cases {
+            helper_function(code, stdin, stdout, stderr, 0);
+            let config = optimize::Config::new(3).pre_exec_steps(0);
+            helper_function_config(code, stdin, stdout, stderr, config);
+        }
+    }
+
+    #[test]
+    fn accelerate_test03() {
+        // counts from -10^30 by 3, which only finishes in closed form
+        let code = format!(
+            "{}하{}앗... 흣... 형...💕 하앙... 흑...💕? 흣..",
+            "형.......... ".repeat(30),
+            "아".repeat(28)
+        );
+        for steps in [0, 1000] {
+            let config = optimize::Config::new(3).pre_exec_steps(steps);
+            helper_function_config(&code, "", "", "5", config);
+        }
+    }
+
+    #[test]
+    fn merge_test01() {
+        use hyeong::core::optimize::{
+            liveness::MergeStacks, renumber::RenumberStacks, Pass, PassStats, Program,
+        };
+
+        let cases = [
+            // stack 4 is only written
+            ("형. 항.... 형.. 항.", 5, 4, vec!["1 stacks dropped"]),
+            // stack 5 is used after stack 4 is empty
+            (
+                "형. 흑.... 항.. 흑... 항.. 흑..... 항.. 흑... 항..",
+                7,
+                5,
+                vec!["1 stacks dropped", "1 stacks shared"],
+            ),
+            // both of them have values at the same time
+            (
+                "형. 흑.... 항.. 흑..... 항.. 흑.... 항..",
+                7,
+                6,
+                vec!["1 stacks dropped"],
+            ),
+        ];
+        for (code, before, after, notes) in cases {
+            let mut p = Program::new(parse::parse(String::from(code)));
+            RenumberStacks
+                .run(&mut p, &mut PassStats::default())
+                .unwrap();
+            assert_eq!(before, p.state.stack_size(), "{}", code);
+
+            let mut stats = PassStats::default();
+            MergeStacks.run(&mut p, &mut stats).unwrap();
+            assert_eq!(after, p.state.stack_size(), "{}", code);
+            assert_eq!(notes, stats.notes, "{}", code);
+        }
+    }
+
+    #[test]
+    fn merge_test02() {
+        let cases = [
+            (
+                "형. 흑.... 항.. 흑... 항.. 흑..... 항.. 흑... 항..",
+                "",
+                "",
+                "\u{1}너무 커엇...\u{1}너무 커엇...",
+            ),
+            (
+                "형. 흑.... 항.. 흑..... 항.. 흑.... 항..",
+                "",
+                "",
+                "\u{1}너무 커엇...너무 커엇...",
+            ),
+            (
+                "형.. 흑.... 항.... 흑..... 항..... 흑.... 하앙. 흑... 하앙.",
+                "",
+                "\u{4}너무 커엇...",
+                "",
+            ),
+        ];
+        for (code, stdin, stdout, stderr) in cases {
+            helper_function(code, stdin, stdout, stderr, 0);
+            let config = optimize::Config::new(3).pre_exec_steps(0);
+            helper_function_config(code, stdin, stdout, stderr, config);
+        }
+    }
+
+    #[test]
+    fn explain_test01() {
+        use hyeong::core::optimize::{Config, PassManager, Program};
+
+        let code = "형. 흑.... 항.. 흑... 항.. 흑..... 항.. 흑... 항..";
+        let (p, _) = PassManager::from_config(&Config::new(3).pre_exec_steps(0))
+            .run(Program::new(parse::parse(String::from(code))))
+            .unwrap();
+        assert_eq!(
+            "stack map:\n  0 -> 0\n  1 -> 1\n  2 -> 2\n  3 -> 3\n  4 -> 4\n  5 -> 4\n\
+             pre-executed: 0 commands\npoints: none\n",
+            p.explain()
+        );
+
+        let (p, _) = PassManager::from_level(3)
+            .run(Program::new(parse::parse(String::from(code))))
+            .unwrap();
+        // everything runs while optimizing, so no stack is left
+        assert_eq!(
+            "stack map:\n  0 -> 0\n  1 -> 1\n  2 -> 2\n  3 -> dropped\n  4 -> dropped\n  5 -> dropped\n\
+             pre-executed: 9 commands\n\
+             output: stderr \"\\u{1}너무 커엇...\\u{1}너무 커엇...\"\n\
+             points: none\n",
+            p.explain()
+        );
+    }
 }
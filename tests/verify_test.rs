@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod verify_test {
+    use hyeong::core::code::{Code, OptCode};
+    use hyeong::core::optimize::{Config, Pass, PassManager, PassStats, Program};
+    use hyeong::core::parse;
+    use hyeong::core::verify::{self, Outcome, Verdict};
+    use hyeong::util::error::Error;
+    use std::fs;
+
+    fn helper_function(code: &str, stdin: &str, pm: &PassManager) -> Verdict {
+        verify::verify(
+            &parse::parse(code.to_string()),
+            pm,
+            stdin,
+            verify::DEFAULT_STEPS,
+        )
+        .unwrap()
+    }
+
+    /// Pass that pushes one more on the first `형`
+    struct Broken;
+
+    impl Pass for Broken {
+        fn name(&self) -> &'static str {
+            "broken"
+        }
+
+        fn run(&self, program: &mut Program, _stats: &mut PassStats) -> Result<(), Error> {
+            if let Some(c) = program.code.iter_mut().find(|c| c.get_type() == 0) {
+                *c = OptCode::new(
+                    0,
+                    c.get_hangul_count() + 1,
+                    c.get_dot_count(),
+                    c.get_area_count(),
+                    c.get_area().clone(),
+                );
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn verify_test01() {
+        let cases = [
+            ("1_to_8", ""),
+            ("a_mult_b", "3 4\n"),
+            ("a_plus_b", "12 30\n"),
+            ("hello_world", ""),
+        ];
+        for (name, stdin) in cases {
+            let code = fs::read_to_string(format!("examples/{}/{}.hyeong", name, name)).unwrap();
+            for level in 1..=3 {
+                for steps in [0, 10000] {
+                    let pm = PassManager::from_config(&Config::new(level).pre_exec_steps(steps));
+                    let res = helper_function(&code, stdin, &pm);
+                    assert!(
+                        matches!(res, Verdict::Same(Outcome::Exit(0))),
+                        "{} at level {}: {:?}",
+                        name,
+                        level,
+                        res
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn verify_test02() {
+        // exit by popping stack 2 in area, after printing
+        let code = "형.. 흑.. 형... 하앙..? 형";
+        for level in 1..=3 {
+            let res = helper_function(code, "", &PassManager::from_level(level));
+            assert!(matches!(res, Verdict::Same(Outcome::Exit(1))), "{:?}", res);
+        }
+    }
+
+    #[test]
+    fn verify_test03() {
+        let code = "형.. 형... 형.... 흑..... 흑... 항. 흑 흑.";
+        let pm = PassManager::from_level(1).add_pass(Broken);
+        match helper_function(code, "", &pm) {
+            Verdict::Diverged(d) => {
+                assert_eq!(
+                    "stack 3 differs: [2] at level 0, [4] optimized as stack 3",
+                    d.reason
+                );
+                assert_eq!(vec!["0 | 형.. at 1:0"], d.un_opt);
+                assert_eq!(vec!["0 | 형_2_2 _"], d.opt);
+            }
+            res => panic!("{:?}", res),
+        }
+    }
+
+    #[test]
+    fn verify_test04() {
+        // difference in output is found even when the command is executed while optimizing
+        let code = "형.. 항. 흑 흑.";
+        let pm = PassManager::new()
+            .add_pass(Broken)
+            .add_pass(hyeong::core::optimize::pre_execute::PreExecute::new(10000));
+        match helper_function(code, "", &pm) {
+            Verdict::Diverged(d) => assert_eq!(
+                "stdout differs: \"\\u{2}\" at level 0, \"\\u{4}\" optimized",
+                d.reason
+            ),
+            res => panic!("{:?}", res),
+        }
+    }
+
+    #[test]
+    fn verify_test05() {
+        let code = "형 흑...♥ 형 흑...♥";
+        let res = verify::verify(
+            &parse::parse(code.to_string()),
+            &PassManager::from_level(1),
+            "",
+            100,
+        )
+        .unwrap();
+        assert!(matches!(res, Verdict::Unknown));
+    }
+}
@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod wat_test {
+    use hyeong::core::compile::wat;
+    use hyeong::core::state::UnOptState;
+    use hyeong::core::{optimize, parse};
+    use std::env;
+    use std::fs;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    /// Script that instantiates the module with host functions and passes the result to the process
+    const RUNNER: &str = "\
+import { readFileSync } from \"fs\";
+const input = Array.from(readFileSync(0, \"utf8\"));
+let pos = 0;
+const out = [\"\", \"\", \"\"];
+class Exit {
+    constructor(code) {
+        this.code = code;
+    }
+}
+const { instance } = await WebAssembly.instantiate(readFileSync(new URL(\"./main.wasm\", import.meta.url)), {
+    hyeong: {
+        read: () => (pos < input.length ? input[pos++].codePointAt(0) : -1),
+        write: (s, c) => {
+            out[s] += String.fromCodePoint(c);
+        },
+        exit: (c) => {
+            throw new Exit(c);
+        },
+    },
+});
+let code = 0;
+try {
+    instance.exports.main();
+} catch (e) {
+    if (!(e instanceof Exit)) throw e;
+    code = e.code;
+}
+process.stdout.write(out[1]);
+process.stderr.write(out[2]);
+process.exit(code);
+";
+
+    fn has_command(name: &str) -> bool {
+        Command::new(name).arg("--version").output().is_ok()
+    }
+
+    /// Builds `code` to WebAssembly text and assembles it, checking that the module is valid
+    fn build(name: &str, code: &str, level: u8) -> std::path::PathBuf {
+        let un_opt_code = parse::parse(code.to_string());
+        let source = if level >= 1 {
+            let (opt_state, opt_code) = optimize::optimize(un_opt_code, level).unwrap();
+            wat::build_source(opt_state, &opt_code, level)
+        } else {
+            wat::build_source(UnOptState::new(), &un_opt_code, level)
+        };
+
+        let p = env::temp_dir().join(format!("hyeong-wat-test-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&p).unwrap();
+        fs::write(p.join("main.wat"), &source).unwrap();
+
+        let bytes = ::wat::parse_str(&source).unwrap();
+        wasmparser::Validator::new().validate_all(&bytes).unwrap();
+        fs::write(p.join("main.wasm"), bytes).unwrap();
+
+        if has_command("wat2wasm") {
+            let status = Command::new("wat2wasm")
+                .arg(p.join("main.wat"))
+                .arg("-o")
+                .arg(p.join("wat2wasm.wasm"))
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+        p
+    }
+
+    /// Builds `code` and runs it with `node`, returning stdout and exit code
+    fn helper_function(name: &str, code: &str, level: u8, stdin: &str) -> (String, i32) {
+        let p = build(name, code, level);
+        fs::write(p.join("run.mjs"), RUNNER).unwrap();
+
+        let mut child = Command::new("node")
+            .arg(p.join("run.mjs"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        fs::remove_dir_all(p).unwrap();
+        (
+            String::from_utf8(output.stdout).unwrap(),
+            output.status.code().unwrap(),
+        )
+    }
+
+    #[test]
+    fn wat_test01() {
+        // every example is a valid module
+        for entry in fs::read_dir("examples").unwrap() {
+            let entry = entry.unwrap().path();
+            let name = entry.file_name().unwrap().to_str().unwrap().to_string();
+            let code = fs::read_to_string(entry.join(format!("{}.hyeong", name))).unwrap();
+            for level in 0..=3 {
+                let p = build(&format!("valid-{}{}", name, level), &code, level);
+                fs::remove_dir_all(p).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn wat_test02() {
+        if !has_command("node") {
+            return;
+        }
+        let cases = [
+            ("1_to_8", "", "12345678"),
+            ("a_mult_b", "3 4\n", "12"),
+            ("a_plus_b", "12 30\n", "42"),
+            ("hello_world", "", "Hello, world!\n"),
+        ];
+        for (name, stdin, out) in cases {
+            let code = fs::read_to_string(format!("examples/{}/{}.hyeong", name, name)).unwrap();
+            for level in 0..=3 {
+                assert_eq!(
+                    (String::from(out), 0),
+                    helper_function(&format!("{}{}", name, level), &code, level, stdin),
+                    "{} at level {}",
+                    name,
+                    level
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wat_test03() {
+        if !has_command("node") {
+            return;
+        }
+        // exit by popping stack 2 in area, before printing
+        for level in 0..=3 {
+            assert_eq!(
+                (String::new(), 1),
+                helper_function(
+                    &format!("exit{}", level),
+                    "형.. 흑.. 형... 하앙..? 형",
+                    level,
+                    ""
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn wat_test04() {
+        if !has_command("node") {
+            return;
+        }
+        // big number, fraction and NaN
+        let cases = [
+            (
+                "혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ \
+                 혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ 혀엉........ \
+                 하아아아아아아아아아아앗... 흣... 항.",
+                "281474976710656",
+            ),
+            ("형... 흡... 흣... 항.", "1/3"),
+            ("형 흡... 항. 항.", "너무 커엇...너무 커엇..."),
+        ];
+        for (i, (code, out)) in cases.iter().enumerate() {
+            for level in [0, 3] {
+                assert_eq!(
+                    (String::from(*out), 0),
+                    helper_function(&format!("num{}{}", i, level), code, level, "")
+                );
+            }
+        }
+    }
+}